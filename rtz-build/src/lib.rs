@@ -40,6 +40,52 @@ fn generate_self_contained_bincodes() {
     generate_osm_admin_bincodes();
 }
 
+/// The most geometry problems that regenerating a dataset may find, from the `RTZ_MAX_GEOMETRY_PROBLEMS`
+/// environment variable (unlimited when it is unset).
+#[cfg(feature = "self-contained")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn get_max_geometry_problems() -> Option<usize> {
+    let value = std::env::var("RTZ_MAX_GEOMETRY_PROBLEMS").ok()?;
+    let max_problems = value.parse().unwrap_or_else(|e| panic!("RTZ_MAX_GEOMETRY_PROBLEMS must be a count, not `{}`: {}", value, e));
+
+    Some(max_problems)
+}
+
+/// Writes the repair report of a regenerated dataset to `OUT_DIR` (as `<items bincode>.repairs.json`),
+/// and surfaces it as a build warning, if ingestion repaired, dropped, or skipped any of its features.
+#[cfg(feature = "self-contained")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn report_geometry_repairs(items_bincode: &std::path::Path, report: rtz_core::base::types::Res<rtz_core::geo::shared::RepairReport>) {
+    let report = report.unwrap_or_else(|e| panic!("Could not generate `{}`: {}", items_bincode.display(), e));
+
+    if report.problem_count() == 0 {
+        return;
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo for build scripts"));
+    let report_path = out_dir.join(format!("{}.repairs.json", items_bincode.file_name().unwrap().to_string_lossy()));
+
+    rtz_core::geo::shared::write_repair_report(&report_path, &report).unwrap_or_else(|e| panic!("Could not write `{}`: {}", report_path.display(), e));
+    println!("cargo::warning=`{}`: {} (see `{}`).", items_bincode.display(), report, report_path.display());
+}
+
+/// Compresses each of a dataset's bincodes next to it (see `rtz_core::geo::shared::compress_asset`),
+/// unless the compressed bincode there is already up to date.
+#[cfg(all(feature = "compressed-assets", feature = "self-contained"))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn compress_bincodes(bincodes: &[&std::path::Path]) {
+    for bincode in bincodes {
+        rtz_core::geo::shared::compress_asset(bincode).unwrap_or_else(|e| panic!("Could not compress `{}`: {}", bincode.display(), e));
+    }
+}
+
+/// Whether each of a dataset's bincodes has a compressed form, even if the bincode itself is absent.
+#[cfg(all(feature = "compressed-assets", feature = "self-contained", not(feature = "force-rebuild")))]
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn has_compressed_bincodes(bincodes: &[&std::path::Path]) -> bool {
+    bincodes.iter().all(|bincode| rtz_core::geo::shared::get_compressed_asset_path(bincode).exists())
+}
+
 #[cfg(all(feature = "tz-ned", feature = "self-contained"))]
 #[cfg_attr(coverage_nightly, coverage(off))]
 fn generate_ned_tz_bincodes() {
@@ -52,15 +98,34 @@ fn generate_ned_tz_bincodes() {
     let timezone_bincode_destination = assets.join(TIMEZONE_BINCODE_DESTINATION_NAME);
     let lookup_bincode_destination = assets.join(LOOKUP_BINCODE_DESTINATION_NAME);
 
+    // The lookup derives entirely from the items, so a missing (e.g., format-bumped) lookup alone
+    // does not need the source data.
     #[cfg(not(feature = "force-rebuild"))]
-    if timezone_bincode_destination.exists() && lookup_bincode_destination.exists() {
+    if timezone_bincode_destination.exists() {
+        if !lookup_bincode_destination.exists() {
+            rtz_core::geo::shared::generate_lookup_bincode::<NedTimezone>(&timezone_bincode_destination, &lookup_bincode_destination);
+        }
+
+        #[cfg(feature = "compressed-assets")]
+        compress_bincodes(&[&timezone_bincode_destination, &lookup_bincode_destination]);
+
+        return;
+    }
+
+    // A crate package may ship only the compressed assets (see `compressed-assets`), which need nothing more.
+    #[cfg(all(feature = "compressed-assets", not(feature = "force-rebuild")))]
+    if has_compressed_bincodes(&[&timezone_bincode_destination, &lookup_bincode_destination]) {
         return;
     }
 
     std::fs::create_dir_all(&assets).unwrap();
 
     let features = get_geojson_features_from_source();
-    generate_bincodes::<NedTimezone>(features, timezone_bincode_destination, lookup_bincode_destination);
+    let report = generate_bincodes::<NedTimezone>(features, &timezone_bincode_destination, &lookup_bincode_destination, get_max_geometry_problems());
+    report_geometry_repairs(&timezone_bincode_destination, report);
+
+    #[cfg(feature = "compressed-assets")]
+    compress_bincodes(&[&timezone_bincode_destination, &lookup_bincode_destination]);
 }
 
 #[cfg(all(feature = "tz-osm", feature = "self-contained"))]
@@ -75,15 +140,34 @@ fn generate_osm_tz_bincodes() {
     let timezone_bincode_destination = assets.join(TIMEZONE_BINCODE_DESTINATION_NAME);
     let lookup_bincode_destination = assets.join(LOOKUP_BINCODE_DESTINATION_NAME);
 
+    // The lookup derives entirely from the items, so a missing (e.g., format-bumped) lookup alone
+    // does not need the source data.
     #[cfg(not(feature = "force-rebuild"))]
-    if timezone_bincode_destination.exists() && lookup_bincode_destination.exists() {
+    if timezone_bincode_destination.exists() {
+        if !lookup_bincode_destination.exists() {
+            rtz_core::geo::shared::generate_lookup_bincode::<OsmTimezone>(&timezone_bincode_destination, &lookup_bincode_destination);
+        }
+
+        #[cfg(feature = "compressed-assets")]
+        compress_bincodes(&[&timezone_bincode_destination, &lookup_bincode_destination]);
+
+        return;
+    }
+
+    // A crate package may ship only the compressed assets (see `compressed-assets`), which need nothing more.
+    #[cfg(all(feature = "compressed-assets", not(feature = "force-rebuild")))]
+    if has_compressed_bincodes(&[&timezone_bincode_destination, &lookup_bincode_destination]) {
         return;
     }
 
     std::fs::create_dir_all(&assets).unwrap();
 
     let features = get_geojson_features_from_source();
-    generate_bincodes::<OsmTimezone>(features, timezone_bincode_destination, lookup_bincode_destination);
+    let report = generate_bincodes::<OsmTimezone>(features, &timezone_bincode_destination, &lookup_bincode_destination, get_max_geometry_problems());
+    report_geometry_repairs(&timezone_bincode_destination, report);
+
+    #[cfg(feature = "compressed-assets")]
+    compress_bincodes(&[&timezone_bincode_destination, &lookup_bincode_destination]);
 }
 
 #[cfg(all(feature = "admin-osm", feature = "self-contained"))]
@@ -98,13 +182,32 @@ fn generate_osm_admin_bincodes() {
     let admin_bincode_destination = assets.join(ADMIN_BINCODE_DESTINATION_NAME);
     let lookup_bincode_destination = assets.join(LOOKUP_BINCODE_DESTINATION_NAME);
 
+    // The lookup derives entirely from the items, so a missing (e.g., format-bumped) lookup alone
+    // does not need the source data.
     #[cfg(not(feature = "force-rebuild"))]
-    if admin_bincode_destination.exists() && lookup_bincode_destination.exists() {
+    if admin_bincode_destination.exists() {
+        if !lookup_bincode_destination.exists() {
+            rtz_core::geo::shared::generate_lookup_bincode::<OsmAdmin>(&admin_bincode_destination, &lookup_bincode_destination);
+        }
+
+        #[cfg(feature = "compressed-assets")]
+        compress_bincodes(&[&admin_bincode_destination, &lookup_bincode_destination]);
+
+        return;
+    }
+
+    // A crate package may ship only the compressed assets (see `compressed-assets`), which need nothing more.
+    #[cfg(all(feature = "compressed-assets", not(feature = "force-rebuild")))]
+    if has_compressed_bincodes(&[&admin_bincode_destination, &lookup_bincode_destination]) {
         return;
     }
 
     std::fs::create_dir_all(&assets).unwrap();
 
     let features = get_geojson_features_from_source();
-    generate_bincodes::<OsmAdmin>(features, admin_bincode_destination, lookup_bincode_destination);
+    let report = generate_bincodes::<OsmAdmin>(features, &admin_bincode_destination, &lookup_bincode_destination, get_max_geometry_problems());
+    report_geometry_repairs(&admin_bincode_destination, report);
+
+    #[cfg(feature = "compressed-assets")]
+    compress_bincodes(&[&admin_bincode_destination, &lookup_bincode_destination]);
 }
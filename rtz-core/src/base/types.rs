@@ -1,5 +1,9 @@
 //! All of the types used in the library.
 
+use std::fmt::{Display, Formatter};
+
+use crate::geo::shared::{RoundDegree, RoundLngLat};
+
 // Result types.
 
 /// A shortened version of [`anyhow::Result<T>`].
@@ -19,3 +23,160 @@ pub type Float = f64;
 #[cfg(not(feature = "double-precision"))]
 pub type Float = f32;
 
+// Coordinate types.
+
+/// A validated longitude and latitude.
+///
+/// Longitudes wrap into `[-180, 180)`, so `540` and `180` both name the antimeridian at `-180`.
+/// Latitudes must lie in `[-90, 90]` (see [`LngLat::clamped`] to clamp them instead), and at the
+/// poles, where every longitude names the same point, the longitude is normalized to `0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LngLat {
+    lng: Float,
+    lat: Float,
+}
+
+impl LngLat {
+    /// Validates a longitude and latitude, wrapping the longitude and rejecting a latitude outside of `[-90, 90]`.
+    pub fn new(lng: Float, lat: Float) -> Result<Self, CoordinateError> {
+        if !lng.is_finite() || !lat.is_finite() {
+            return Err(CoordinateError::NotFinite { lng, lat });
+        }
+
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(CoordinateError::LatitudeOutOfRange { lat });
+        }
+
+        // At the poles, every longitude is the same point.
+        let lng = if lat.abs() == 90.0 { 0.0 } else { wrap_longitude(lng) };
+
+        Ok(Self { lng, lat })
+    }
+
+    /// Validates a longitude and latitude, like [`LngLat::new`], but clamps the latitude into `[-90, 90]`.
+    pub fn clamped(lng: Float, lat: Float) -> Result<Self, CoordinateError> {
+        if lat.is_nan() {
+            return Err(CoordinateError::NotFinite { lng, lat });
+        }
+
+        Self::new(lng, lat.clamp(-90.0, 90.0))
+    }
+
+    /// The longitude, in `[-180, 180)`.
+    pub fn lng(&self) -> Float {
+        self.lng
+    }
+
+    /// The latitude, in `[-90, 90]`.
+    pub fn lat(&self) -> Float {
+        self.lat
+    }
+
+    /// The 1x1 degree lookup cell containing the point.
+    ///
+    /// The north pole belongs to the topmost row of cells (`89`), which would otherwise be the only
+    /// latitude to fall off the grid.
+    pub fn cell(&self) -> RoundLngLat {
+        (self.lng.floor() as RoundDegree, (self.lat.floor() as RoundDegree).min(89))
+    }
+}
+
+impl TryFrom<(Float, Float)> for LngLat {
+    type Error = CoordinateError;
+
+    fn try_from((lng, lat): (Float, Float)) -> Result<Self, Self::Error> {
+        Self::new(lng, lat)
+    }
+}
+
+/// Wraps a longitude into `[-180, 180)`.
+fn wrap_longitude(lng: Float) -> Float {
+    // Leave in-range longitudes untouched, since the round trip through `rem_euclid` can lose precision.
+    if (-180.0..180.0).contains(&lng) {
+        return lng;
+    }
+
+    let wrapped = (lng + 180.0).rem_euclid(360.0) - 180.0;
+
+    // `rem_euclid` can round up to exactly `360` for inputs just below a multiple of it.
+    if wrapped >= 180.0 {
+        -180.0
+    } else {
+        wrapped
+    }
+}
+
+/// The error returned when a longitude and latitude do not name a point on the globe.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordinateError {
+    /// The longitude or latitude is `NaN` or infinite.
+    NotFinite {
+        /// The given longitude.
+        lng: Float,
+        /// The given latitude.
+        lat: Float,
+    },
+    /// The latitude is outside of `[-90, 90]`.
+    LatitudeOutOfRange {
+        /// The given latitude.
+        lat: Float,
+    },
+}
+
+impl CoordinateError {
+    /// A stable, machine-readable code for the error (e.g., for API responses).
+    pub fn code(&self) -> &'static str {
+        match self {
+            CoordinateError::NotFinite { .. } => "coordinate_not_finite",
+            CoordinateError::LatitudeOutOfRange { .. } => "latitude_out_of_range",
+        }
+    }
+}
+
+impl Display for CoordinateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoordinateError::NotFinite { lng, lat } => write!(f, "The coordinate `({}, {})` is not finite.", lng, lat),
+            CoordinateError::LatitudeOutOfRange { lat } => write!(f, "The latitude `{}` is outside of `[-90, 90]`.", lat),
+        }
+    }
+}
+
+impl std::error::Error for CoordinateError {}
+
+// Tests.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn longitudes_wrap_onto_the_grid() {
+        assert_eq!(LngLat::new(-121.0, 46.0).unwrap().lng(), -121.0);
+        assert_eq!(LngLat::new(540.0, 0.0).unwrap().lng(), -180.0);
+        assert_eq!(LngLat::new(180.0, 0.0).unwrap().cell(), (-180, 0));
+        assert_eq!(LngLat::new(-181.0, 0.0).unwrap().lng(), 179.0);
+        assert_eq!(LngLat::new(359.5, 0.0).unwrap().lng(), -0.5);
+        assert_eq!(LngLat::new(-180.0, 0.0).unwrap().lng(), -180.0);
+    }
+
+    #[test]
+    fn poles_have_one_longitude_and_stay_on_the_grid() {
+        assert_eq!(LngLat::new(123.0, 90.0).unwrap(), LngLat::new(-45.0, 90.0).unwrap());
+        assert_eq!(LngLat::new(123.0, 90.0).unwrap().cell(), (0, 89));
+        assert_eq!(LngLat::new(123.0, -90.0).unwrap().cell(), (0, -90));
+    }
+
+    #[test]
+    fn invalid_coordinates_are_errors() {
+        assert_eq!(LngLat::new(0.0, 95.0), Err(CoordinateError::LatitudeOutOfRange { lat: 95.0 }));
+        assert_eq!(LngLat::new(Float::INFINITY, 0.0).unwrap_err().code(), "coordinate_not_finite");
+        assert!(LngLat::new(Float::NAN, 0.0).is_err());
+        assert!(LngLat::try_from((0.0, Float::NAN)).is_err());
+
+        assert_eq!(LngLat::clamped(10.0, 95.0).unwrap(), LngLat::new(0.0, 90.0).unwrap());
+        assert_eq!(LngLat::clamped(10.0, Float::NEG_INFINITY).unwrap().lat(), -90.0);
+        assert!(LngLat::clamped(Float::NAN, 0.0).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! All of the geo-specific functions for OSM admin lookups.
 
 use geo::Geometry;
+use geojson::Feature;
 use serde_json::{Map, Value};
 use std::borrow::Cow;
 
@@ -13,7 +14,10 @@ use bincode::{
 
 use crate::{
     base::types::Float,
-    geo::shared::{simplify_geometry, ConcreteVec, EncodableGeometry, EncodableString, HasGeometry, HasProperties, IdFeaturePair},
+    geo::shared::{
+        get_feature_properties, get_str_property, get_u64_property, try_from_id_feature_pair, ConcreteVec, EncodableGeometry, EncodableString, HasGeometry, HasProperties, IdFeaturePair,
+        IngestionError, TryFromFeature,
+    },
 };
 
 // Source ingestion is native-only (it reads GeoJSON off disk), so its imports carry the same
@@ -73,6 +77,8 @@ pub fn get_geojson_features_from_source() -> geojson::FeatureCollection {
     }
 }
 
+/// The upstream source of the admin GeoJSON, which `cargo xtask extract-admin` extracts with `osm_extract_polygon`.
+pub static SOURCE: &str = "https://planet.openstreetmap.org/pbf/planet-latest.osm.pbf";
 /// The name of the timezone bincode file.
 pub static ADMIN_BINCODE_DESTINATION_NAME: &str = "osm_admins.bincode";
 /// The name of the cache bincode file.
@@ -145,22 +151,27 @@ impl PartialEq for OsmAdmin {
     }
 }
 
-impl From<IdFeaturePair> for OsmAdmin {
-    fn from(value: IdFeaturePair) -> OsmAdmin {
-        let id = value.0;
-        let properties = value.1.properties.as_ref().unwrap();
-        let geometry = value.1.geometry.as_ref().unwrap();
+impl TryFromFeature for OsmAdmin {
+    fn try_from_feature(id: usize, feature: &Feature, geometry: Geometry<Float>) -> Result<OsmAdmin, IngestionError> {
+        let properties = get_feature_properties(feature)?;
 
-        // Read defensively: a way-backed boundary can lack `relation_id`, and a single missing
-        // value would otherwise panic the whole regen. `0` marks "unknown" (no OSM id is ever 0).
+        // Read defensively: a way-backed boundary can lack `relation_id`, which is no reason to skip
+        // it. `0` marks "unknown" (no OSM id is ever 0).
         let relation_id = properties.get("relation_id").and_then(|v| v.as_u64()).unwrap_or(0);
-        let name = EncodableString(Cow::Owned(properties.get("name").unwrap().as_str().unwrap().to_string()));
-        let level = properties.get("admin_level").unwrap().as_u64().unwrap() as usize;
+        let name = EncodableString(Cow::Owned(get_str_property(properties, "name")?.to_string()));
+        let level = get_u64_property(properties, "admin_level")? as usize;
 
-        let geometry: Geometry<Float> = geometry.value.clone().try_into().unwrap();
-        let geometry = EncodableGeometry(simplify_geometry(geometry, SIMPLIFICATION_EPSILON));
+        let geometry = EncodableGeometry::new(geometry);
 
-        OsmAdmin { id, relation_id, name, level, geometry }
+        Ok(OsmAdmin { id, relation_id, name, level, geometry })
+    }
+}
+
+impl TryFrom<IdFeaturePair> for OsmAdmin {
+    type Error = IngestionError;
+
+    fn try_from(value: IdFeaturePair) -> Result<OsmAdmin, IngestionError> {
+        try_from_id_feature_pair(value)
     }
 }
 
@@ -175,10 +186,22 @@ impl HasGeometry for OsmAdmin {
         self.id
     }
 
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+
     fn geometry(&self) -> &Geometry<Float> {
         &self.geometry.0
     }
 
+    fn simplification_epsilon() -> Float {
+        SIMPLIFICATION_EPSILON
+    }
+
+    fn source() -> &'static str {
+        SOURCE
+    }
+
     /// Order admins broadest-first, so a lookup returns the containment hierarchy in reading
     /// order (country, then state, then county, then city) rather than in source-file order.
     ///
@@ -0,0 +1,404 @@
+//! All of the geo-specific functions for custom datasets: any GeoJSON [`geojson::FeatureCollection`]
+//! of (multi) polygons (e.g., sales regions or service areas), whose properties are read through
+//! a [`CustomFeatureMapping`].
+
+use std::borrow::Cow;
+
+use geo::Geometry;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[cfg(feature = "self-contained")]
+use bincode::{
+    de::{BorrowDecoder, Decoder},
+    error::DecodeError,
+    BorrowDecode, Decode, Encode,
+};
+#[cfg(feature = "self-contained")]
+use std::path::Path;
+
+#[cfg(feature = "self-contained")]
+use crate::base::types::Res;
+
+use crate::{
+    base::types::Float,
+    geo::shared::{
+        get_feature_geometry, get_repaired_items_with, try_from_id_feature_pair, ConcreteVec, EncodableGeometry, EncodableOptionString, EncodableString, HasGeometry, HasProperties, IdFeaturePair,
+        IngestionError, RepairReport, TryFromFeature,
+    },
+};
+
+#[cfg(feature = "self-contained")]
+use crate::geo::shared::{generate_lookup_bincode, write_asset, AssetKind, DatasetInfo};
+
+// Types.
+
+/// Which properties of a GeoJSON feature a [`CustomFeature`] is read from.
+///
+/// The default reads the key from the feature's `id` member, the name from its `name` property,
+/// and keeps every property.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomFeatureMapping {
+    /// The property that uniquely identifies a feature (e.g., `region_id`), or `None` to use the
+    /// feature's `id` member.
+    #[serde(default)]
+    pub key: Option<String>,
+    /// The property that holds a feature's display name, or `None` to use `name`.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The properties to keep on each feature, or `None` to keep all of them.
+    #[serde(default)]
+    pub properties: Option<Vec<String>>,
+}
+
+impl CustomFeatureMapping {
+    /// Read the key from the `key` property of each feature.
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Read the name from the `name` property of each feature.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Keep only the `properties` of each feature.
+    pub fn with_properties(mut self, properties: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.properties = Some(properties.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Read a [`CustomFeature`] from a GeoJSON feature, which fails if the feature has no geometry, or
+    /// a malformed one (see [`get_custom_items_from_features`], which skips those that are not polygonal).
+    pub fn read_feature(&self, id: usize, feature: &geojson::Feature) -> Result<CustomFeature, IngestionError> {
+        let geometry = get_feature_geometry(feature)?;
+
+        Ok(self.read_feature_with_geometry(id, feature, geometry))
+    }
+
+    /// Read a [`CustomFeature`] from the properties of a GeoJSON feature, with `geometry` as its geometry.
+    pub fn read_feature_with_geometry(&self, id: usize, feature: &geojson::Feature, geometry: Geometry<Float>) -> CustomFeature {
+        let empty = Map::new();
+        let properties = feature.properties.as_ref().unwrap_or(&empty);
+
+        let key = match &self.key {
+            Some(key) => properties.get(key).and_then(get_property_string),
+            None => feature.id.as_ref().map(|id| match id {
+                geojson::feature::Id::String(id) => id.clone(),
+                geojson::feature::Id::Number(id) => id.to_string(),
+            }),
+        };
+        let name = properties.get(self.name.as_deref().unwrap_or("name")).and_then(get_property_string);
+
+        let properties = properties
+            .iter()
+            .filter(|(property, _)| self.properties.as_ref().is_none_or(|kept| kept.contains(property)))
+            .map(|(property, value)| (EncodableString(Cow::Owned(property.clone())), EncodableString(Cow::Owned(value.to_string()))))
+            .collect();
+
+        CustomFeature {
+            id,
+            key: EncodableOptionString(key.map(Cow::Owned)),
+            name: EncodableOptionString(name.map(Cow::Owned)),
+            properties,
+            geometry: EncodableGeometry::new(geometry),
+        }
+    }
+}
+
+/// A feature of a custom dataset (see [`CustomFeatureMapping`]).
+///
+/// Its geometry is kept as it is in the source, once repaired (see [`get_custom_items_from_features`]), so
+/// simplify it beforehand (see [`simplify_geometries`](crate::geo::shared::simplify_geometries)) if it is detailed.
+#[derive(Debug)]
+#[cfg_attr(feature = "self-contained", derive(Encode))]
+pub struct CustomFeature {
+    /// The index of the [`CustomFeature`] in its dataset.
+    ///
+    /// This is not stable across builds or new data sets.  It is merely unique during a single build.
+    pub id: usize,
+
+    /// The value of the key property (see [`CustomFeatureMapping::key`]), if the feature has one.
+    ///
+    /// Unlike [`CustomFeature::id`], this is as stable as the source data.
+    pub key: EncodableOptionString,
+    /// The value of the name property (see [`CustomFeatureMapping::name`]), if the feature has one.
+    pub name: EncodableOptionString,
+
+    /// The kept properties of the [`CustomFeature`] (see [`CustomFeatureMapping::properties`]),
+    /// with their values as JSON.
+    pub properties: Vec<(EncodableString, EncodableString)>,
+
+    /// The geometry of the [`CustomFeature`].
+    pub geometry: EncodableGeometry,
+}
+
+#[cfg(feature = "self-contained")]
+impl<Context> Decode<Context> for CustomFeature {
+    fn decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
+    where
+        D: Decoder<Context = Context>,
+    {
+        let id = usize::decode(decoder)?;
+        let key = EncodableOptionString::decode(decoder)?;
+        let name = EncodableOptionString::decode(decoder)?;
+        let properties = Vec::decode(decoder)?;
+        let geometry = EncodableGeometry::decode(decoder)?;
+
+        Ok(CustomFeature { id, key, name, properties, geometry })
+    }
+}
+
+#[cfg(feature = "self-contained")]
+impl<'de, Context> BorrowDecode<'de, Context> for CustomFeature
+where
+    'de: 'static,
+{
+    fn borrow_decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
+    where
+        D: BorrowDecoder<'de, Context = Context>,
+    {
+        let id = usize::decode(decoder)?;
+        let key = EncodableOptionString::borrow_decode(decoder)?;
+        let name = EncodableOptionString::borrow_decode(decoder)?;
+        let properties = Vec::borrow_decode(decoder)?;
+        let geometry = EncodableGeometry::borrow_decode(decoder)?;
+
+        Ok(CustomFeature { id, key, name, properties, geometry })
+    }
+}
+
+impl PartialEq for CustomFeature {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+/// Reads the feature with the default [`CustomFeatureMapping`].
+impl TryFromFeature for CustomFeature {
+    fn try_from_feature(id: usize, feature: &geojson::Feature, geometry: Geometry<Float>) -> Result<CustomFeature, IngestionError> {
+        Ok(CustomFeatureMapping::default().read_feature_with_geometry(id, feature, geometry))
+    }
+}
+
+/// Reads the feature with the default [`CustomFeatureMapping`].
+impl TryFrom<IdFeaturePair> for CustomFeature {
+    type Error = IngestionError;
+
+    fn try_from(value: IdFeaturePair) -> Result<CustomFeature, IngestionError> {
+        try_from_id_feature_pair(value)
+    }
+}
+
+impl HasGeometry for CustomFeature {
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+
+    fn geometry(&self) -> &Geometry<Float> {
+        &self.geometry.0
+    }
+}
+
+impl HasProperties for CustomFeature {
+    fn properties(&self) -> Map<String, Value> {
+        self.properties
+            .iter()
+            .map(|(property, value)| (property.to_string(), serde_json::from_str(value).unwrap_or(Value::Null)))
+            .collect()
+    }
+}
+
+// Helpers.
+
+/// Get the concrete custom features from features, read through `mapping`, along with a report of every
+/// feature whose geometry ingestion repaired or dropped, or that it skipped (see [`get_repaired_items_with`]).
+///
+/// Features without a polygonal geometry (e.g., points, or `null`) cannot contain a point, so
+/// they are left out (and out of the report), and the rest are numbered in source order.
+pub fn get_custom_items_from_features(features: geojson::FeatureCollection, mapping: &CustomFeatureMapping) -> (ConcreteVec<CustomFeature>, RepairReport) {
+    let feature_count = features.features.len();
+    let (source_indices, features): (Vec<_>, Vec<_>) = features.features.into_iter().enumerate().filter(|(_, feature)| is_polygonal(feature)).unzip();
+    let features = geojson::FeatureCollection {
+        features,
+        bbox: None,
+        foreign_members: None,
+    };
+
+    let (items, mut report) = get_repaired_items_with(features, CustomFeature::simplification_epsilon(), |id, feature, geometry| {
+        Ok(mapping.read_feature_with_geometry(id, feature, geometry))
+    });
+
+    // The report indexes the polygonal features, so it is mapped back to the source.
+    report.feature_count = feature_count;
+    report.features.iter_mut().for_each(|repaired| repaired.feature = source_indices[repaired.feature]);
+    report.skipped.iter_mut().for_each(|diagnostic| diagnostic.feature = source_indices[diagnostic.feature]);
+
+    (items, report)
+}
+
+/// Generates bincodes for a custom dataset and its lookup cache from the GeoJSON (e.g., from a
+/// build script), which load with `GeoIndex::from_bytes` or `GeoIndex::from_files` in `rtz`, and
+/// returns the report of the features that ingestion repaired, dropped, or skipped.
+///
+/// Fails, without writing either bincode, if the geometries have more than `max_problems` problems.
+#[cfg(feature = "self-contained")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn generate_custom_bincodes(
+    geojson_features: geojson::FeatureCollection,
+    mapping: &CustomFeatureMapping,
+    items_bincode_destination: impl AsRef<Path>,
+    lookup_bincode_destination: impl AsRef<Path>,
+    max_problems: Option<usize>,
+) -> Res<RepairReport> {
+    let (items, report) = get_custom_items_from_features(geojson_features, mapping);
+
+    if let Some(max_problems) = max_problems.filter(|&max_problems| report.problem_count() > max_problems) {
+        anyhow::bail!("Found {report}, which is more than the {max_problems} allowed.");
+    }
+
+    let info = DatasetInfo::new::<CustomFeature>(items.len());
+
+    write_asset(items_bincode_destination.as_ref(), AssetKind::Items, info, items);
+    generate_lookup_bincode::<CustomFeature>(items_bincode_destination, lookup_bincode_destination);
+
+    Ok(report)
+}
+
+/// Whether a feature has a geometry that can contain a point.
+fn is_polygonal(feature: &geojson::Feature) -> bool {
+    matches!(
+        feature.geometry.as_ref().map(|geometry| &geometry.value),
+        Some(geojson::GeometryValue::Polygon { .. } | geojson::GeometryValue::MultiPolygon { .. })
+    )
+}
+
+/// Get a property as a string: strings as they are, and other (non-`null`) values as JSON.
+fn get_property_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(value) => Some(value.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+// Tests.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn get_test_features() -> geojson::FeatureCollection {
+        let json = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                { "type": "Feature", "id": "west", "properties": { "region_id": 7, "label": "West", "manager": "Ana", "target": 1.5 },
+                  "geometry": { "type": "Polygon", "coordinates": [[[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]]] } },
+                { "type": "Feature", "properties": { "label": "Depot" }, "geometry": { "type": "Point", "coordinates": [5, 5] } },
+                { "type": "Feature", "properties": null,
+                  "geometry": { "type": "MultiPolygon", "coordinates": [[[[20, 0], [30, 0], [30, 10], [20, 10], [20, 0]]]] } }
+            ]
+        }"#;
+
+        crate::geo::shared::get_geojson_features_from_string(json)
+    }
+
+    #[test]
+    fn can_read_features_with_the_default_mapping() {
+        let (items, report) = get_custom_items_from_features(get_test_features(), &CustomFeatureMapping::default());
+
+        // The point is skipped, and the rest are renumbered.
+        assert_eq!(items.len(), 2);
+        assert_eq!(items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![0, 1]);
+
+        assert_eq!(items[0].key.as_deref(), Some("west"));
+        assert_eq!(items[0].name.as_deref(), None);
+        assert_eq!(items[0].properties().len(), 4);
+        assert_eq!(items[0].properties()["target"], 1.5);
+
+        assert_eq!(items[1].key.as_deref(), None);
+        assert!(items[1].properties().is_empty());
+
+        // The polygons are valid, so nothing is reported.
+        assert_eq!(report.problem_count(), 0);
+        assert_eq!(report.feature_count, 3);
+    }
+
+    #[test]
+    fn can_read_features_with_a_mapping() {
+        let mapping = CustomFeatureMapping::default().with_key("region_id").with_name("label").with_properties(["manager"]);
+        let (items, _) = get_custom_items_from_features(get_test_features(), &mapping);
+
+        assert_eq!(items[0].key.as_deref(), Some("7"));
+        assert_eq!(items[0].name.as_deref(), Some("West"));
+        assert_eq!(items[0].properties(), Map::from_iter([("manager".to_string(), Value::from("Ana"))]));
+    }
+
+    #[test]
+    fn features_are_repaired_and_reported_by_source_index() {
+        let mut features = get_test_features();
+        features.features.push(crate::geo::shared::get_geojson_feature_from_string(
+            r#"{ "type": "Feature", "properties": { "label": "Bowtie" },
+                 "geometry": { "type": "Polygon", "coordinates": [[[40, 0], [42, 2], [42, 0], [40, 2], [40, 0]]] } }"#,
+        ));
+        features.features.push(crate::geo::shared::get_geojson_feature_from_string(
+            r#"{ "type": "Feature", "properties": { "label": "Collapsed" },
+                 "geometry": { "type": "Polygon", "coordinates": [[[50, 0], [51, 1], [52, 2], [50, 0]]] } }"#,
+        ));
+
+        let (items, report) = get_custom_items_from_features(features, &CustomFeatureMapping::default().with_name("label"));
+
+        // The collapsed polygon is dropped, and the bowtie rebuilt.
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[2].name.as_deref(), Some("Bowtie"));
+        assert!(matches!(items[2].geometry(), Geometry::MultiPolygon(polygons) if polygons.0.len() == 2));
+
+        assert_eq!(
+            report.features.iter().map(|repaired| (repaired.feature, repaired.dropped)).collect::<Vec<_>>(),
+            vec![(3, false), (4, true)]
+        );
+        assert_eq!(report.feature_count, 5);
+    }
+
+    #[test]
+    fn features_without_a_geometry_are_an_error() {
+        let feature = geojson::Feature {
+            properties: Some(Map::from_iter([("name".to_string(), Value::from("Nowhere"))])),
+            ..Default::default()
+        };
+
+        assert_eq!(CustomFeatureMapping::default().read_feature(0, &feature).unwrap_err(), IngestionError::MissingGeometry);
+
+        let mut features = get_test_features();
+        features.features.insert(0, feature);
+        let (items, report) = crate::geo::shared::get_repaired_items_from_features::<CustomFeature>(features);
+
+        // The point is kept, since only `get_custom_items_from_features` requires polygons.
+        assert_eq!(items.len(), 3);
+        assert_eq!(report.skipped.iter().map(|diagnostic| diagnostic.feature).collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    #[cfg(feature = "self-contained")]
+    fn custom_features_roundtrip() {
+        use crate::geo::shared::get_global_bincode_config;
+
+        let mapping = CustomFeatureMapping::default().with_name("label");
+        let (items, _) = get_custom_items_from_features(get_test_features(), &mapping);
+
+        let bytes = bincode::encode_to_vec(&items, get_global_bincode_config()).unwrap();
+        let (decoded, _): (ConcreteVec<CustomFeature>, _) = bincode::decode_from_slice(&bytes, get_global_bincode_config()).unwrap();
+
+        assert_eq!(decoded.len(), items.len());
+        assert_eq!(decoded[0].name.as_deref(), Some("West"));
+        assert_eq!(decoded[0].properties(), items[0].properties());
+        assert_eq!(decoded[1].geometry(), items[1].geometry());
+    }
+}
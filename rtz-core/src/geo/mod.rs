@@ -3,3 +3,6 @@
 pub mod admin;
 pub mod shared;
 pub mod tz;
+
+#[cfg(feature = "custom")]
+pub mod custom;
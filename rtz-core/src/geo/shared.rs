@@ -3,19 +3,20 @@
 use core::str;
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fmt::{Display, Formatter},
     ops::Deref,
 };
 
-use chashmap::CHashMap;
-use geo::{Coord, Geometry, Intersects, Rect, SimplifyVw};
-// These types are named only in the `self-contained` codec helpers; `simplify_geometry` uses them
-// via `Geometry::` variants, which don't need the imports. Gating them keeps the default build warning-free.
-#[cfg(feature = "self-contained")]
-use geo::{LineString, MultiPolygon, Polygon};
+use geo::{
+    line_intersection::line_intersection,
+    orient::Direction,
+    sweep::{Cross, Intersections},
+    BoundingRect, Closest, Contains, Coord, CoordsIter, Distance, Geodesic, Geometry, Haversine, HaversineClosestPoint, Intersects, Line, LineIntersection, LineString, MakeValid, MultiPolygon,
+    Orient, Point, Polygon, Rect, RemoveRepeatedPoints, SimplifyVw,
+};
 use geojson::{Feature, FeatureCollection, GeoJson};
-use rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::prelude::{IndexedParallelIterator, IntoParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
 use serde_json::{Map, Value};
 use std::path::Path;
 
@@ -28,6 +29,12 @@ use bincode::{
     BorrowDecode, Decode, Encode,
 };
 
+#[cfg(feature = "self-contained")]
+use std::mem::ManuallyDrop;
+
+#[cfg(feature = "self-contained")]
+use crate::base::types::{Res, Void};
+
 use crate::base::types::Float;
 
 // Types.
@@ -41,6 +48,26 @@ pub type RoundLngLat = (RoundDegree, RoundDegree);
 /// An `(id, Feature)` pair.
 pub type IdFeaturePair = (usize, geojson::Feature);
 
+// Constants.
+
+/// The count of partial (i.e., not covering) candidates at or below which a lookup cell's quadtree stops subdividing.
+pub const LOOKUP_LEAF_CAPACITY: usize = 4;
+/// The maximum depth of a lookup cell's quadtree.  A leaf at depth `n` spans `1 / 2^n` degrees.
+pub const LOOKUP_MAX_DEPTH: u32 = 5;
+/// The margin (in degrees) by which a lookup leaf is grown before its candidates are clipped to it.
+///
+/// Far larger than a [`Float`] ulp, so that every point of the leaf is well inside the clip.
+pub const LOOKUP_FRAGMENT_MARGIN: Float = 1.0 / 1024.0;
+/// The number of 1x1 degree cells in the lookup cache, which covers the whole globe.
+pub const LOOKUP_CELL_COUNT: usize = 360 * 180;
+/// The fragment id of a candidate that covers its whole leaf, and so needs no `contains` check.
+pub const NO_FRAGMENT: Id = Id::MAX;
+/// A lower bound on the length, in meters, of a degree of latitude (or of longitude at the equator)
+/// anywhere on the WGS84 ellipsoid, so that distance-driven cell searches never fall short.
+pub const MIN_METERS_PER_DEGREE: Float = 110_574.0;
+/// A nominal length, in meters, of a degree of latitude (or of longitude at the equator).
+pub const METERS_PER_DEGREE: Float = 111_320.0;
+
 // Concrete helpers.
 
 /// A concrete collection of concrete values.
@@ -64,14 +91,63 @@ impl<T> From<Vec<T>> for ConcreteVec<T> {
     }
 }
 
-impl<T> From<geojson::FeatureCollection> for ConcreteVec<T>
+impl<T> ConcreteVec<T>
+where
+    T: HasGeometry + TryFrom<IdFeaturePair> + Send,
+    T::Error: Into<IngestionError>,
+{
+    /// Ingest features, skipping those that cannot be read rather than failing on the first, and
+    /// return the rest, numbered in source order, along with why each skipped feature was skipped.
+    pub fn from_features(features: FeatureCollection) -> (ConcreteVec<T>, Vec<IngestionDiagnostic>) {
+        let results = features
+            .features
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, feature)| T::try_from((index, feature)).map_err(|error| IngestionDiagnostic { feature: index, error: error.into() }))
+            .collect::<Vec<_>>();
+
+        ConcreteVec::from_results(results)
+    }
+}
+
+impl<T> ConcreteVec<T>
+where
+    T: HasGeometry,
+{
+    /// Keep the values that were read, numbered in source order, and the diagnostics of the rest.
+    fn from_results(results: Vec<Result<T, IngestionDiagnostic>>) -> (ConcreteVec<T>, Vec<IngestionDiagnostic>) {
+        let mut values = Vec::with_capacity(results.len());
+        let mut diagnostics = Vec::new();
+
+        for result in results {
+            match result {
+                Ok(mut value) => {
+                    // `id` is the item's index, so a skipped feature shifts the ones after it.
+                    value.set_id(values.len());
+                    values.push(value);
+                }
+                Err(diagnostic) => diagnostics.push(diagnostic),
+            }
+        }
+
+        (ConcreteVec(values), diagnostics)
+    }
+}
+
+/// Ingests features, failing with the diagnostics of every feature that cannot be read, if any (see
+/// [`ConcreteVec::from_features`] to keep the rest instead).
+impl<T> TryFrom<geojson::FeatureCollection> for ConcreteVec<T>
 where
-    T: From<IdFeaturePair> + Send,
+    T: HasGeometry + TryFrom<IdFeaturePair> + Send,
+    T::Error: Into<IngestionError>,
 {
-    fn from(value: geojson::FeatureCollection) -> ConcreteVec<T> {
-        let values = value.features.into_par_iter().enumerate().map(T::from).collect::<Vec<T>>();
+    type Error = Vec<IngestionDiagnostic>;
 
-        ConcreteVec(values)
+    fn try_from(value: geojson::FeatureCollection) -> Result<ConcreteVec<T>, Vec<IngestionDiagnostic>> {
+        match ConcreteVec::from_features(value) {
+            (values, diagnostics) if diagnostics.is_empty() => Ok(values),
+            (_, diagnostics) => Err(diagnostics),
+        }
     }
 }
 
@@ -225,7 +301,7 @@ impl<Context> Decode<Context> for EncodableOptionString {
 
                 EncodableOptionString(Some(es.0))
             }
-            _ => panic!("Unsupported variant."),
+            found => return Err(unexpected_variant("EncodableOptionString", found)),
         };
 
         Ok(result)
@@ -247,7 +323,7 @@ impl<'de, Context> BorrowDecode<'de, Context> for EncodableOptionString {
 
                 EncodableOptionString(Some(es.0))
             }
-            _ => panic!("Unsupported variant."),
+            found => return Err(unexpected_variant("EncodableOptionString", found)),
         };
 
         Ok(result)
@@ -262,9 +338,30 @@ impl<'de, Context> BorrowDecode<'de, Context> for EncodableOptionString {
 pub trait HasGeometry {
     /// Get the `id` of the [`HasGeometry`].
     fn id(&self) -> usize;
+    /// Set the `id` of the [`HasGeometry`] to its new position in its collection (e.g., when the
+    /// features before it fail to ingest, see [`ConcreteVec::from_features`]).
+    fn set_id(&mut self, id: usize);
     /// Get the [`Geometry`] of the [`HasGeometry`].
     fn geometry(&self) -> &Geometry<Float>;
 
+    /// Get the Visvalingam-Whyatt epsilon (see [`simplify_geometries`]) that the geometries are
+    /// simplified with at build time.  Defaults to `0` (not simplified).
+    fn simplification_epsilon() -> Float
+    where
+        Self: Sized,
+    {
+        0.0
+    }
+
+    /// Describe the upstream source the items are generated from (e.g., a release URL), which is
+    /// recorded in the generated assets (see [`DatasetInfo`]).  Defaults to empty (unknown).
+    fn source() -> &'static str
+    where
+        Self: Sized,
+    {
+        ""
+    }
+
     /// Impose a canonical order on the items at build time.  Defaults to leaving source order
     /// alone; override to make lookup results come back in a meaningful order.
     ///
@@ -280,6 +377,15 @@ pub trait HasGeometry {
     }
 }
 
+/// A trait for types that are read from a GeoJSON feature, given its geometry.
+///
+/// Ingestion converts (and repairs) each geometry itself, so it hands it over, rather than having it
+/// read from the feature again (see [`get_repaired_items_from_features`]).
+pub trait TryFromFeature: Sized {
+    /// Read the item with the `id` from the properties of `feature`, with `geometry` as its geometry.
+    fn try_from_feature(id: usize, feature: &Feature, geometry: Geometry<Float>) -> Result<Self, IngestionError>;
+}
+
 /// A trait for types that have properties.
 pub trait HasProperties {
     /// Get the properties of the [`HasProperties`].
@@ -397,75 +503,801 @@ pub fn simplify_geometry(geometry: Geometry<Float>, simplification_epsilon: Floa
     geometry
 }
 
+/// A vertex, keyed by the bits of its coordinates, so that the vertices that geometries share are matched exactly.
+type VertexKey = (u64, u64);
+
+/// Get the [`VertexKey`] of a vertex.
+fn get_vertex_key(vertex: Coord<Float>) -> VertexKey {
+    // Adding zero folds `-0.0` into `0.0`.
+    ((vertex.x + 0.0).to_bits() as u64, (vertex.y + 0.0).to_bits() as u64)
+}
+
+/// Simplifies [`Geometry`]s together using the [Visvalingam-Whyatt algorithm](https://bost.ocks.org/mike/simplify/),
+/// so that the borders that neighbouring polygons share stay shared.
+///
+/// [`simplify_geometry`] simplifies each polygon on its own, which simplifies a shared border differently
+/// on each side of it, and leaves slivers of gap (points in neither neighbour) and of overlap (points in
+/// both).  Instead, every ring is split into arcs at its junctions (the vertices where the rings that run
+/// along it part ways), and each arc is simplified once, keeping its ends, for every ring that runs along
+/// it.  A ring without junctions (e.g., an island) is one closed arc, which is kept as it is if it would
+/// collapse, and a ring that its simplified arcs would collapse is also kept as it is.
+///
+/// Borders are matched by their vertices, which must be exactly equal on both sides (as they are in
+/// topologically built sources, like Natural Earth and OpenStreetMap).  Geometries other than (multi)
+/// polygons are simplified on their own (see [`simplify_geometry`]).
+pub fn simplify_geometries(geometries: Vec<Geometry<Float>>, simplification_epsilon: Float) -> Vec<Geometry<Float>> {
+    if cfg!(feature = "unsimplified") || simplification_epsilon <= 0.0 {
+        return geometries;
+    }
+
+    let original_rings = geometries.iter().flat_map(get_polygon_rings).collect::<Vec<_>>();
+    let rings = original_rings
+        .iter()
+        .map(|ring| {
+            let mut ring = ring.0.clone();
+            ring.dedup_by_key(|vertex| get_vertex_key(*vertex));
+            ring
+        })
+        .collect::<Vec<_>>();
+    let junctions = get_junctions(&rings);
+
+    // Collect each distinct arc once (in the orientation whose keys sort first), and which arcs, in which
+    // orientation, make up each ring.
+    let mut arc_ids = HashMap::<Vec<VertexKey>, usize>::new();
+    let mut arcs = Vec::<Vec<Coord<Float>>>::new();
+    let mut ring_arcs = Vec::with_capacity(rings.len());
+
+    for ring in &rings {
+        let mut ring_arc_ids = Vec::new();
+
+        for mut arc in split_ring(ring, &junctions) {
+            let mut keys = arc.iter().copied().map(get_vertex_key).collect::<Vec<_>>();
+            let reversed_keys = keys.iter().rev().copied().collect::<Vec<_>>();
+            let reversed = reversed_keys < keys;
+
+            if reversed {
+                keys = reversed_keys;
+                arc.reverse();
+            }
+
+            let id = *arc_ids.entry(keys).or_insert_with(|| {
+                arcs.push(arc);
+                arcs.len() - 1
+            });
+
+            ring_arc_ids.push((id, reversed));
+        }
+
+        ring_arcs.push(ring_arc_ids);
+    }
+
+    let arcs = arcs
+        .into_par_iter()
+        .map(|arc| {
+            let arc = LineString(arc);
+            let simplified = arc.simplify_vw(simplification_epsilon);
+
+            // A closed arc is a whole ring, so it must stay one.
+            if arc.is_closed() && simplified.0.len() < 4 {
+                arc.0
+            } else {
+                simplified.0
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut rings = original_rings.into_iter().zip(ring_arcs).map(|(original, ring_arc_ids)| {
+        let mut ring = Vec::new();
+
+        for (id, reversed) in ring_arc_ids {
+            // Each arc starts where the previous one ends.
+            let skip = usize::from(!ring.is_empty());
+
+            if reversed {
+                ring.extend(arcs[id].iter().rev().skip(skip));
+            } else {
+                ring.extend(arcs[id].iter().skip(skip));
+            }
+        }
+
+        if ring.len() < 4 {
+            original.clone()
+        } else {
+            LineString(ring)
+        }
+    });
+    let mut simplify_polygon = |polygon: &Polygon<Float>| {
+        let exterior = rings.next().unwrap();
+        let interiors = polygon.interiors().iter().map(|_| rings.next().unwrap()).collect();
+
+        Polygon::new(exterior, interiors)
+    };
+
+    geometries
+        .iter()
+        .map(|geometry| match geometry {
+            Geometry::Polygon(polygon) => Geometry::Polygon(simplify_polygon(polygon)),
+            Geometry::MultiPolygon(multi_polygon) => Geometry::MultiPolygon(MultiPolygon::new(multi_polygon.iter().map(&mut simplify_polygon).collect())),
+            geometry => simplify_geometry(geometry.clone(), simplification_epsilon),
+        })
+        .collect()
+}
+
+/// Get the rings of a (multi) polygon, in order: each polygon's exterior, and then its interiors.
+fn get_polygon_rings(geometry: &Geometry<Float>) -> Vec<&LineString<Float>> {
+    let polygons = match geometry {
+        Geometry::Polygon(polygon) => std::slice::from_ref(polygon),
+        Geometry::MultiPolygon(multi_polygon) => multi_polygon.0.as_slice(),
+        _ => &[],
+    };
+
+    polygons.iter().flat_map(|polygon| std::iter::once(polygon.exterior()).chain(polygon.interiors())).collect()
+}
+
+/// Get the junctions of closed rings: the vertices that do not have exactly two neighbours across all of
+/// them, which is where the rings that share a border part ways.
+fn get_junctions(rings: &[Vec<Coord<Float>>]) -> HashSet<VertexKey> {
+    let mut neighbours = HashMap::<VertexKey, Vec<VertexKey>>::new();
+
+    for ring in rings {
+        for edge in ring.windows(2) {
+            let (a, b) = (get_vertex_key(edge[0]), get_vertex_key(edge[1]));
+
+            for (vertex, neighbour) in [(a, b), (b, a)] {
+                let vertex_neighbours = neighbours.entry(vertex).or_default();
+
+                // Three neighbours already make a junction.
+                if vertex_neighbours.len() < 3 && !vertex_neighbours.contains(&neighbour) {
+                    vertex_neighbours.push(neighbour);
+                }
+            }
+        }
+    }
+
+    neighbours.into_iter().filter(|(_, neighbours)| neighbours.len() != 2).map(|(vertex, _)| vertex).collect()
+}
+
+/// Split a closed ring (without repeated vertices) into the arcs between its `junctions`, each of which
+/// starts where the previous one ends.  A ring that is not a ring (i.e., of less than three vertices) has no arcs.
+///
+/// A ring without junctions is a single closed arc from its least vertex, which any ring that runs
+/// along all of it also starts at.
+fn split_ring(ring: &[Coord<Float>], junctions: &HashSet<VertexKey>) -> Vec<Vec<Coord<Float>>> {
+    let vertices = &ring[..ring.len().saturating_sub(1)];
+    let len = vertices.len();
+
+    if len < 3 {
+        return Vec::new();
+    }
+
+    let is_junction = |vertex: Coord<Float>| junctions.contains(&get_vertex_key(vertex));
+    let start = vertices
+        .iter()
+        .position(|&vertex| is_junction(vertex))
+        .unwrap_or_else(|| (0..len).min_by_key(|&k| get_vertex_key(vertices[k])).unwrap_or_default());
+
+    let mut arcs = Vec::new();
+    let mut arc = vec![vertices[start]];
+
+    for k in 1..=len {
+        let vertex = vertices[(start + k) % len];
+        arc.push(vertex);
+
+        if k == len || is_junction(vertex) {
+            arcs.push(std::mem::replace(&mut arc, vec![vertex]));
+        }
+    }
+
+    arcs
+}
+
+/// Get the 1x1 degree [`Rect`] whose south-west corner is `(x, y)`.
+pub fn get_cell_rect(x: RoundDegree, y: RoundDegree) -> Rect<Float> {
+    let xf = x as Float;
+    let yf = y as Float;
+
+    Rect::new(Coord { x: xf, y: yf }, Coord { x: xf + 1.0, y: yf + 1.0 })
+}
+
+/// Get the index of the 1x1 degree cell whose south-west corner is `(x, y)` in the lookup cache's
+/// table, which holds the cells in key order (i.e., by longitude, then by latitude), or `None` if
+/// the cell is off the globe.
+#[inline]
+pub fn get_cell_index((x, y): RoundLngLat) -> Option<usize> {
+    if !(-180..180).contains(&x) || !(-90..90).contains(&y) {
+        return None;
+    }
+
+    Some((x + 180) as usize * 180 + (y + 90) as usize)
+}
+
+/// Get every 1x1 degree cell that may hold a point within `max_distance` meters of `(xf, yf)`.
+///
+/// The search wraps across the antimeridian, and widens to every longitude once it reaches a pole.
+pub fn get_cells_within_distance(xf: Float, yf: Float, max_distance: Float) -> Vec<RoundLngLat> {
+    let reach = max_distance.max(0.0) / MIN_METERS_PER_DEGREE;
+
+    let y0 = (yf - reach).floor().max(-90.0) as i32;
+    let y1 = (yf + reach).floor().min(89.0) as i32;
+
+    // A degree of longitude shrinks with the cosine of the latitude, so widen by the band's most poleward edge.
+    let cos = (yf.abs() + reach).min(90.0).to_radians().cos();
+    let (x0, x1) = if cos > 0.0 && reach / cos < 180.0 {
+        ((xf - reach / cos).floor() as i32, (xf + reach / cos).floor() as i32)
+    } else {
+        (-180, 179)
+    };
+
+    let mut cells = (x0..=x1)
+        .map(|x| ((x + 180).rem_euclid(360) - 180) as RoundDegree)
+        .flat_map(|x| (y0..=y1).map(move |y| (x, y as RoundDegree)))
+        .collect::<Vec<_>>();
+    cells.sort_unstable();
+    cells.dedup();
+
+    cells
+}
+
+/// Get the [`Rect`]s that a `(west, south, east, north)` bounding box covers, where a `west`
+/// greater than `east` crosses the antimeridian (as in GeoJSON) and so splits in two.
+pub fn get_bbox_rects(west: Float, south: Float, east: Float, north: Float) -> Vec<Rect<Float>> {
+    if west <= east {
+        vec![Rect::new(Coord { x: west, y: south }, Coord { x: east, y: north })]
+    } else {
+        vec![
+            Rect::new(Coord { x: west, y: south }, Coord { x: 180.0, y: north }),
+            Rect::new(Coord { x: -180.0, y: south }, Coord { x: east, y: north }),
+        ]
+    }
+}
+
+/// Get every 1x1 degree cell that a [`Rect`] touches, edges included.
+pub fn get_cells_in_rect(rect: Rect<Float>) -> impl Iterator<Item = RoundLngLat> {
+    let x0 = rect.min().x.floor().max(-180.0) as i32;
+    let x1 = rect.max().x.floor().min(179.0) as i32;
+    let y0 = rect.min().y.floor().max(-90.0) as i32;
+    let y1 = rect.max().y.floor().min(89.0) as i32;
+
+    (x0..=x1).flat_map(move |x| (y0..=y1).map(move |y| (x as RoundDegree, y as RoundDegree)))
+}
+
+/// Split a route segment where it crosses the antimeridian.
+///
+/// A segment whose ends are more than 180 degrees of longitude apart is taken to go the short way
+/// round, and so becomes two: one up to the antimeridian on the start's side, and one from it on
+/// the end's side.  Any other segment is returned as is.
+pub fn split_line_at_antimeridian(line: Line<Float>) -> Vec<Line<Float>> {
+    let (start, end) = (line.start, line.end);
+
+    let shift = if end.x - start.x > 180.0 {
+        -360.0
+    } else if start.x - end.x > 180.0 {
+        360.0
+    } else {
+        return vec![line];
+    };
+
+    // Unwrap the end to the start's side, and find where the segment meets the antimeridian there.
+    let side = shift / 2.0;
+    let t = (side - start.x) / (end.x + shift - start.x);
+    let y = start.y + t * (end.y - start.y);
+
+    vec![Line::new(start, Coord { x: side, y }), Line::new(Coord { x: -side, y }, end)]
+}
+
+/// Get the parameters (from `0` at the start to `1` at the end) at which a segment meets the rings
+/// of a (multi) polygon, in no particular order; any other geometry yields none.
+pub fn get_line_crossings(line: Line<Float>, geometry: &Geometry<Float>) -> Vec<Float> {
+    let polygons = match geometry {
+        Geometry::Polygon(polygon) => std::slice::from_ref(polygon),
+        Geometry::MultiPolygon(multi_polygon) => multi_polygon.0.as_slice(),
+        _ => return Vec::new(),
+    };
+
+    let delta = line.delta();
+    let parameter = |c: Coord<Float>| {
+        let t = if delta.x.abs() >= delta.y.abs() {
+            (c.x - line.start.x) / delta.x
+        } else {
+            (c.y - line.start.y) / delta.y
+        };
+
+        t.clamp(0.0, 1.0)
+    };
+
+    let rect = line.bounding_rect();
+
+    polygons
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        .filter(|ring| ring.bounding_rect().is_some_and(|r| r.intersects(&rect)))
+        .flat_map(|ring| ring.lines())
+        .filter_map(|edge| line_intersection(line, edge))
+        .flat_map(|intersection| match intersection {
+            LineIntersection::SinglePoint { intersection, .. } => vec![parameter(intersection)],
+            LineIntersection::Collinear { intersection } => vec![parameter(intersection.start), parameter(intersection.end)],
+        })
+        .collect()
+}
+
+/// Get the geodesic distance, in meters, from `(xf, yf)` to the nearest point on the rings of a
+/// (multi) polygon, or `None` for any other geometry.
+///
+/// The nearest point is found on the sphere, and then measured on the WGS84 ellipsoid.
+pub fn get_distance_to_boundary(geometry: &Geometry<Float>, xf: Float, yf: Float) -> Option<Float> {
+    let polygons = match geometry {
+        Geometry::Polygon(polygon) => std::slice::from_ref(polygon),
+        Geometry::MultiPolygon(multi_polygon) => multi_polygon.0.as_slice(),
+        _ => return None,
+    };
+
+    let from = Point::new(xf, yf);
+    let (nearest, _) = polygons
+        .iter()
+        .flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors()))
+        .flat_map(|ring| ring.lines())
+        .filter_map(|line| match line.haversine_closest_point(&from) {
+            Closest::Intersection(p) | Closest::SinglePoint(p) => Some((p, Haversine.distance(p, from))),
+            Closest::Indeterminate => None,
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    Some(get_geodesic_distance(Coord { x: xf, y: yf }, nearest.0))
+}
+
+/// Get the distance, in meters, from a boundary within which a lookup may disagree with the source
+/// data, for geometries simplified with `simplification_epsilon` (see [`simplify_geometry`]).
+///
+/// This is a heuristic.  Visvalingam-Whyatt drops vertices whose triangles span less than the
+/// epsilon (in square degrees), which moves an edge by roughly its square root (in degrees), and
+/// rounding coordinates to [`Float`] moves it a little further.
+pub fn get_boundary_tolerance(simplification_epsilon: Float) -> Float {
+    // With `unsimplified`, every vertex is kept, and only the rounding remains.
+    let simplification = if cfg!(feature = "unsimplified") { 0.0 } else { simplification_epsilon.sqrt() };
+    let rounding = Float::EPSILON * 180.0;
+
+    (simplification + rounding) * METERS_PER_DEGREE
+}
+
+/// Get the geodesic distance, in meters, between two points on the WGS84 ellipsoid.
+pub fn get_geodesic_distance(a: Coord<Float>, b: Coord<Float>) -> Float {
+    Geodesic.distance(Point::new(a.x as f64, a.y as f64), Point::new(b.x as f64, b.y as f64)) as Float
+}
+
+/// Get quadrant `k` of a [`Rect`], where `k` is `0` (south-west), `1` (south-east), `2` (north-west), or `3` (north-east).
+///
+/// Both the build and the lookup derive child bounds through this, so the midpoints agree bit for bit.
+pub fn get_quadrant_rect(rect: Rect<Float>, k: usize) -> Rect<Float> {
+    let (min, max) = (rect.min(), rect.max());
+    let mid = Coord {
+        x: (min.x + max.x) / 2.0,
+        y: (min.y + max.y) / 2.0,
+    };
+
+    let (x0, x1) = if k & 1 == 0 { (min.x, mid.x) } else { (mid.x, max.x) };
+    let (y0, y1) = if k & 2 == 0 { (min.y, mid.y) } else { (mid.y, max.y) };
+
+    Rect::new(Coord { x: x0, y: y0 }, Coord { x: x1, y: y1 })
+}
+
+/// Get the quadrant of a [`Rect`] (see [`get_quadrant_rect`]) that a point falls in.
+pub fn get_quadrant_index(rect: Rect<Float>, xf: Float, yf: Float) -> usize {
+    let (min, max) = (rect.min(), rect.max());
+    let mid_x = (min.x + max.x) / 2.0;
+    let mid_y = (min.y + max.y) / 2.0;
+
+    (xf >= mid_x) as usize | ((yf >= mid_y) as usize) << 1
+}
+
+/// Whether a geometry fully covers a [`Rect`].
+///
+/// This is stricter than [`geo::Contains`]: the _closed_ rect must lie in the geometry's interior,
+/// so that every point of it (edges and corners included) passes a point `contains` check.
+pub fn geometry_covers_rect(geometry: &Geometry<Float>, rect: Rect<Float>) -> bool {
+    let polygons = match geometry {
+        Geometry::Polygon(polygon) => std::slice::from_ref(polygon),
+        Geometry::MultiPolygon(multi_polygon) => multi_polygon.0.as_slice(),
+        _ => return false,
+    };
+
+    // The rect is connected, so if no ring touches it, it lies either entirely inside or entirely outside.
+    let touches = polygons.iter().flat_map(|p| std::iter::once(p.exterior()).chain(p.interiors())).any(|ring| ring.intersects(&rect));
+
+    !touches && geometry.contains(&rect.center())
+}
+
+/// Grow a lookup node's [`Rect`] by [`LOOKUP_FRAGMENT_MARGIN`] on every side, so that the whole
+/// (closed) node lies strictly inside it.
+pub fn get_fragment_rect(rect: Rect<Float>) -> Rect<Float> {
+    let margin = Coord {
+        x: LOOKUP_FRAGMENT_MARGIN,
+        y: LOOKUP_FRAGMENT_MARGIN,
+    };
+
+    Rect::new(rect.min() - margin, rect.max() + margin)
+}
+
+/// Clip a ring to the near side of an axis-aligned line, where `is_out` tells whether a point is
+/// beyond the line, and `clamp` moves a point onto it.
+///
+/// Each run of points beyond the line collapses to its first and last points, joined via their
+/// clamped copies.  The difference from the original ring is then a loop that never leaves the far
+/// side, so the ring winds around every point strictly on the near side exactly as before.  Returns
+/// `None` if the whole ring is beyond the line, since it then winds around nothing on the near side.
+fn clip_ring_to_half_plane(ring: &[Coord<Float>], is_out: impl Fn(Coord<Float>) -> bool, clamp: impl Fn(Coord<Float>) -> Coord<Float>) -> Option<Vec<Coord<Float>>> {
+    let ring = match ring.split_last() {
+        Some((last, rest)) if rest.first() == Some(last) => rest,
+        _ => ring,
+    };
+
+    // Start on the near side, so that no run wraps around the end.
+    let start = ring.iter().position(|&c| !is_out(c))?;
+
+    let mut result = Vec::with_capacity(ring.len() + 1);
+    let mut run = Vec::new();
+
+    for i in 0..=ring.len() {
+        let c = ring[(start + i) % ring.len()];
+
+        if i < ring.len() && is_out(c) {
+            run.push(c);
+            continue;
+        }
+
+        // Collapsing only pays off past four points.
+        match run.as_slice() {
+            [first, .., last] if run.len() > 4 => result.extend([*first, clamp(*first), clamp(*last), *last]),
+            _ => result.extend_from_slice(&run),
+        }
+        run.clear();
+
+        if i < ring.len() {
+            result.push(c);
+        }
+    }
+
+    result.push(result[0]);
+
+    Some(result)
+}
+
+/// Clip a geometry to a [`Rect`], such that `contains` agrees with the original geometry for every
+/// point strictly inside the rect.
+///
+/// Unlike a textbook (e.g., Sutherland–Hodgman) clip, this never computes new intersection points,
+/// which would be rounded: edges that cross the rect are kept whole, and only the points beyond each
+/// side are collapsed onto it.  So the agreement is exact rather than approximate, at the cost of a
+/// few points just outside the rect.
+pub fn clip_geometry_to_rect(geometry: &Geometry<Float>, rect: Rect<Float>) -> Geometry<Float> {
+    let polygons = match geometry {
+        Geometry::Polygon(polygon) => std::slice::from_ref(polygon),
+        Geometry::MultiPolygon(multi_polygon) => multi_polygon.0.as_slice(),
+        _ => &[],
+    };
+
+    let (min, max) = (rect.min(), rect.max());
+
+    let clip_ring = |ring: &LineString<Float>| {
+        let ring = clip_ring_to_half_plane(&ring.0, |c| c.x > max.x, |c| Coord { x: max.x, ..c })?;
+        let ring = clip_ring_to_half_plane(&ring, |c| c.x < min.x, |c| Coord { x: min.x, ..c })?;
+        let ring = clip_ring_to_half_plane(&ring, |c| c.y > max.y, |c| Coord { y: max.y, ..c })?;
+        let ring = clip_ring_to_half_plane(&ring, |c| c.y < min.y, |c| Coord { y: min.y, ..c })?;
+
+        Some(LineString(ring))
+    };
+
+    let polygons = polygons
+        .iter()
+        .filter_map(|polygon| {
+            let exterior = clip_ring(polygon.exterior())?;
+            let interiors = polygon.interiors().iter().filter_map(clip_ring).collect();
+
+            Some(Polygon::new(exterior, interiors))
+        })
+        .collect();
+
+    Geometry::MultiPolygon(MultiPolygon::new(polygons))
+}
+
+/// A candidate of a lookup node under construction.
+struct NodeCandidate {
+    id: Id,
+    /// The candidate's geometry clipped to the node, or `None` if it covers the node.
+    fragment: Option<Geometry<Float>>,
+}
+
+impl NodeCandidate {
+    /// Build the candidate for `rect`, if `geometry` intersects it.
+    ///
+    /// `geometry` may be the whole item, or its fragment of an enclosing node, which agrees with the
+    /// item everywhere in `rect`.
+    fn new(id: Id, geometry: &Geometry<Float>, rect: Rect<Float>) -> Option<NodeCandidate> {
+        if !geometry.intersects(&rect) {
+            return None;
+        }
+
+        let fragment = (!geometry_covers_rect(geometry, rect)).then(|| clip_geometry_to_rect(geometry, get_fragment_rect(rect)));
+
+        Some(NodeCandidate { id, fragment })
+    }
+
+    /// Narrow this candidate to a quadrant of its node.
+    fn narrow(&self, rect: Rect<Float>) -> Option<NodeCandidate> {
+        match &self.fragment {
+            // Covering a node implies covering every quadrant of it.
+            None => Some(NodeCandidate { id: self.id, fragment: None }),
+            Some(fragment) => NodeCandidate::new(self.id, fragment, rect),
+        }
+    }
+}
+
+/// The leaves of a lookup cell under construction.
+#[derive(Default)]
+struct CellLeaves {
+    ids: Vec<Id>,
+    fragment_ids: Vec<Id>,
+    fragments: Vec<Geometry<Float>>,
+}
+
+impl CellLeaves {
+    /// Append a leaf's candidates, and get the leaf node that refers to them.
+    fn push(&mut self, candidates: Vec<NodeCandidate>) -> LookupNode {
+        let start = self.ids.len() as u32;
+
+        for candidate in candidates {
+            self.ids.push(candidate.id);
+
+            match candidate.fragment {
+                None => self.fragment_ids.push(NO_FRAGMENT),
+                Some(fragment) => {
+                    self.fragment_ids.push(self.fragments.len() as Id);
+                    self.fragments.push(fragment);
+                }
+            }
+        }
+
+        LookupNode {
+            children: 0,
+            start,
+            len: self.ids.len() as u32 - start,
+        }
+    }
+}
+
+/// Recursively subdivide a lookup cell's quadtree node until few of its candidates need a `contains`
+/// check, it reaches [`LOOKUP_MAX_DEPTH`], or it stops improving (e.g., a coastline that runs through every quadrant).
+///
+/// Candidates that cover the node are free at lookup time, so only the partial ones count toward [`LOOKUP_LEAF_CAPACITY`].
+fn subdivide_lookup_node(candidates: Vec<NodeCandidate>, rect: Rect<Float>, depth: u32, index: usize, nodes: &mut Vec<LookupNode>, leaves: &mut CellLeaves) {
+    let partial = |candidates: &[NodeCandidate]| candidates.iter().filter(|c| c.fragment.is_some()).count();
+    let parent_partial = partial(&candidates);
+
+    if parent_partial > LOOKUP_LEAF_CAPACITY && depth < LOOKUP_MAX_DEPTH {
+        let children = [0, 1, 2, 3].map(|k| {
+            let quadrant = get_quadrant_rect(rect, k);
+
+            candidates.iter().filter_map(|c| c.narrow(quadrant)).collect::<Vec<_>>()
+        });
+
+        if children.iter().any(|child| partial(child) < parent_partial) {
+            let first = nodes.len();
+            nodes[index].children = first as u32;
+            nodes.extend([LookupNode::default(); 4]);
+
+            for (k, child) in children.into_iter().enumerate() {
+                subdivide_lookup_node(child, get_quadrant_rect(rect, k), depth + 1, first + k, nodes, leaves);
+            }
+
+            return;
+        }
+    }
+
+    nodes[index] = leaves.push(candidates);
+}
+
 /// Get the cache from the timezones.
-pub fn get_lookup_from_geometries<T>(geometries: &ConcreteVec<T>) -> HashMap<RoundLngLat, EncodableIds>
+pub fn get_lookup_from_geometries<T>(geometries: &ConcreteVec<T>) -> EncodableLookup
 where
     T: HasGeometry + Send + Sync,
 {
-    let map = CHashMap::new();
+    // An indexed parallel collect keeps the cells in key order, so that the fragments are gathered
+    // in an order that does not depend on thread scheduling.
+    let mut cells = (-180..180)
+        .into_par_iter()
+        .flat_map_iter(|x| (-90..90).map(move |y| (x, y)))
+        .map(|(x, y)| {
+            let rect = get_cell_rect(x, y);
 
-    (-180..180).into_par_iter().for_each(|x| {
-        for y in -90..90 {
-            let xf = x as Float;
-            let yf = y as Float;
+            let candidates = geometries.iter().filter_map(|g| NodeCandidate::new(g.id() as Id, g.geometry(), rect)).collect::<Vec<_>>();
 
-            let rect = Rect::new(Coord { x: xf, y: yf }, Coord { x: xf + 1.0, y: yf + 1.0 });
+            CellParts::from_candidates(candidates, rect)
+        })
+        .collect::<Vec<_>>();
 
-            let mut intersected = Vec::new();
+    let mut fragments = Vec::new();
 
-            for g in geometries {
-                if g.geometry().intersects(&rect) {
-                    intersected.push(g.id() as Id);
-                }
-            }
+    for cell in &mut cells {
+        let offset = fragments.len() as Id;
 
-            map.insert((x as RoundDegree, y as RoundDegree), intersected);
+        for fragment_id in cell.leaves.fragment_ids.iter_mut().filter(|id| **id != NO_FRAGMENT) {
+            *fragment_id += offset;
         }
-    });
 
-    let mut cache = HashMap::new();
-    for (key, value) in map.into_iter() {
-        cache.insert(key, EncodableIds(value));
+        fragments.extend(std::mem::take(&mut cell.leaves.fragments).into_iter().map(EncodableGeometry::new));
     }
 
-    cache
+    EncodableLookup::from_cells(fragments, cells.iter().map(CellParts::as_cell))
 }
 
 /// Generate the bincode representation of the 100km cache.
 ///
 /// "100km" is a bit of a misnomer.  This is really 100km _at the equator_, but this
 /// makes it easier to reason about what the caches are doing.
+///
+/// The cache derives entirely from the items blob, so this needs no source data.
 #[cfg(feature = "self-contained")]
 #[cfg_attr(coverage_nightly, coverage(off))]
-fn generate_lookup_bincode<T>(bincode_input: impl AsRef<Path>, bincode_destination: impl AsRef<Path>)
+pub fn generate_lookup_bincode<T>(bincode_input: impl AsRef<Path>, bincode_destination: impl AsRef<Path>)
 where
     T: HasGeometry + Decode<()> + Send + Sync + 'static,
 {
-    let data = std::fs::read(bincode_input).unwrap();
-    let (timezones, _len): (ConcreteVec<T>, usize) = bincode::decode_from_slice(&data, get_global_bincode_config()).unwrap();
+    let (info, timezones) = read_items_asset::<T>(bincode_input).unwrap();
 
     let cache = get_lookup_from_geometries(&timezones);
 
-    bincode::encode_into_std_write(cache, &mut std::fs::File::create(bincode_destination).unwrap(), get_global_bincode_config()).unwrap();
+    write_asset(bincode_destination, AssetKind::Lookup, info, cache);
 }
 
-/// Get the concrete timezones from features.
+/// Get the concrete timezones from features, whose geometries are simplified together, with
+/// [`HasGeometry::simplification_epsilon`], so that neighbours keep their shared borders (see [`simplify_geometries`]),
+/// and then validated and repaired (see [`get_repaired_items_from_features`]).
 pub fn get_items_from_features<T>(features: FeatureCollection) -> ConcreteVec<T>
 where
-    T: HasGeometry + From<IdFeaturePair> + Send,
+    T: HasGeometry + TryFromFeature + Send,
+{
+    get_repaired_items_from_features(features).0
+}
+
+/// Get the concrete timezones from features (see [`get_items_from_features`]), along with a report of
+/// every feature whose geometry had problems, or that could not be ingested at all.
+///
+/// Unclosed rings are closed, rings (and polygons) without area are removed, rings that cross or touch
+/// themselves are rebuilt into valid polygons, and rings are oriented (exteriors counter-clockwise,
+/// interiors clockwise).  A feature with a non-finite coordinate, or with nothing left of its geometry,
+/// is dropped, and one that cannot be read (see [`ConcreteVec::from_features`]) is skipped, so the ids
+/// of the features after it shift down.
+pub fn get_repaired_items_from_features<T>(features: FeatureCollection) -> (ConcreteVec<T>, RepairReport)
+where
+    T: HasGeometry + TryFromFeature + Send,
+{
+    get_repaired_items_with(features, T::simplification_epsilon(), T::try_from_feature)
+}
+
+/// Get the concrete values from features, which `read` reads from each feature and its repaired
+/// geometry (see [`get_repaired_items_from_features`]).
+pub fn get_repaired_items_with<T, F>(features: FeatureCollection, simplification_epsilon: Float, read: F) -> (ConcreteVec<T>, RepairReport)
+where
+    T: HasGeometry + Send,
+    F: Fn(usize, &Feature, Geometry<Float>) -> Result<T, IngestionError> + Sync,
 {
-    ConcreteVec::from(features)
+    let mut features = features.features;
+    let (geometries, mut report) = repair_features(&mut features, simplification_epsilon);
+
+    let results = features
+        .into_par_iter()
+        .zip(geometries)
+        .enumerate()
+        .filter_map(|(index, (feature, geometry))| {
+            // A dropped feature is already in the report.
+            let geometry = geometry?;
+
+            Some(
+                geometry
+                    .and_then(|geometry| read(index, &feature, geometry))
+                    .map_err(|error| IngestionDiagnostic { feature: index, error }),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let (items, skipped) = ConcreteVec::from_results(results);
+    report.skipped = skipped;
+
+    (items, report)
+}
+
+/// The geometry of a feature after repair: `None` if the feature was dropped, or the error if it could not be read.
+type RepairedGeometry = Option<Result<Geometry<Float>, IngestionError>>;
+
+/// Take the geometry out of each feature, simplify the (multi) polygons together (see [`simplify_geometries`]),
+/// and then validate and repair them (see [`repair_geometry`]), dropping those that are beyond repair.
+fn repair_features(features: &mut [Feature], simplification_epsilon: Float) -> (Vec<RepairedGeometry>, RepairReport) {
+    let feature_count = features.len();
+
+    // The conversion closes unclosed rings, so they are found beforehand.
+    let (mut geometries, is_unclosed): (Vec<_>, Vec<_>) = features
+        .par_iter_mut()
+        .map(|feature| match feature.geometry.take() {
+            Some(geometry) => {
+                let is_unclosed = has_unclosed_ring(&geometry.value);
+                (Some(convert_geometry_value(geometry.value)), is_unclosed)
+            }
+            None => (Some(Err(IngestionError::MissingGeometry)), false),
+        })
+        .unzip();
+
+    // Non-finite geometries are swapped for empty ones, which simplification passes over.
+    let (polygonal, (polygons, problems)): (Vec<_>, (Vec<_>, Vec<_>)) = geometries
+        .par_iter_mut()
+        .zip(is_unclosed)
+        .enumerate()
+        .filter(|(_, (geometry, _))| matches!(geometry, Some(Ok(Geometry::Polygon(_) | Geometry::MultiPolygon(_)))))
+        .map(|(index, (geometry, is_unclosed))| {
+            let Some(Ok(geometry)) = geometry.take() else { unreachable!() };
+            let mut problems = Vec::new();
+
+            if is_unclosed {
+                problems.push(GeometryProblem::UnclosedRing);
+            }
+
+            if geometry.coords_iter().all(|coord| coord.x.is_finite() && coord.y.is_finite()) {
+                (index, (Some(geometry), problems))
+            } else {
+                problems.push(GeometryProblem::NonFiniteCoordinate);
+                (index, (None, problems))
+            }
+        })
+        .unzip();
+
+    let is_finite = polygons.iter().map(Option::is_some).collect::<Vec<_>>();
+    let polygons = polygons
+        .into_iter()
+        .map(|geometry| geometry.unwrap_or_else(|| Geometry::MultiPolygon(MultiPolygon::new(Vec::new()))))
+        .collect();
+    let polygons = simplify_geometries(polygons, simplification_epsilon);
+    let repaired = polygons
+        .into_par_iter()
+        .zip(is_finite)
+        .map(|(geometry, is_finite)| if is_finite { repair_geometry(geometry) } else { (None, Vec::new()) })
+        .collect::<Vec<_>>();
+
+    let mut report = RepairReport { feature_count, ..Default::default() };
+
+    for ((index, mut problems), (geometry, repair_problems)) in polygonal.into_iter().zip(problems).zip(repaired) {
+        problems.extend(repair_problems);
+
+        if !problems.is_empty() {
+            report.features.push(RepairedFeature {
+                feature: index,
+                problems,
+                dropped: geometry.is_none(),
+            });
+        }
+
+        geometries[index] = geometry.map(Ok);
+    }
+
+    (geometries, report)
 }
 
-/// Generate bincode representation of the timezones.
+/// Generate bincode representation of the timezones, unless their geometries have more than `max_problems` problems.
 #[cfg(feature = "self-contained")]
 #[cfg_attr(coverage_nightly, coverage(off))]
-fn generate_item_bincode<T>(geojson_features: FeatureCollection, bincode_destination: impl AsRef<Path>)
+fn generate_item_bincode<T>(geojson_features: FeatureCollection, bincode_destination: impl AsRef<Path>, max_problems: Option<usize>) -> Res<RepairReport>
 where
-    T: HasGeometry + Encode + From<IdFeaturePair> + Send + 'static,
+    T: HasGeometry + Encode + TryFromFeature + Send + 'static,
 {
-    let items: ConcreteVec<T> = T::reorder(get_items_from_features(geojson_features));
-    bincode::encode_into_std_write(items, &mut std::fs::File::create(bincode_destination).unwrap(), get_global_bincode_config()).unwrap();
+    let (items, report) = get_repaired_items_from_features::<T>(geojson_features);
+
+    if let Some(max_problems) = max_problems.filter(|&max_problems| report.problem_count() > max_problems) {
+        anyhow::bail!("Found {report}, which is more than the {max_problems} allowed.");
+    }
+
+    let items: ConcreteVec<T> = T::reorder(items);
+    let info = DatasetInfo::new::<T>(items.len());
+
+    write_asset(bincode_destination, AssetKind::Items, info, items);
+
+    Ok(report)
 }
 
 /// Re-impose [`HasGeometry::reorder`] on already-generated bincodes, rewriting the items blob and
@@ -481,15 +1313,14 @@ pub fn resort_items_bincode<T>(items_path: impl AsRef<Path>, lookup_path: impl A
 where
     T: HasGeometry + Encode + Decode<()> + Send + Sync + 'static,
 {
-    let data = std::fs::read(items_path.as_ref()).unwrap();
-    let (items, _len): (ConcreteVec<T>, usize) = bincode::decode_from_slice(&data, get_global_bincode_config()).unwrap();
+    let (info, items) = read_items_asset::<T>(items_path.as_ref()).unwrap();
 
     // Reorder first: the cache stores ids, so it has to be derived from the reindexed items.
     let items = T::reorder(items);
     let cache = get_lookup_from_geometries(&items);
 
-    bincode::encode_into_std_write(items, &mut std::fs::File::create(items_path.as_ref()).unwrap(), get_global_bincode_config()).unwrap();
-    bincode::encode_into_std_write(cache, &mut std::fs::File::create(lookup_path.as_ref()).unwrap(), get_global_bincode_config()).unwrap();
+    let info = write_asset(items_path.as_ref(), AssetKind::Items, info, items);
+    write_asset(lookup_path.as_ref(), AssetKind::Lookup, info, cache);
 }
 
 /// Get the GeoJSON features from the binary assets.
@@ -514,61 +1345,981 @@ pub fn get_geojson_feature_from_string(geojson_input: &str) -> Feature {
     Feature::try_from(geojson_input.parse::<GeoJson>().unwrap()).unwrap()
 }
 
-/// Generates new bincodes for the timezones and the cache from the GeoJSON.
+/// Generates new bincodes for the timezones and the cache from the GeoJSON, and returns the report of
+/// the geometries that ingestion repaired or dropped (see [`get_repaired_items_from_features`]).
+///
+/// Fails, without writing either bincode, if the geometries have more than `max_problems` problems.
 #[cfg(feature = "self-contained")]
 #[cfg_attr(coverage_nightly, coverage(off))]
-pub fn generate_bincodes<T>(geojson_features: FeatureCollection, timezone_bincode_destination: impl AsRef<Path>, lookup_bincode_destination: impl AsRef<Path>)
+pub fn generate_bincodes<T>(
+    geojson_features: FeatureCollection,
+    timezone_bincode_destination: impl AsRef<Path>,
+    lookup_bincode_destination: impl AsRef<Path>,
+    max_problems: Option<usize>,
+) -> Res<RepairReport>
 where
-    T: HasGeometry + Encode + From<IdFeaturePair> + Decode<()> + Send + Sync + 'static,
+    T: HasGeometry + Encode + TryFromFeature + Decode<()> + Send + Sync + 'static,
 {
-    generate_item_bincode::<T>(geojson_features, timezone_bincode_destination.as_ref());
+    let report = generate_item_bincode::<T>(geojson_features, timezone_bincode_destination.as_ref(), max_problems)?;
     generate_lookup_bincode::<T>(timezone_bincode_destination, lookup_bincode_destination);
-}
-
-// Helpers to get GeoJSON features from a source.
 
-/// Trait that supports getting the GeoJSON features from a source.
-pub trait CanGetGeoJsonFeaturesFromSource {
-    /// Get the GeoJSON features from a source.
-    fn get_geojson_features_from_source() -> geojson::FeatureCollection;
+    Ok(report)
 }
 
-// Bincode helpers.
-
-/// Computes the best bincode to be used for the target architecture.
-#[cfg(all(feature = "self-contained", target_endian = "big"))]
+/// Writes a [`RepairReport`], as JSON.
+#[cfg(feature = "self-contained")]
+#[cfg_attr(coverage_nightly, coverage(off))]
+pub fn write_repair_report(destination: impl AsRef<Path>, report: &RepairReport) -> Void {
+    std::fs::write(destination, serde_json::to_string_pretty(report)?)?;
 
-pub fn get_global_bincode_config() -> Configuration<bincode::config::BigEndian, bincode::config::Fixint> {
-    bincode::config::legacy().with_big_endian()
+    Ok(())
 }
 
-/// Computes the best bincode to be used for the target architecture.
-#[cfg(all(feature = "self-contained", target_endian = "little"))]
-pub fn get_global_bincode_config() -> Configuration<bincode::config::LittleEndian, bincode::config::Fixint> {
-    bincode::config::legacy()
+// Ingestion errors.
+
+/// Why a feature could not be ingested (see [`ConcreteVec::from_features`]).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum IngestionError {
+    /// The feature has no properties.
+    MissingProperties,
+    /// The feature has no geometry.
+    MissingGeometry,
+    /// The feature lacks a property that the dataset requires.
+    MissingProperty {
+        /// The name of the property.
+        name: String,
+    },
+    /// A property of the feature has the wrong type.
+    InvalidProperty {
+        /// The name of the property.
+        name: String,
+        /// The type that the dataset requires (e.g., "a string").
+        expected: String,
+    },
+    /// The geometry of the feature is malformed.
+    InvalidGeometry {
+        /// Why the geometry could not be read.
+        reason: String,
+    },
 }
 
-// Special encoding / decoding logic for geometries.
+impl Display for IngestionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IngestionError::MissingProperties => write!(f, "The feature has no properties."),
+            IngestionError::MissingGeometry => write!(f, "The feature has no geometry."),
+            IngestionError::MissingProperty { name } => write!(f, "The feature has no `{}` property.", name),
+            IngestionError::InvalidProperty { name, expected } => write!(f, "The feature's `{}` property is not {}.", name, expected),
+            IngestionError::InvalidGeometry { reason } => write!(f, "The feature's geometry is malformed: {}.", reason),
+        }
+    }
+}
+
+impl std::error::Error for IngestionError {}
+
+/// A feature that could not be ingested, and so was skipped.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IngestionDiagnostic {
+    /// The index of the feature in the source feature collection.
+    pub feature: usize,
+    /// Why the feature could not be ingested.
+    pub error: IngestionError,
+}
+
+impl Display for IngestionDiagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Feature {}: {}", self.feature, self.error)
+    }
+}
+
+/// Get the properties of a feature.
+pub fn get_feature_properties(feature: &Feature) -> Result<&Map<String, Value>, IngestionError> {
+    feature.properties.as_ref().ok_or(IngestionError::MissingProperties)
+}
+
+/// Get the geometry of a feature.
+pub fn get_feature_geometry(feature: &Feature) -> Result<Geometry<Float>, IngestionError> {
+    let geometry = feature.geometry.as_ref().ok_or(IngestionError::MissingGeometry)?;
+
+    convert_geometry_value(geometry.value.clone())
+}
+
+/// Read an item from a feature and its own geometry (e.g., for a [`TryFrom<IdFeaturePair>`] implementation).
+pub fn try_from_id_feature_pair<T>((id, feature): IdFeaturePair) -> Result<T, IngestionError>
+where
+    T: TryFromFeature,
+{
+    let geometry = get_feature_geometry(&feature)?;
+
+    T::try_from_feature(id, &feature, geometry)
+}
+
+/// Convert a GeoJSON geometry.
+fn convert_geometry_value(value: geojson::GeometryValue) -> Result<Geometry<Float>, IngestionError> {
+    Geometry::try_from(value).map_err(|e| IngestionError::InvalidGeometry { reason: e.to_string() })
+}
+
+/// Get a property of a feature, which may be `null`.
+pub fn get_property<'a>(properties: &'a Map<String, Value>, name: &str) -> Result<&'a Value, IngestionError> {
+    properties.get(name).ok_or_else(|| IngestionError::MissingProperty { name: name.to_string() })
+}
+
+/// Get a string property of a feature.
+pub fn get_str_property<'a>(properties: &'a Map<String, Value>, name: &str) -> Result<&'a str, IngestionError> {
+    get_property(properties, name)?.as_str().ok_or_else(|| get_invalid_property_error(name, "a string"))
+}
+
+/// Get a number property of a feature.
+pub fn get_f64_property(properties: &Map<String, Value>, name: &str) -> Result<f64, IngestionError> {
+    get_property(properties, name)?.as_f64().ok_or_else(|| get_invalid_property_error(name, "a number"))
+}
+
+/// Get a non-negative integer property of a feature.
+pub fn get_u64_property(properties: &Map<String, Value>, name: &str) -> Result<u64, IngestionError> {
+    get_property(properties, name)?.as_u64().ok_or_else(|| get_invalid_property_error(name, "a non-negative integer"))
+}
+
+/// Get the error for a property that is not of the `expected` type.
+fn get_invalid_property_error(name: &str, expected: &str) -> IngestionError {
+    IngestionError::InvalidProperty {
+        name: name.to_string(),
+        expected: expected.to_string(),
+    }
+}
+
+// Geometry validation and repair.
+
+/// A problem with the geometry of a feature, which ingestion repairs if it can (see [`get_repaired_items_from_features`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GeometryProblem {
+    /// A ring whose last position is not its first, which is closed.
+    UnclosedRing,
+    /// A coordinate that is not finite, which drops the feature.
+    NonFiniteCoordinate,
+    /// A ring or polygon without area (e.g., one that simplification collapsed), which is removed.
+    EmptyPolygon,
+    /// A ring that crosses or touches itself, which is rebuilt into valid polygons.
+    SelfIntersection,
+    /// An exterior that is not counter-clockwise, or an interior that is not clockwise, which is reversed.
+    WrongOrientation,
+}
+
+/// The problems with the geometry of one feature.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RepairedFeature {
+    /// The index of the feature in the source feature collection.
+    pub feature: usize,
+    /// The problems, each once, in the order they were found.
+    pub problems: Vec<GeometryProblem>,
+    /// Whether the feature was dropped, since nothing of its geometry could be kept.
+    pub dropped: bool,
+}
+
+/// A report of every feature whose geometry ingestion repaired or dropped, or that it skipped (see [`get_repaired_items_from_features`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RepairReport {
+    /// The number of features ingested.
+    pub feature_count: usize,
+    /// The features whose geometries had problems, in source order.
+    pub features: Vec<RepairedFeature>,
+    /// The features that could not be ingested, and so were skipped, in source order.
+    pub skipped: Vec<IngestionDiagnostic>,
+}
+
+impl RepairReport {
+    /// The number of problems, over all features, counting each skipped feature as one.
+    pub fn problem_count(&self) -> usize {
+        self.features.iter().map(|feature| feature.problems.len()).sum::<usize>() + self.skipped.len()
+    }
+
+    /// The number of features dropped.
+    pub fn dropped_count(&self) -> usize {
+        self.features.iter().filter(|feature| feature.dropped).count()
+    }
+}
+
+impl Display for RepairReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} problem(s) in {} of {} feature(s): {} repaired, {} dropped, and {} skipped",
+            self.problem_count(),
+            self.features.len() + self.skipped.len(),
+            self.feature_count,
+            self.features.len() - self.dropped_count(),
+            self.dropped_count(),
+            self.skipped.len()
+        )
+    }
+}
+
+/// Whether a GeoJSON (multi) polygon has a ring whose last position is not its first.
+fn has_unclosed_ring(value: &geojson::GeometryValue) -> bool {
+    let polygons = match value {
+        geojson::GeometryValue::Polygon { coordinates } => std::slice::from_ref(coordinates),
+        geojson::GeometryValue::MultiPolygon { coordinates } => coordinates.as_slice(),
+        _ => &[],
+    };
+
+    polygons.iter().flatten().any(|ring| ring.first() != ring.last())
+}
+
+/// Validate and repair a (multi) polygon, so that `Contains` holds for it (see [`GeometryProblem`]), and
+/// return what is left of it (if anything), along with the problems found.
+///
+/// Geometries other than (multi) polygons are returned as they are.
+fn repair_geometry(geometry: Geometry<Float>) -> (Option<Geometry<Float>>, Vec<GeometryProblem>) {
+    let (polygons, is_multi) = match geometry {
+        Geometry::Polygon(polygon) => (vec![polygon], false),
+        Geometry::MultiPolygon(multi_polygon) => (multi_polygon.0, true),
+        geometry => return (Some(geometry), Vec::new()),
+    };
+
+    let mut problems = Vec::new();
+    let mut note = |problem| {
+        if !problems.contains(&problem) {
+            problems.push(problem);
+        }
+    };
+    let mut repaired = Vec::with_capacity(polygons.len());
+
+    for polygon in polygons {
+        let (exterior, interiors) = polygon.remove_repeated_points().into_inner();
+
+        if !ring_has_area(&exterior) {
+            note(GeometryProblem::EmptyPolygon);
+            continue;
+        }
+
+        let mut is_oriented = get_ring_signed_area(&exterior) > 0.0;
+        let mut kept_interiors = Vec::with_capacity(interiors.len());
+
+        for interior in interiors {
+            if !ring_has_area(&interior) {
+                note(GeometryProblem::EmptyPolygon);
+                continue;
+            }
+
+            is_oriented &= get_ring_signed_area(&interior) < 0.0;
+            kept_interiors.push(interior);
+        }
+
+        let polygon = Polygon::new(exterior, kept_interiors);
+
+        if std::iter::once(polygon.exterior()).chain(polygon.interiors()).any(ring_has_self_intersection) {
+            note(GeometryProblem::SelfIntersection);
+
+            // A polygon that cannot be rebuilt (e.g., of coordinates out of the triangulation's range) is removed.
+            if let Ok(valid) = polygon.make_valid() {
+                repaired.extend(valid.0.iter().map(|polygon| polygon.orient(Direction::Default)));
+            }
+        } else if is_oriented {
+            repaired.push(polygon);
+        } else {
+            note(GeometryProblem::WrongOrientation);
+            repaired.push(polygon.orient(Direction::Default));
+        }
+    }
+
+    if repaired.is_empty() {
+        note(GeometryProblem::EmptyPolygon);
+
+        return (None, problems);
+    }
+
+    let geometry = if is_multi || repaired.len() > 1 {
+        Geometry::MultiPolygon(MultiPolygon::new(repaired))
+    } else {
+        Geometry::Polygon(repaired.remove(0))
+    };
+
+    (Some(geometry), problems)
+}
+
+/// Get the signed area of a closed ring (in square degrees), which is positive if it is counter-clockwise.
+///
+/// The lobes of a ring that crosses itself cancel out, so its signed area may be zero (see [`ring_has_area`]).
+fn get_ring_signed_area(ring: &LineString<Float>) -> f64 {
+    get_ring_cross_products(ring).sum::<f64>() / 2.0
+}
+
+/// Whether a closed ring encloses any area, which it does unless all of its vertices are on one line.
+fn ring_has_area(ring: &LineString<Float>) -> bool {
+    get_ring_cross_products(ring).any(|cross_product| cross_product != 0.0)
+}
+
+/// Get the cross products of the vertices at either end of each edge of a ring, relative to its first
+/// vertex (which keeps the products small, and so precise).
+fn get_ring_cross_products(ring: &LineString<Float>) -> impl Iterator<Item = f64> + '_ {
+    let origin = ring.0.first().copied().unwrap_or_default();
+
+    ring.0.windows(2).map(move |edge| {
+        let (a, b) = (edge[0] - origin, edge[1] - origin);
+
+        f64::from(a.x) * f64::from(b.y) - f64::from(b.x) * f64::from(a.y)
+    })
+}
+
+/// A segment of a ring, along with its position in the ring.
+#[derive(Clone)]
+struct RingSegment {
+    index: usize,
+    line: Line<Float>,
+}
+
+impl Cross for RingSegment {
+    type Scalar = Float;
+
+    fn line(&self) -> Line<Float> {
+        self.line
+    }
+}
+
+/// Whether a closed ring (without repeated vertices) crosses or touches itself.
+///
+/// Neighbouring segments always meet at the vertex between them, so they only count if they overlap.
+fn ring_has_self_intersection(ring: &LineString<Float>) -> bool {
+    let segments = ring.lines().enumerate().map(|(index, line)| RingSegment { index, line }).collect::<Vec<_>>();
+    let len = segments.len();
+
+    Intersections::from_iter(&segments).any(|(a, b, intersection)| {
+        let distance = a.index.abs_diff(b.index);
+        let are_neighbours = distance == 1 || distance == len - 1;
+
+        !are_neighbours || matches!(intersection, LineIntersection::Collinear { .. })
+    })
+}
+
+// Helpers to get GeoJSON features from a source.
+
+/// Trait that supports getting the GeoJSON features from a source.
+pub trait CanGetGeoJsonFeaturesFromSource {
+    /// Get the GeoJSON features from a source.
+    fn get_geojson_features_from_source() -> geojson::FeatureCollection;
+}
+
+// Bincode helpers.
+
+/// The byte order of every asset, on every target: the header, and every scalar in the body.
+///
+/// Assets are encoded by the build host (see `rtz-build`) and decoded on the target, which may differ
+/// (e.g., when cross-compiling from `x86_64` to a big-endian target), so the order is fixed, rather than native.
+#[cfg(feature = "self-contained")]
+pub type AssetByteOrder = bincode::config::LittleEndian;
+
+/// Computes the bincode config that every asset is encoded with, whatever the host or target (see [`AssetByteOrder`]).
+#[cfg(feature = "self-contained")]
+pub fn get_global_bincode_config() -> Configuration<AssetByteOrder, bincode::config::Fixint> {
+    bincode::config::legacy()
+}
+
+/// The most memory that a safe decode (see [`get_safe_bincode_config`]) may claim for containers
+/// before it fails, so that a corrupt length cannot make it allocate without bound.
+#[cfg(feature = "self-contained")]
+pub const SAFE_DECODE_LIMIT: usize = 1 << 30;
+
+/// Computes the bincode config for data that did not come from a trusted build: [`get_global_bincode_config`],
+/// limited to [`SAFE_DECODE_LIMIT`].
+#[cfg(feature = "self-contained")]
+pub fn get_safe_bincode_config() -> Configuration<AssetByteOrder, bincode::config::Fixint, bincode::config::Limit<SAFE_DECODE_LIMIT>> {
+    get_global_bincode_config().with_limit::<SAFE_DECODE_LIMIT>()
+}
+
+// Asset headers.
+
+/// The bytes every generated bincode starts with.
+pub const ASSET_MAGIC: [u8; 8] = *b"RTZASSET";
+
+/// The version of the asset format: bump it whenever the encoding of the header, the items, or the lookup changes.
+pub const ASSET_FORMAT_VERSION: u32 = 5;
+
+/// The width, in bits, of the [`Float`]s that this build's geometries (and, so, the assets it generates) use.
+///
+/// Every build reads assets of either width, widening or narrowing their coordinates on load.
+pub const FLOAT_BITS: u8 = (std::mem::size_of::<Float>() * 8) as u8;
+
+/// What a dataset was generated from, and with which build parameters.
+///
+/// Every generated bincode starts with an [`AssetHeader`] that carries it, and a lookup carries
+/// exactly the info of the items it was built from.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DatasetInfo {
+    /// The asset format version (see [`ASSET_FORMAT_VERSION`]).
+    pub format_version: u32,
+    /// The width, in bits, of the floats of the build that generated the geometries (see [`FLOAT_BITS`]).
+    pub float_bits: u8,
+    /// The epsilon the geometries were simplified with (see [`HasGeometry::simplification_epsilon`]), or `0` if they were not.
+    pub simplification_epsilon: f64,
+    /// The upstream source of the items (see [`HasGeometry::source`]).
+    pub source: String,
+    /// When the items were generated, in seconds since the Unix epoch, or the `SOURCE_DATE_EPOCH`
+    /// of the build, if it set one (see [`get_generated_at`]).
+    pub generated_at: u64,
+    /// The number of items.
+    pub item_count: u64,
+    /// The CRC-32 of the encoded items (the body of their asset), which pairs a lookup with the
+    /// items it was built from, or `0` until the items are written (see [`write_asset`]).
+    pub items_checksum: u32,
+}
+
+impl DatasetInfo {
+    /// Describes `item_count` items of `T`, generated now (see [`get_generated_at`]), by this build.
+    pub fn new<T>(item_count: usize) -> Self
+    where
+        T: HasGeometry,
+    {
+        let generated_at = get_generated_at(std::env::var("SOURCE_DATE_EPOCH").ok().as_deref());
+        // Widen through the shortest decimal representation, so `0.0001` reads as `0.0001`, rather than `0.00009999999747378752`.
+        let simplification_epsilon = if cfg!(feature = "unsimplified") {
+            0.0
+        } else {
+            T::simplification_epsilon().to_string().parse().unwrap_or_default()
+        };
+
+        DatasetInfo {
+            format_version: ASSET_FORMAT_VERSION,
+            float_bits: FLOAT_BITS,
+            simplification_epsilon,
+            source: T::source().to_string(),
+            generated_at,
+            item_count: item_count as u64,
+            items_checksum: 0,
+        }
+    }
+}
+
+/// Get the generation time to record in a [`DatasetInfo`]: `source_date_epoch` (the
+/// [`SOURCE_DATE_EPOCH`](https://reproducible-builds.org/specs/source-date-epoch/) of the build),
+/// if it is a valid one, and the current time otherwise.
+///
+/// Everything else in a generated asset derives from the source data and the build features alone,
+/// so setting `SOURCE_DATE_EPOCH` makes regenerations from identical data byte-for-byte identical.
+pub fn get_generated_at(source_date_epoch: Option<&str>) -> u64 {
+    source_date_epoch
+        .and_then(|epoch| epoch.trim().parse().ok())
+        .unwrap_or_else(|| std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default())
+}
+
+/// Which of a dataset's two bincodes an asset is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    /// The items (e.g., `osm_time_zones.bincode`).
+    Items,
+    /// The lookup cache over the items (e.g., `osm_time_zone_lookup.bincode`).
+    Lookup,
+}
+
+impl Display for AssetKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetKind::Items => write!(f, "items"),
+            AssetKind::Lookup => write!(f, "lookup"),
+        }
+    }
+}
+
+/// The header every generated bincode starts with, so that it can be checked before it is decoded.
+///
+/// It is laid out as [`ASSET_MAGIC`], the format version (`u32`), the length of the header in bytes
+/// (`u32`), and then the kind, the [`DatasetInfo`], and the length and checksum of the body (and then
+/// the [`DatasetInfo::items_checksum`]), padded
+/// with zeros to a multiple of 8 bytes, so that the body that follows stays as aligned as the data
+/// it is in.  Like the body, it is little-endian on every host and target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetHeader {
+    /// Which of the dataset's bincodes this is.
+    pub kind: AssetKind,
+    /// What the dataset was generated from.
+    pub info: DatasetInfo,
+    /// The length of the body, in bytes.
+    pub body_len: u64,
+    /// The CRC-32 of the body, which is verified before the body is decoded.
+    pub checksum: u32,
+}
+
+/// An error reading a generated bincode.
+#[derive(Debug)]
+pub enum AssetError {
+    /// The data does not start with [`ASSET_MAGIC`]: it is not an asset, or it predates asset headers.
+    MissingHeader,
+    /// The asset was generated with a different format version than this build reads.
+    UnsupportedFormatVersion {
+        /// The version in the header.
+        found: u32,
+        /// The version this build reads.
+        expected: u32,
+    },
+    /// The asset's geometries use floats that are neither `f32`s nor `f64`s.
+    UnsupportedFloatWidth {
+        /// The width, in bits, in the header.
+        found: u8,
+    },
+    /// The asset is the wrong one of the dataset's two bincodes.
+    WrongKind {
+        /// The kind in the header.
+        found: AssetKind,
+        /// The kind that was expected.
+        expected: AssetKind,
+    },
+    /// The lookup was built from different items than the ones it was paired with.
+    MismatchedLookup {
+        /// The info of the items.
+        items: Box<DatasetInfo>,
+        /// The info of the items the lookup was built from.
+        lookup: Box<DatasetInfo>,
+    },
+    /// The body is shorter or longer than the header says (e.g., the file was cut off).
+    Truncated {
+        /// The length in the header.
+        expected: u64,
+        /// The length of the body.
+        found: u64,
+    },
+    /// The body does not match the checksum in the header: it was corrupted.
+    ChecksumMismatch {
+        /// The checksum in the header.
+        expected: u32,
+        /// The checksum of the body.
+        found: u32,
+    },
+    /// The body decoded to a different number of items than the header says.
+    ItemCountMismatch {
+        /// The count in the header.
+        expected: u64,
+        /// The count that was decoded.
+        found: u64,
+    },
+    /// The body decoded, but is not internally consistent (e.g., a lookup refers to an item past the end of the items).
+    Invalid(String),
+    /// The asset is compressed (see [`is_compressed_asset`]), and must be decompressed before it is read.
+    Compressed,
+    /// The asset could not be read, written, compressed, or decompressed.
+    Io(std::io::Error),
+    /// The header or the body is malformed.
+    #[cfg(feature = "self-contained")]
+    Decode(DecodeError),
+}
+
+impl Display for AssetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetError::MissingHeader => write!(
+                f,
+                "The data has no asset header: it is not an `rtz` bincode, or it predates asset headers (regenerate it with `force-rebuild`)."
+            ),
+            AssetError::UnsupportedFormatVersion { found, expected } => write!(
+                f,
+                "The asset has format version {}, but this build reads version {}: regenerate it with `force-rebuild`, or use a matching `rtz`.",
+                found, expected
+            ),
+            AssetError::UnsupportedFloatWidth { found } => write!(f, "The asset was generated with {}-bit floats, but only 32-bit and 64-bit floats are read.", found),
+            AssetError::WrongKind { found, expected } => write!(f, "Expected the {} bincode, but found the {} bincode.", expected, found),
+            AssetError::MismatchedLookup { items, lookup } => write!(
+                f,
+                "The lookup was built from {} items with checksum {:08x}, but the items are {} with checksum {:08x}: they must come from the same build of the dataset.",
+                lookup.item_count, lookup.items_checksum, items.item_count, items.items_checksum
+            ),
+            AssetError::Truncated { expected, found } => write!(f, "The asset header says the body is {} bytes, but it is {} bytes: the asset was cut off or padded.", expected, found),
+            AssetError::ChecksumMismatch { expected, found } => write!(f, "The asset's checksum is {:08x}, but its header says {:08x}: the asset is corrupted.", found, expected),
+            AssetError::ItemCountMismatch { expected, found } => write!(f, "The asset header says {} items, but {} were decoded.", expected, found),
+            AssetError::Invalid(reason) => write!(f, "The asset is invalid: {}.", reason),
+            AssetError::Compressed => write!(f, "The asset is compressed: decompress it first, or enable `compressed-assets`, which decompresses it on load."),
+            AssetError::Io(e) => write!(f, "The asset could not be read: {}.", e),
+            #[cfg(feature = "self-contained")]
+            AssetError::Decode(e) => write!(f, "The asset is malformed: {}.", e),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+#[cfg(feature = "self-contained")]
+impl From<DecodeError> for AssetError {
+    fn from(value: DecodeError) -> Self {
+        AssetError::Decode(value)
+    }
+}
+
+impl AssetHeader {
+    /// Checks that this is the header of the expected one of a dataset's two bincodes.
+    pub fn expect_kind(&self, expected: AssetKind) -> Result<(), AssetError> {
+        if self.kind != expected {
+            return Err(AssetError::WrongKind { found: self.kind, expected });
+        }
+
+        Ok(())
+    }
+}
+
+/// The length of the fixed start of an [`AssetHeader`]: the magic, the format version, and the header length.
+#[cfg(feature = "self-contained")]
+const ASSET_HEADER_PREFIX_LEN: usize = ASSET_MAGIC.len() + 4 + 4;
+
+/// The encoded fields of an [`AssetHeader`], after its prefix: the kind, the [`DatasetInfo`] (but
+/// the format version and the items checksum), the length and checksum of the body, and the items checksum.
+#[cfg(feature = "self-contained")]
+type AssetHeaderFields = (u8, u8, f64, u64, u64, String, u64, u32, u32);
+
+#[cfg(feature = "self-contained")]
+impl AssetHeader {
+    /// Creates the header of an asset, with the length and checksum of its (encoded) body.
+    ///
+    /// The checksum of an items asset is also its info's [`DatasetInfo::items_checksum`], while a
+    /// lookup keeps that of the items it was built from.
+    pub fn new(kind: AssetKind, mut info: DatasetInfo, body: &[u8]) -> Self {
+        let checksum = crc32fast::hash(body);
+
+        if kind == AssetKind::Items {
+            info.items_checksum = checksum;
+        }
+
+        AssetHeader {
+            kind,
+            info,
+            body_len: body.len() as u64,
+            checksum,
+        }
+    }
+
+    /// Encodes the header, padded to a multiple of 8 bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let config = get_global_bincode_config();
+        let kind: u8 = match self.kind {
+            AssetKind::Items => 0,
+            AssetKind::Lookup => 1,
+        };
+        let info = &self.info;
+        let fields = bincode::encode_to_vec(
+            (
+                kind,
+                info.float_bits,
+                info.simplification_epsilon,
+                info.generated_at,
+                info.item_count,
+                info.source.as_str(),
+                self.body_len,
+                self.checksum,
+                info.items_checksum,
+            ),
+            config,
+        )
+        .unwrap();
+
+        let len = (ASSET_HEADER_PREFIX_LEN + fields.len()).next_multiple_of(8);
+
+        let mut bytes = Vec::with_capacity(len);
+        bytes.extend_from_slice(&ASSET_MAGIC);
+        bytes.extend(bincode::encode_to_vec((info.format_version, len as u32), config).unwrap());
+        bytes.extend(fields);
+        bytes.resize(len, 0);
+
+        bytes
+    }
+
+    /// Decodes the header at the start of `data`, checking it against this build and verifying the
+    /// body's length and checksum, and returns it along with the body that follows it.
+    pub fn from_bytes(data: &[u8]) -> Result<(AssetHeader, &[u8]), AssetError> {
+        let config = get_global_bincode_config();
+
+        if is_compressed_asset(data) {
+            return Err(AssetError::Compressed);
+        }
+
+        if data.len() < ASSET_HEADER_PREFIX_LEN || data[..ASSET_MAGIC.len()] != ASSET_MAGIC {
+            return Err(AssetError::MissingHeader);
+        }
+
+        let ((format_version, len), _): ((u32, u32), usize) = bincode::decode_from_slice(&data[ASSET_MAGIC.len()..ASSET_HEADER_PREFIX_LEN], config)?;
+
+        if format_version != ASSET_FORMAT_VERSION {
+            return Err(AssetError::UnsupportedFormatVersion {
+                found: format_version,
+                expected: ASSET_FORMAT_VERSION,
+            });
+        }
+
+        let len = len as usize;
+        if len < ASSET_HEADER_PREFIX_LEN || len > data.len() {
+            return Err(AssetError::Decode(DecodeError::UnexpectedEnd {
+                additional: len.saturating_sub(data.len()),
+            }));
+        }
+
+        let ((kind, float_bits, simplification_epsilon, generated_at, item_count, source, body_len, checksum, items_checksum), _): (AssetHeaderFields, usize) =
+            bincode::decode_from_slice(&data[ASSET_HEADER_PREFIX_LEN..len], config)?;
+
+        let kind = match kind {
+            0 => AssetKind::Items,
+            1 => AssetKind::Lookup,
+            found => return Err(unexpected_variant("AssetKind", found as usize).into()),
+        };
+
+        if float_bits != 32 && float_bits != 64 {
+            return Err(AssetError::UnsupportedFloatWidth { found: float_bits });
+        }
+
+        let body = &data[len..];
+
+        if body.len() as u64 != body_len {
+            return Err(AssetError::Truncated {
+                expected: body_len,
+                found: body.len() as u64,
+            });
+        }
+
+        let found = crc32fast::hash(body);
+        if found != checksum {
+            return Err(AssetError::ChecksumMismatch { expected: checksum, found });
+        }
+
+        if kind == AssetKind::Items && items_checksum != checksum {
+            return Err(AssetError::Invalid(format!(
+                "the items checksum {:08x} is not the checksum of the items, {:08x}",
+                items_checksum, checksum
+            )));
+        }
+
+        let info = DatasetInfo {
+            format_version,
+            float_bits,
+            simplification_epsilon,
+            source,
+            generated_at,
+            item_count,
+            items_checksum,
+        };
+
+        Ok((AssetHeader { kind, info, body_len, checksum }, body))
+    }
+}
+
+/// Checks that each item's id is its index (which is how lookups refer to it), and that every coordinate is finite.
+///
+/// Generated items always pass: this is for items that did not come from a trusted build.
+pub fn validate_items<T>(items: &[T]) -> Result<(), AssetError>
+where
+    T: HasGeometry,
+{
+    for (k, item) in items.iter().enumerate() {
+        if item.id() != k {
+            return Err(AssetError::Invalid(format!("item {} has id {}", k, item.id())));
+        }
+
+        if item.geometry().coords_iter().any(|c| !c.x.is_finite() || !c.y.is_finite()) {
+            return Err(AssetError::Invalid(format!("item {} has a coordinate that is not finite", k)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes an asset: the header, then the encoded value.
+///
+/// Returns the info in the header, which, for items, records their checksum, so that it can be
+/// handed on to the lookup built from them (see [`DatasetInfo::items_checksum`]).
+#[cfg(feature = "self-contained")]
+pub fn write_asset<V>(destination: impl AsRef<Path>, kind: AssetKind, info: DatasetInfo, value: V) -> DatasetInfo
+where
+    V: Encode,
+{
+    let body = bincode::encode_to_vec(value, get_global_bincode_config()).unwrap();
+    let header = AssetHeader::new(kind, info, &body);
+
+    let mut bytes = header.to_bytes();
+    bytes.extend(body);
+
+    std::fs::write(destination, bytes).unwrap();
+
+    header.info
+}
+
+/// Reads a dataset's items asset, checking its header.
+#[cfg(feature = "self-contained")]
+pub fn read_items_asset<T>(source: impl AsRef<Path>) -> Result<(DatasetInfo, ConcreteVec<T>), AssetError>
+where
+    T: Decode<()> + 'static,
+{
+    let data = std::fs::read(source).map_err(AssetError::Io)?;
+    let (header, body) = AssetHeader::from_bytes(&data)?;
+
+    header.expect_kind(AssetKind::Items)?;
+
+    let (items, _len): (ConcreteVec<T>, usize) = bincode::decode_from_slice(body, get_global_bincode_config())?;
+
+    if items.len() as u64 != header.info.item_count {
+        return Err(AssetError::ItemCountMismatch {
+            expected: header.info.item_count,
+            found: items.len() as u64,
+        });
+    }
+
+    Ok((header.info, items))
+}
+
+// Compressed assets.
+
+/// The bytes a compressed asset (a zstd frame) starts with.
+pub const COMPRESSED_ASSET_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The zstd level assets are compressed at: they are compressed once, at build time, and decompressed
+/// on every start, so this is the highest level short of zstd's `--ultra` ones.
+pub const ASSET_COMPRESSION_LEVEL: i32 = 19;
+
+/// The largest asset that a compressed asset may decompress to, so that a corrupt size cannot make
+/// decompression allocate without bound.
+pub const MAX_DECOMPRESSED_ASSET_LEN: u64 = 1 << 32;
+
+/// Whether `data` is a compressed asset, rather than an asset.
+pub fn is_compressed_asset(data: &[u8]) -> bool {
+    data.starts_with(&COMPRESSED_ASSET_MAGIC)
+}
+
+/// Gets the path of an asset's compressed form: its path, with `.zst` appended (e.g., `osm_time_zones.bincode.zst`).
+pub fn get_compressed_asset_path(path: impl AsRef<Path>) -> std::path::PathBuf {
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(".zst");
+
+    path.into()
+}
+
+/// Compresses the asset at `source` next to it (see [`get_compressed_asset_path`]), unless the
+/// compressed asset there already decompresses to it, and returns the compressed asset's path.
+#[cfg(feature = "compressed-assets")]
+pub fn compress_asset(source: impl AsRef<Path>) -> Result<std::path::PathBuf, AssetError> {
+    let destination = get_compressed_asset_path(&source);
+    let data = std::fs::read(source).map_err(AssetError::Io)?;
+
+    // Only compress what will decode once decompressed.
+    AssetHeader::from_bytes(&data)?;
+
+    if let Ok(existing) = std::fs::read(&destination) {
+        if decompress_asset(&existing).is_ok_and(|existing| existing == data) {
+            return Ok(destination);
+        }
+    }
+
+    let compressed = zstd::bulk::compress(&data, ASSET_COMPRESSION_LEVEL).map_err(AssetError::Io)?;
+    std::fs::write(&destination, compressed).map_err(AssetError::Io)?;
+
+    Ok(destination)
+}
+
+/// Gets the length of the asset that a compressed asset decompresses to, as recorded in it.
+#[cfg(feature = "compressed-assets")]
+pub fn get_decompressed_asset_len(data: &[u8]) -> Result<usize, AssetError> {
+    let len = match zstd::zstd_safe::get_frame_content_size(data) {
+        Ok(Some(len)) => len,
+        _ => return Err(AssetError::Invalid("the compressed asset does not record its decompressed length".to_string())),
+    };
+
+    if len > MAX_DECOMPRESSED_ASSET_LEN {
+        return Err(AssetError::Invalid(format!("the compressed asset decompresses to {} bytes, more than {}", len, MAX_DECOMPRESSED_ASSET_LEN)));
+    }
+
+    Ok(len as usize)
+}
+
+/// Decompresses a compressed asset into `buffer`, which must be exactly as long as the asset (see
+/// [`get_decompressed_asset_len`]), so that the caller controls where (and how aligned) it lands.
+#[cfg(feature = "compressed-assets")]
+pub fn decompress_asset_into(data: &[u8], buffer: &mut [u8]) -> Result<(), AssetError> {
+    let len = zstd::bulk::decompress_to_buffer(data, buffer).map_err(AssetError::Io)?;
+
+    if len != buffer.len() {
+        return Err(AssetError::Truncated {
+            expected: buffer.len() as u64,
+            found: len as u64,
+        });
+    }
+
+    Ok(())
+}
+
+/// Decompresses a compressed asset.
+#[cfg(feature = "compressed-assets")]
+pub fn decompress_asset(data: &[u8]) -> Result<Vec<u8>, AssetError> {
+    let mut buffer = vec![0; get_decompressed_asset_len(data)?];
+    decompress_asset_into(data, &mut buffer)?;
+
+    Ok(buffer)
+}
+
+// Special encoding / decoding logic for geometries.
+
+/// Whether a decoded value's memory is its own, or borrowed from the bytes of the asset that it was
+/// decoded from (see [`borrow_decode_raw_vec`]), which must never be freed through it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Backing {
+    /// The value owns its memory, and frees it on drop.
+    #[default]
+    Owned,
+    /// The value's arrays point into asset bytes that live for the rest of the program.
+    Borrowed,
+}
+
+/// The backing of the arrays that [`borrow_decode_raw_vec`] returns: borrowed, unless the target's
+/// byte order forces them to be copied.
+#[cfg(feature = "self-contained")]
+const RAW_VEC_BACKING: Backing = if cfg!(target_endian = "big") { Backing::Owned } else { Backing::Borrowed };
 
 /// A wrapped [`Geometry`] that can be encoded and decoded via bincode.
 #[derive(Debug)]
-pub struct EncodableGeometry(pub Geometry<Float>);
+pub struct EncodableGeometry(pub Geometry<Float>, Backing);
+
+impl EncodableGeometry {
+    /// Wraps a geometry that owns its memory.
+    pub fn new(geometry: Geometry<Float>) -> Self {
+        Self(geometry, Backing::Owned)
+    }
+}
 
-// When `owned-decode` is off, geometries are borrow-decoded: their coordinate `Vec`s are
-// reconstructed via `Vec::from_raw_parts` directly over the embedded asset bytes (see
-// `borrow_decode_poly`). Those `Vec`s do not own heap memory, so letting `geo`'s `Vec::drop` run
-// would `dealloc` a pointer into `.rodata` — undefined behavior. We forget the geometry on drop so
-// that never happens; the bytes live in the binary, so there is nothing to free.
+// A borrow-decoded geometry's coordinate `Vec`s are reconstructed via `Vec::from_raw_parts`
+// directly over the asset bytes (see `borrow_decode_poly`). Those `Vec`s do not own heap memory, so
+// letting `geo`'s `Vec::drop` run on them would `dealloc` a pointer into `.rodata` (or a leaked
+// buffer) — undefined behavior. On drop, we forget each of those rings, but free the `Vec`s of
+// rings and polygons that hold them, which the decode did allocate.  Every other geometry (built
+// in memory, owned-decoded, quantized, or of the other float width) owns its memory, and drops
+// normally.
 //
-// SAFETY / INVARIANT: this leak-on-drop is correct *only* because, with `owned-decode` off, every
-// geometry originates from `borrow_decode` (static-backed). The single selector that guarantees
-// this is `decode_binary_data` in `rtz/src/geo/shared.rs` — keep the two in lockstep. With
-// `owned-decode` on, geometries own real allocations and must drop normally, so there is no `Drop`.
-#[cfg(not(feature = "owned-decode"))]
+// SAFETY / INVARIANT: only `borrow_decode` marks a geometry as `Backing::Borrowed`, and it can only
+// borrow from `'static` bytes (see `try_decode_binary_data` in `rtz/src/geo/shared.rs`).
 impl Drop for EncodableGeometry {
     fn drop(&mut self) {
-        let geometry = std::mem::replace(&mut self.0, Geometry::Point(geo::Point::new(0.0, 0.0)));
-        std::mem::forget(geometry);
+        if self.1 == Backing::Owned {
+            return;
+        }
+
+        match std::mem::replace(&mut self.0, Geometry::Point(geo::Point::new(0.0, 0.0))) {
+            Geometry::Polygon(polygon) => forget_borrowed_rings(polygon),
+            Geometry::MultiPolygon(multi) => multi.0.into_iter().for_each(forget_borrowed_rings),
+            geometry => std::mem::forget(geometry),
+        }
+    }
+}
+
+/// Forgets the (borrowed) rings of a borrow-decoded polygon, freeing only the `Vec` of its interiors.
+fn forget_borrowed_rings(polygon: Polygon<Float>) {
+    let (exterior, interiors) = polygon.into_inner();
+
+    std::mem::forget(exterior);
+    interiors.into_iter().for_each(std::mem::forget);
+}
+
+/// The error for a variant that is never encoded (of an enum with two variants), as found in corrupt data.
+#[cfg(feature = "self-contained")]
+fn unexpected_variant(type_name: &'static str, found: usize) -> DecodeError {
+    unexpected_variant_of(type_name, &bincode::error::AllowedEnumVariants::Range { min: 0, max: 1 }, found)
+}
+
+/// The error for a variant that is never encoded (of an enum with the `allowed` variants), as found in corrupt data.
+#[cfg(feature = "self-contained")]
+fn unexpected_variant_of(type_name: &'static str, allowed: &'static bincode::error::AllowedEnumVariants, found: usize) -> DecodeError {
+    DecodeError::UnexpectedVariant {
+        type_name,
+        allowed,
+        found: found.min(u32::MAX as usize) as u32,
     }
 }
 
@@ -610,22 +2361,167 @@ where
     Ok(())
 }
 
+/// The number of fixed-point units per degree that quantized coordinates are stored in (i.e., 1e-7°, about 1 cm,
+/// which is also the resolution that OpenStreetMap stores coordinates at).
+pub const QUANTIZED_UNITS_PER_DEGREE: f64 = 1e7;
+
+/// The variants of an encoded [`EncodableGeometry`]: a polygon and a multi-polygon of raw `f32`
+/// coordinates, then of quantized ones (see [`encode_quantized_poly`]), and then of raw `f64` ones.
+#[cfg(feature = "self-contained")]
+const GEOMETRY_VARIANTS: bincode::error::AllowedEnumVariants = bincode::error::AllowedEnumVariants::Range { min: 0, max: 5 };
+
+/// The polygon variant of an encoded [`EncodableGeometry`] with raw coordinates of this build's [`Float`] width,
+/// which are borrowed from the asset (the multi-polygon variant follows it).
+#[cfg(feature = "self-contained")]
+const RAW_GEOMETRY_VARIANT: usize = if FLOAT_BITS == 32 { 0 } else { 4 };
+
+/// The polygon variant of an encoded [`EncodableGeometry`] with raw coordinates of the other width,
+/// which are widened or narrowed to [`Float`] on decode (see [`decode_converted_ring`]).
+#[cfg(feature = "self-contained")]
+const CONVERTED_GEOMETRY_VARIANT: usize = 4 - RAW_GEOMETRY_VARIANT;
+
+/// The float of the other width than [`Float`], which the [`CONVERTED_GEOMETRY_VARIANT`] stores.
+#[cfg(all(feature = "self-contained", not(feature = "double-precision")))]
+type OtherFloat = f64;
+/// The float of the other width than [`Float`], which the [`CONVERTED_GEOMETRY_VARIANT`] stores.
+#[cfg(all(feature = "self-contained", feature = "double-precision"))]
+type OtherFloat = f32;
+
+/// Quantizes a coordinate to a whole number of [`QUANTIZED_UNITS_PER_DEGREE`].
+#[cfg(feature = "self-contained")]
+fn quantize(value: Float) -> Result<i32, EncodeError> {
+    let units = (value as f64 * QUANTIZED_UNITS_PER_DEGREE).round();
+
+    if !(i32::MIN as f64..=i32::MAX as f64).contains(&units) {
+        return Err(EncodeError::OtherString(format!("The coordinate {} cannot be quantized.", value)));
+    }
+
+    Ok(units as i32)
+}
+
+/// Dequantizes a coordinate from a whole number of [`QUANTIZED_UNITS_PER_DEGREE`].
+#[cfg(feature = "self-contained")]
+fn dequantize(units: i32) -> Float {
+    (units as f64 / QUANTIZED_UNITS_PER_DEGREE) as Float
+}
+
+/// Encode a ring as its length, and then the zigzag, LEB128 varint deltas of its quantized
+/// coordinates from the previous ones (from `(0, 0)`, for the first), as a byte array that is
+/// padded with zeros to keep what follows it aligned.
+///
+/// Neighbouring vertices are close, so most deltas take one or two bytes, rather than the four or
+/// eight of a raw [`Float`].
+#[cfg(feature = "self-contained")]
+fn encode_quantized_ring<E>(ring: &[Coord<Float>], encoder: &mut E) -> Result<(), EncodeError>
+where
+    E: Encoder,
+{
+    let mut data = Vec::with_capacity(ring.len() * 4);
+    let mut previous = (0i64, 0i64);
+
+    for point in ring {
+        let current = (quantize(point.x)? as i64, quantize(point.y)? as i64);
+
+        push_varint(&mut data, current.0 - previous.0);
+        push_varint(&mut data, current.1 - previous.1);
+
+        previous = current;
+    }
+
+    let alignment = std::mem::align_of::<u64>();
+    data.resize(data.len().next_multiple_of(alignment), 0);
+
+    ring.len().encode(encoder)?;
+    data.encode(encoder)
+}
+
+/// Push a zigzag, LEB128 varint.
+#[cfg(feature = "self-contained")]
+fn push_varint(data: &mut Vec<u8>, value: i64) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+
+    while value >= 0x80 {
+        data.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    data.push(value as u8);
+}
+
+/// Read a zigzag, LEB128 varint from the front of `data`, advancing past it.
+#[cfg(feature = "self-contained")]
+fn take_varint(data: &mut &[u8]) -> Result<i64, DecodeError> {
+    let mut value = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data.split_first().ok_or(DecodeError::UnexpectedEnd { additional: 1 })?;
+        *data = rest;
+
+        value |= ((byte & 0x7f) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok((value >> 1) as i64 ^ -((value & 1) as i64));
+        }
+    }
+
+    Err(DecodeError::OtherString("A varint is longer than 64 bits.".to_string()))
+}
+
+/// Encode a polygon like [`encode_poly`], but with each ring quantized (see [`encode_quantized_ring`]).
+#[cfg(feature = "self-contained")]
+fn encode_quantized_poly<E>(polygon: &Polygon<Float>, encoder: &mut E) -> Result<(), EncodeError>
+where
+    E: Encoder,
+{
+    encode_quantized_ring(&polygon.exterior().0, encoder)?;
+
+    let interiors = polygon.interiors();
+
+    // Encode the number of interiors.
+    interiors.len().encode(encoder)?;
+
+    // Encode the interiors.
+    for interior in interiors {
+        encode_quantized_ring(&interior.0, encoder)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "self-contained")]
 impl Encode for EncodableGeometry {
     fn encode<E>(&self, encoder: &mut E) -> Result<(), EncodeError>
     where
         E: Encoder,
     {
+        self.encode_with(encoder, cfg!(feature = "quantized"))
+    }
+}
+
+/// Encodes a polygon's rings (i.e., [`encode_poly`] or [`encode_quantized_poly`]).
+#[cfg(feature = "self-contained")]
+type PolygonEncoder<E> = fn(&Polygon<Float>, &mut E) -> Result<(), EncodeError>;
+
+#[cfg(feature = "self-contained")]
+impl EncodableGeometry {
+    /// Encode the geometry with raw coordinates (as the variants of this build's [`Float`] width), or quantized ones
+    /// (as variants `2` and `3`), either of which any build reads.
+    fn encode_with<E>(&self, encoder: &mut E, quantized: bool) -> Result<(), EncodeError>
+    where
+        E: Encoder,
+    {
+        let (variant, encode_polygon): (usize, PolygonEncoder<E>) = if quantized { (2, encode_quantized_poly) } else { (RAW_GEOMETRY_VARIANT, encode_poly) };
+
         match &self.0 {
             Geometry::Polygon(polygon) => {
                 // Encode the variant.
-                0usize.encode(encoder)?;
+                variant.encode(encoder)?;
 
-                encode_poly(polygon, encoder)?;
+                encode_polygon(polygon, encoder)?;
             }
             Geometry::MultiPolygon(multi_polygon) => {
                 // Encode the variant.
-                1usize.encode(encoder)?;
+                (variant + 1).encode(encoder)?;
 
                 let polygons = &multi_polygon.0;
 
@@ -634,10 +2530,10 @@ impl Encode for EncodableGeometry {
 
                 // Encode the polygons.
                 for polygon in polygons {
-                    encode_poly(polygon, encoder)?;
+                    encode_polygon(polygon, encoder)?;
                 }
             }
-            _ => panic!("Unsupported geometry variant."),
+            _ => return Err(EncodeError::Other("Only polygons and multi-polygons can be encoded.")),
         }
 
         Ok(())
@@ -649,67 +2545,278 @@ fn decode_poly<D>(decoder: &mut D) -> Result<Polygon<Float>, DecodeError>
 where
     D: Decoder,
 {
-    let exterior_len = usize::decode(decoder)?;
+    decode_poly_with(decoder, decode_ring)
+}
+
+/// Decode a polygon's rings with `decode_ring`.
+#[cfg(feature = "self-contained")]
+fn decode_poly_with<D>(decoder: &mut D, decode_ring: fn(&mut D) -> Result<LineString<Float>, DecodeError>) -> Result<Polygon<Float>, DecodeError>
+where
+    D: Decoder,
+{
+    let exterior = decode_ring(decoder)?;
+
+    let interior_len = usize::decode(decoder)?;
+    decoder.claim_container_read::<LineString<Float>>(interior_len)?;
+
+    let mut interiors = Vec::with_capacity(interior_len);
+
+    for _ in 0..interior_len {
+        decoder.unclaim_bytes_read(std::mem::size_of::<LineString<Float>>());
+
+        interiors.push(decode_ring(decoder)?);
+    }
+
+    Ok(Polygon::new(exterior, interiors))
+}
+
+#[cfg(feature = "self-contained")]
+fn decode_ring<D>(decoder: &mut D) -> Result<LineString<Float>, DecodeError>
+where
+    D: Decoder,
+{
+    let len = usize::decode(decoder)?;
+    decoder.claim_container_read::<Coord<Float>>(len)?;
+
+    let mut ring = Vec::with_capacity(len);
 
-    let mut exterior = Vec::with_capacity(exterior_len);
+    for _ in 0..len {
+        decoder.unclaim_bytes_read(std::mem::size_of::<Coord<Float>>());
 
-    for _ in 0..exterior_len {
         let x = Float::decode(decoder)?;
         let y = Float::decode(decoder)?;
 
-        exterior.push(Coord { x, y });
+        ring.push(Coord { x, y });
+    }
+
+    Ok(LineString(ring))
+}
+
+/// Decode a polygon encoded by [`encode_quantized_poly`], into owned rings.
+#[cfg(feature = "self-contained")]
+fn decode_quantized_poly<D>(decoder: &mut D) -> Result<Polygon<Float>, DecodeError>
+where
+    D: Decoder,
+{
+    decode_poly_with(decoder, decode_quantized_ring)
+}
+
+/// Decode a polygon encoded by [`encode_poly`] in a build of the other float width, into owned rings.
+#[cfg(feature = "self-contained")]
+fn decode_converted_poly<D>(decoder: &mut D) -> Result<Polygon<Float>, DecodeError>
+where
+    D: Decoder,
+{
+    decode_poly_with(decoder, decode_converted_ring)
+}
+
+/// Decode a ring of [`OtherFloat`]s, widening or narrowing each of them to a [`Float`].
+///
+/// Narrowing `f64`s rounds them to the nearest `f32`, which is what generating the asset in an `f32` build does.
+#[cfg(feature = "self-contained")]
+fn decode_converted_ring<D>(decoder: &mut D) -> Result<LineString<Float>, DecodeError>
+where
+    D: Decoder,
+{
+    let len = usize::decode(decoder)?;
+    decoder.claim_container_read::<Coord<OtherFloat>>(len)?;
+
+    let mut ring = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        decoder.unclaim_bytes_read(std::mem::size_of::<Coord<OtherFloat>>());
+
+        let x = OtherFloat::decode(decoder)?;
+        let y = OtherFloat::decode(decoder)?;
+
+        ring.push(Coord { x: x as Float, y: y as Float });
+    }
+
+    Ok(LineString(ring))
+}
+
+#[cfg(feature = "self-contained")]
+fn decode_quantized_ring<D>(decoder: &mut D) -> Result<LineString<Float>, DecodeError>
+where
+    D: Decoder,
+{
+    let len = usize::decode(decoder)?;
+    let data = Vec::<u8>::decode(decoder)?;
+
+    // Every coordinate takes at least two bytes, so a length past that is corrupt (and is not allocated).
+    if len > data.len() / 2 {
+        return Err(DecodeError::OtherString(format!("A quantized ring of {} coordinates does not fit in {} bytes.", len, data.len())));
+    }
+
+    let mut ring = Vec::with_capacity(len);
+    let mut rest = data.as_slice();
+    let mut current = (0i64, 0i64);
+
+    for _ in 0..len {
+        current = (current.0.wrapping_add(take_varint(&mut rest)?), current.1.wrapping_add(take_varint(&mut rest)?));
+
+        let (Ok(x), Ok(y)) = (i32::try_from(current.0), i32::try_from(current.1)) else {
+            return Err(DecodeError::OtherString("A quantized coordinate is out of range.".to_string()));
+        };
+
+        ring.push(Coord { x: dequantize(x), y: dequantize(y) });
     }
 
+    if rest.len() >= std::mem::align_of::<u64>() || rest.iter().any(|&byte| byte != 0) {
+        return Err(DecodeError::OtherString(format!("A quantized ring of {} coordinates has {} trailing bytes.", len, rest.len())));
+    }
+
+    Ok(LineString(ring))
+}
+
+/// Decode a multi-polygon's polygons with `decode_poly`.
+#[cfg(feature = "self-contained")]
+fn decode_multi_poly<D>(decoder: &mut D, decode_poly: fn(&mut D) -> Result<Polygon<Float>, DecodeError>) -> Result<MultiPolygon<Float>, DecodeError>
+where
+    D: Decoder,
+{
+    let polygon_len = usize::decode(decoder)?;
+    decoder.claim_container_read::<Polygon<Float>>(polygon_len)?;
+
+    let mut polygons = Vec::with_capacity(polygon_len);
+
+    for _ in 0..polygon_len {
+        decoder.unclaim_bytes_read(std::mem::size_of::<Polygon<Float>>());
+
+        polygons.push(decode_poly(decoder)?);
+    }
+
+    Ok(MultiPolygon::new(polygons))
+}
+
+#[cfg(feature = "self-contained")]
+fn borrow_decode_poly<'de, D>(decoder: &mut D) -> Result<ManuallyDrop<Polygon<Float>>, DecodeError>
+where
+    D: BorrowDecoder<'de>,
+{
+    // Until the polygon is wrapped in an `EncodableGeometry`, nothing must drop its rings, even if
+    // a later ring fails to decode.
+    let exterior = borrow_decode_ring(decoder)?;
+
     let interior_len = usize::decode(decoder)?;
+    decoder.claim_container_read::<LineString<Float>>(interior_len)?;
 
-    let mut interiors = Vec::with_capacity(interior_len);
+    let mut interiors = ManuallyDrop::new(Vec::with_capacity(interior_len));
 
     for _ in 0..interior_len {
-        let interior_len = usize::decode(decoder)?;
+        decoder.unclaim_bytes_read(std::mem::size_of::<LineString<Float>>());
 
-        let mut interior = Vec::with_capacity(interior_len);
+        interiors.push(ManuallyDrop::into_inner(borrow_decode_ring(decoder)?));
+    }
 
-        for _ in 0..interior_len {
-            let x = Float::decode(decoder)?;
-            let y = Float::decode(decoder)?;
+    Ok(ManuallyDrop::new(Polygon::new(ManuallyDrop::into_inner(exterior), ManuallyDrop::into_inner(interiors))))
+}
 
-            interior.push(Coord { x, y });
-        }
+#[cfg(feature = "self-contained")]
+fn borrow_decode_ring<'de, D>(decoder: &mut D) -> Result<ManuallyDrop<LineString<Float>>, DecodeError>
+where
+    D: BorrowDecoder<'de>,
+{
+    let len = usize::decode(decoder)?;
+    let ring = ManuallyDrop::new(LineString(borrow_decode_raw_vec::<D, Coord<Float>>(decoder, len)?));
 
-        interiors.push(LineString(interior));
+    // `Polygon::new` closes open rings by pushing onto them, which would reallocate a `Vec` that
+    // does not own its memory: every encoded ring is closed, so one that is not is corrupt.
+    if !ring.0.is_empty() && !ring.is_closed() {
+        return Err(DecodeError::OtherString(format!("A ring of {} coordinates is not closed.", ring.0.len())));
     }
 
-    Ok(Polygon::new(LineString(exterior), interiors))
+    Ok(ring)
+}
+
+/// Plain data that is valid for any bits, and laid out in memory as a run of scalars of the same width
+/// (coordinates, ids, and the lookup's nodes and offsets), so that arrays of it can be borrow-decoded
+/// (see [`borrow_decode_raw_vec`]).
+#[cfg(feature = "self-contained")]
+trait RawData: Copy {
+    /// The width, in bytes, of each of the scalars that make up a value.
+    const SCALAR_SIZE: usize;
+}
+
+#[cfg(feature = "self-contained")]
+impl RawData for Id {
+    const SCALAR_SIZE: usize = std::mem::size_of::<Id>();
+}
+
+#[cfg(feature = "self-contained")]
+impl RawData for Coord<Float> {
+    const SCALAR_SIZE: usize = std::mem::size_of::<Float>();
+}
+
+#[cfg(feature = "self-contained")]
+impl RawData for LookupNode {
+    const SCALAR_SIZE: usize = std::mem::size_of::<u32>();
+}
+
+#[cfg(feature = "self-contained")]
+impl RawData for CellOffsets {
+    const SCALAR_SIZE: usize = std::mem::size_of::<u32>();
 }
 
+/// Borrow-decode `len` values of `T` straight over the decoder's bytes, as a `Vec` that does not
+/// own its memory (see `EncodableGeometry`'s `Drop`).
+///
+/// The length is overflow-checked, and the bytes are bounds-checked (by the reader) and checked to
+/// be aligned for `T`, so that corrupt data is an error rather than undefined behavior.
+///
+/// On a big-endian target, the (little-endian, see [`AssetByteOrder`]) bytes cannot be borrowed as
+/// they are, so they are copied and byte-swapped into owned memory (see [`RAW_VEC_BACKING`]).
 #[cfg(feature = "self-contained")]
-fn borrow_decode_poly<'de, D>(decoder: &mut D) -> Result<Polygon<Float>, DecodeError>
+fn borrow_decode_raw_vec<'de, D, T>(decoder: &mut D, len: usize) -> Result<Vec<T>, DecodeError>
 where
     D: BorrowDecoder<'de>,
+    T: RawData,
 {
-    let exterior_len = usize::decode(decoder)?;
-    let exterior_slice = decoder.borrow_reader().take_bytes(exterior_len * std::mem::size_of::<Float>() * 2)?;
+    let size = len
+        .checked_mul(std::mem::size_of::<T>())
+        .ok_or_else(|| DecodeError::OtherString(format!("An array of {} `{}`s is too long.", len, std::any::type_name::<T>())))?;
+    let slice = decoder.borrow_reader().take_bytes(size)?;
+
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+
+    if cfg!(target_endian = "big") {
+        return Ok(copy_swapped_raw_vec(slice));
+    }
+
+    if slice.as_ptr().align_offset(std::mem::align_of::<T>()) != 0 {
+        return Err(DecodeError::OtherString(format!("An array of `{}`s is not aligned.", std::any::type_name::<T>())));
+    }
 
     // SAFETY: Perform unholy rites, and summon the devil, lol.
     // Basically, this is an extreme optimization to prevent loading huge amounts of data into memory that are already
-    // in memory as part of the binary assets.
-    let exterior = unsafe { Vec::from_raw_parts(exterior_slice.as_ptr() as *mut Coord<Float>, exterior_len, exterior_len) };
-
-    let interior_len = usize::decode(decoder)?;
+    // in memory as part of the binary assets.  The bytes are in bounds, aligned, in the target's byte order, and valid
+    // for any `T`, and they are never freed through the `Vec` (see `Backing::Borrowed`).
+    Ok(unsafe { Vec::from_raw_parts(slice.as_ptr() as *mut T, len, len) })
+}
 
-    let mut interiors = Vec::with_capacity(interior_len);
+/// Copy the values of `T` in `bytes`, which are in the opposite byte order to the target's, swapping
+/// each of their scalars into the target's order.
+#[cfg(feature = "self-contained")]
+fn copy_swapped_raw_vec<T>(bytes: &[u8]) -> Vec<T>
+where
+    T: RawData,
+{
+    let len = bytes.len() / std::mem::size_of::<T>();
+    let mut vec = Vec::<T>::with_capacity(len);
 
-    for _ in 0..interior_len {
-        let interior_len = usize::decode(decoder)?;
-        let interior_slice = decoder.borrow_reader().take_bytes(interior_len * std::mem::size_of::<Float>() * 2)?;
+    // SAFETY: The `Vec` has room for `len` values, which span `bytes.len()` bytes, and any bits are a valid `T`.
+    unsafe {
+        let data = std::slice::from_raw_parts_mut(vec.as_mut_ptr() as *mut u8, bytes.len());
 
-        // SAFETY: Perform unholy rites again: see above.
-        let interior = unsafe { Vec::from_raw_parts(interior_slice.as_ptr() as *mut Coord<Float>, interior_len, interior_len) };
+        data.copy_from_slice(bytes);
+        data.chunks_exact_mut(T::SCALAR_SIZE).for_each(<[u8]>::reverse);
 
-        interiors.push(LineString(interior));
+        vec.set_len(len);
     }
 
-    Ok(Polygon::new(LineString(exterior), interiors))
+    vec
 }
 
 #[cfg(feature = "self-contained")]
@@ -721,28 +2828,20 @@ impl<Context> Decode<Context> for EncodableGeometry {
         let variant = usize::decode(decoder)?;
 
         let geometry = match variant {
-            0 => {
+            2 => Geometry::Polygon(decode_quantized_poly(decoder)?),
+            3 => Geometry::MultiPolygon(decode_multi_poly(decoder, decode_quantized_poly)?),
+            variant if variant == RAW_GEOMETRY_VARIANT => {
                 let polygon = decode_poly(decoder)?;
 
                 Geometry::Polygon(polygon)
             }
-            1 => {
-                let polygon_len = usize::decode(decoder)?;
-
-                let mut polygons = Vec::with_capacity(polygon_len);
-
-                for _ in 0..polygon_len {
-                    let polygon = decode_poly(decoder)?;
-
-                    polygons.push(polygon);
-                }
-
-                Geometry::MultiPolygon(MultiPolygon::new(polygons))
-            }
-            _ => panic!("Unsupported geometry variant."),
+            variant if variant == RAW_GEOMETRY_VARIANT + 1 => Geometry::MultiPolygon(decode_multi_poly(decoder, decode_poly)?),
+            variant if variant == CONVERTED_GEOMETRY_VARIANT => Geometry::Polygon(decode_converted_poly(decoder)?),
+            variant if variant == CONVERTED_GEOMETRY_VARIANT + 1 => Geometry::MultiPolygon(decode_multi_poly(decoder, decode_converted_poly)?),
+            found => return Err(unexpected_variant_of("EncodableGeometry", &GEOMETRY_VARIANTS, found)),
         };
 
-        Ok(EncodableGeometry(geometry))
+        Ok(EncodableGeometry::new(geometry))
     }
 }
 
@@ -754,35 +2853,43 @@ impl<'de, Context> BorrowDecode<'de, Context> for EncodableGeometry {
     {
         let variant = usize::decode(decoder)?;
 
-        let geometry = match variant {
-            0 => {
+        let (geometry, backing) = match variant {
+            variant if variant == RAW_GEOMETRY_VARIANT => {
                 let polygon = borrow_decode_poly(decoder)?;
 
-                Geometry::Polygon(polygon)
+                (Geometry::Polygon(ManuallyDrop::into_inner(polygon)), RAW_VEC_BACKING)
             }
-            1 => {
+            variant if variant == RAW_GEOMETRY_VARIANT + 1 => {
                 let polygon_len = usize::decode(decoder)?;
+                decoder.claim_container_read::<Polygon<Float>>(polygon_len)?;
 
-                let mut polygons = Vec::with_capacity(polygon_len);
+                let mut polygons = ManuallyDrop::new(Vec::with_capacity(polygon_len));
 
                 for _ in 0..polygon_len {
+                    decoder.unclaim_bytes_read(std::mem::size_of::<Polygon<Float>>());
+
                     let polygon = borrow_decode_poly(decoder)?;
 
-                    polygons.push(polygon);
+                    polygons.push(ManuallyDrop::into_inner(polygon));
                 }
 
-                Geometry::MultiPolygon(MultiPolygon::new(polygons))
+                (Geometry::MultiPolygon(MultiPolygon::new(ManuallyDrop::into_inner(polygons))), RAW_VEC_BACKING)
             }
-            _ => panic!("Unsupported geometry variant."),
+            // Quantized coordinates, and raw ones of the other width, cannot be borrowed, so they are decoded into owned memory.
+            2 => (Geometry::Polygon(decode_quantized_poly(decoder)?), Backing::Owned),
+            3 => (Geometry::MultiPolygon(decode_multi_poly(decoder, decode_quantized_poly)?), Backing::Owned),
+            variant if variant == CONVERTED_GEOMETRY_VARIANT => (Geometry::Polygon(decode_converted_poly(decoder)?), Backing::Owned),
+            variant if variant == CONVERTED_GEOMETRY_VARIANT + 1 => (Geometry::MultiPolygon(decode_multi_poly(decoder, decode_converted_poly)?), Backing::Owned),
+            found => return Err(unexpected_variant_of("EncodableGeometry", &GEOMETRY_VARIANTS, found)),
         };
 
-        Ok(EncodableGeometry(geometry))
+        Ok(EncodableGeometry(geometry, backing))
     }
 }
 
 /// A wrapped ['Vec`] that can be encoded and decoded via bincode.
 #[derive(Debug)]
-pub struct EncodableIds(pub Vec<Id>);
+pub struct EncodableIds(pub Vec<Id>, Backing);
 
 impl Deref for EncodableIds {
     type Target = Vec<Id>;
@@ -792,24 +2899,211 @@ impl Deref for EncodableIds {
     }
 }
 
-impl AsRef<[Id]> for EncodableIds {
-    fn as_ref(&self) -> &[Id] {
+impl AsRef<[Id]> for EncodableIds {
+    fn as_ref(&self) -> &[Id] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "self-contained")]
+impl Encode for EncodableIds {
+    fn encode<E>(&self, encoder: &mut E) -> Result<(), EncodeError>
+    where
+        E: Encoder,
+    {
+        // Encode the exterior length.
+        self.0.len().encode(encoder)?;
+
+        // Encode the exterior points.
+        for x in &self.0 {
+            x.encode(encoder)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "self-contained")]
+impl<Context> Decode<Context> for EncodableIds {
+    fn decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
+    where
+        D: Decoder,
+    {
+        let len = usize::decode(decoder)?;
+        decoder.claim_container_read::<Id>(len)?;
+
+        let mut vec = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            decoder.unclaim_bytes_read(std::mem::size_of::<Id>());
+
+            let x = Id::decode(decoder)?;
+
+            vec.push(x);
+        }
+
+        Ok(EncodableIds(vec, Backing::Owned))
+    }
+}
+
+#[cfg(feature = "self-contained")]
+impl<'de, Context> BorrowDecode<'de, Context> for EncodableIds {
+    fn borrow_decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
+    where
+        D: BorrowDecoder<'de>,
+    {
+        let len = usize::decode(decoder)?;
+
+        Ok(EncodableIds(borrow_decode_raw_vec(decoder, len)?, RAW_VEC_BACKING))
+    }
+}
+
+// Borrow-decoded ids point into the asset bytes, so, like geometries' rings, they are forgotten rather than freed: see `EncodableGeometry`'s `Drop`.
+impl Drop for EncodableIds {
+    fn drop(&mut self) {
+        if self.1 == Backing::Borrowed {
+            std::mem::forget(std::mem::take(&mut self.0));
+        }
+    }
+}
+
+// Special encoding / decoding logic for lookup cells.
+
+/// A node in a lookup cell's adaptive quadtree.
+///
+/// The layout is fixed (`repr(C)`, three `u32`s) so that the node array can be borrow-decoded in place.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct LookupNode {
+    /// The index of the first of this node's four children (ordered as in [`get_quadrant_rect`]), or `0` for a leaf.
+    ///
+    /// The root is always at index `0`, so it can never be a child.
+    pub children: u32,
+    /// The offset of this leaf's candidates in [`LookupCell::leaf_ids`].
+    pub start: u32,
+    /// The number of candidates in this leaf.
+    pub len: u32,
+}
+
+/// A wrapped [`Vec`] of [`LookupNode`]s that can be encoded and decoded via bincode.
+#[derive(Debug)]
+pub struct EncodableNodes(pub Vec<LookupNode>, Backing);
+
+impl Deref for EncodableNodes {
+    type Target = Vec<LookupNode>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "self-contained")]
+impl Encode for EncodableNodes {
+    fn encode<E>(&self, encoder: &mut E) -> Result<(), EncodeError>
+    where
+        E: Encoder,
+    {
+        self.0.len().encode(encoder)?;
+
+        for node in &self.0 {
+            node.children.encode(encoder)?;
+            node.start.encode(encoder)?;
+            node.len.encode(encoder)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "self-contained")]
+impl<Context> Decode<Context> for EncodableNodes {
+    fn decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
+    where
+        D: Decoder,
+    {
+        let len = usize::decode(decoder)?;
+        decoder.claim_container_read::<LookupNode>(len)?;
+
+        let mut vec = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            decoder.unclaim_bytes_read(std::mem::size_of::<LookupNode>());
+
+            let children = u32::decode(decoder)?;
+            let start = u32::decode(decoder)?;
+            let len = u32::decode(decoder)?;
+
+            vec.push(LookupNode { children, start, len });
+        }
+
+        Ok(EncodableNodes(vec, Backing::Owned))
+    }
+}
+
+#[cfg(feature = "self-contained")]
+impl<'de, Context> BorrowDecode<'de, Context> for EncodableNodes {
+    fn borrow_decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
+    where
+        D: BorrowDecoder<'de>,
+    {
+        let len = usize::decode(decoder)?;
+
+        // `LookupNode` is `repr(C)` over three `u32`s, which is exactly how they are encoded.
+        Ok(EncodableNodes(borrow_decode_raw_vec(decoder, len)?, RAW_VEC_BACKING))
+    }
+}
+
+// Borrow-decoded nodes point into the asset bytes, so they are forgotten rather than freed: see `EncodableGeometry`'s `Drop`.
+impl Drop for EncodableNodes {
+    fn drop(&mut self) {
+        if self.1 == Backing::Borrowed {
+            std::mem::forget(std::mem::take(&mut self.0));
+        }
+    }
+}
+
+/// Where a lookup cell's data starts in each of the [`EncodableLookup`]'s arrays.
+///
+/// A cell's data ends where the next cell's starts.  The layout is fixed (`repr(C)`, four `u32`s)
+/// so that the table of offsets can be borrow-decoded in place.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct CellOffsets {
+    /// The offset of the cell's ids in [`EncodableLookup::ids`].
+    pub ids: u32,
+    /// The offset of the cell's quadtree in [`EncodableLookup::nodes`].
+    pub nodes: u32,
+    /// The offset of the cell's leaf candidates in [`EncodableLookup::leaf_ids`].
+    pub leaf_ids: u32,
+    /// The offset of the cell's fragment ids in [`EncodableLookup::fragment_ids`].
+    pub fragment_ids: u32,
+}
+
+/// A wrapped [`Vec`] of [`CellOffsets`] that can be encoded and decoded via bincode.
+#[derive(Debug)]
+pub struct EncodableCellOffsets(pub Vec<CellOffsets>, Backing);
+
+impl Deref for EncodableCellOffsets {
+    type Target = Vec<CellOffsets>;
+
+    fn deref(&self) -> &Self::Target {
         &self.0
     }
 }
 
 #[cfg(feature = "self-contained")]
-impl Encode for EncodableIds {
+impl Encode for EncodableCellOffsets {
     fn encode<E>(&self, encoder: &mut E) -> Result<(), EncodeError>
     where
         E: Encoder,
     {
-        // Encode the exterior length.
         self.0.len().encode(encoder)?;
 
-        // Encode the exterior points.
-        for x in &self.0 {
-            x.encode(encoder)?;
+        for offsets in &self.0 {
+            offsets.ids.encode(encoder)?;
+            offsets.nodes.encode(encoder)?;
+            offsets.leaf_ids.encode(encoder)?;
+            offsets.fragment_ids.encode(encoder)?;
         }
 
         Ok(())
@@ -817,38 +3111,345 @@ impl Encode for EncodableIds {
 }
 
 #[cfg(feature = "self-contained")]
-impl<Context> Decode<Context> for EncodableIds {
+impl<Context> Decode<Context> for EncodableCellOffsets {
     fn decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
     where
         D: Decoder,
     {
         let len = usize::decode(decoder)?;
+        decoder.claim_container_read::<CellOffsets>(len)?;
 
         let mut vec = Vec::with_capacity(len);
 
         for _ in 0..len {
-            let x = Id::decode(decoder)?;
+            decoder.unclaim_bytes_read(std::mem::size_of::<CellOffsets>());
 
-            vec.push(x);
+            let ids = u32::decode(decoder)?;
+            let nodes = u32::decode(decoder)?;
+            let leaf_ids = u32::decode(decoder)?;
+            let fragment_ids = u32::decode(decoder)?;
+
+            vec.push(CellOffsets { ids, nodes, leaf_ids, fragment_ids });
         }
 
-        Ok(EncodableIds(vec))
+        Ok(EncodableCellOffsets(vec, Backing::Owned))
     }
 }
 
 #[cfg(feature = "self-contained")]
-impl<'de, Context> BorrowDecode<'de, Context> for EncodableIds {
+impl<'de, Context> BorrowDecode<'de, Context> for EncodableCellOffsets {
     fn borrow_decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
     where
         D: BorrowDecoder<'de>,
     {
         let len = usize::decode(decoder)?;
-        let slice = decoder.borrow_reader().take_bytes(len * std::mem::size_of::<Id>())?;
 
-        // SAFETY: Perform unholy rites again: see above.
-        let vec = unsafe { Vec::from_raw_parts(slice.as_ptr() as *mut Id, len, len) };
+        // `CellOffsets` is `repr(C)` over four `u32`s, which is exactly how they are encoded.
+        Ok(EncodableCellOffsets(borrow_decode_raw_vec(decoder, len)?, RAW_VEC_BACKING))
+    }
+}
+
+// Borrow-decoded offsets point into the asset bytes, so they are forgotten rather than freed: see `EncodableGeometry`'s `Drop`.
+impl Drop for EncodableCellOffsets {
+    fn drop(&mut self) {
+        if self.1 == Backing::Borrowed {
+            std::mem::forget(std::mem::take(&mut self.0));
+        }
+    }
+}
+
+/// The candidates of a lookup cell's quadtree leaf (see [`LookupCell::candidates_at`]).
+#[derive(Debug, Clone, Copy)]
+pub struct LeafCandidates<'a> {
+    /// Every item that intersects the leaf, in item order.
+    pub ids: &'a [Id],
+    /// For each of `ids`, the id of its geometry clipped to the leaf in [`EncodableLookup::fragments`],
+    /// or [`NO_FRAGMENT`] if it covers the whole leaf.
+    pub fragment_ids: &'a [Id],
+}
+
+impl LeafCandidates<'_> {
+    /// Whether `ids[k]` fully covers the leaf, so any point in the leaf is known to be in it without a `contains` check.
+    pub fn is_covering(&self, k: usize) -> bool {
+        self.fragment_ids[k] == NO_FRAGMENT
+    }
+
+    /// Iterate over the `(id, fragment id)` pairs of the leaf, where the fragment id is `None` for a covering item.
+    pub fn iter(&self) -> impl Iterator<Item = (Id, Option<Id>)> + '_ {
+        self.ids
+            .iter()
+            .zip(self.fragment_ids)
+            .map(|(&id, &fragment_id)| (id, (fragment_id != NO_FRAGMENT).then_some(fragment_id)))
+    }
+}
+
+/// A single 1x1 degree cell of the lookup cache, borrowed from the [`EncodableLookup`]'s cell table.
+///
+/// `ids` holds every item that intersects the cell.  Busy cells additionally carry an adaptive
+/// quadtree (`nodes`, rooted at index `0`) whose leaves narrow that down to the items that
+/// intersect a much smaller square, so a lookup only runs `contains` on those.  Cells that
+/// never needed subdividing have no nodes, and their leaf _is_ `ids`.
+///
+/// Every leaf candidate refers to its geometry clipped to the leaf (see [`clip_geometry_to_rect`]),
+/// so that `contains` runs against a few nearby edges rather than, say, a whole continent; or to
+/// no fragment at all if it covers the leaf (e.g., open ocean, or the country around a city), so
+/// that a lookup can skip `contains` entirely.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LookupCell<'a> {
+    /// Every item that intersects the cell, in item order.
+    pub ids: &'a [Id],
+    /// The cell's quadtree, or empty if the cell is not subdivided.
+    pub nodes: &'a [LookupNode],
+    /// The concatenated candidates of every leaf in `nodes`.
+    pub leaf_ids: &'a [Id],
+    /// The fragment ids (see [`LeafCandidates::fragment_ids`]) of the leaf candidates: of `leaf_ids`
+    /// if the cell is subdivided, and of `ids` otherwise.
+    pub fragment_ids: &'a [Id],
+}
+
+impl<'a> LookupCell<'a> {
+    /// Get the candidates of the quadtree leaf containing `(xf, yf)`, where `(x, y)` is the cell's key.
+    pub fn candidates_at(&self, (x, y): RoundLngLat, xf: Float, yf: Float) -> LeafCandidates<'a> {
+        if self.nodes.is_empty() {
+            return LeafCandidates {
+                ids: self.ids,
+                fragment_ids: self.fragment_ids,
+            };
+        }
+
+        let mut rect = get_cell_rect(x, y);
+        let mut node = self.nodes[0];
+
+        while node.children != 0 {
+            let k = get_quadrant_index(rect, xf, yf);
+
+            rect = get_quadrant_rect(rect, k);
+            node = self.nodes[node.children as usize + k];
+        }
+
+        let range = node.start as usize..(node.start + node.len) as usize;
+
+        LeafCandidates {
+            ids: &self.leaf_ids[range.clone()],
+            fragment_ids: &self.fragment_ids[range],
+        }
+    }
+}
+
+impl Deref for LookupCell<'_> {
+    type Target = [Id];
+
+    fn deref(&self) -> &Self::Target {
+        self.ids
+    }
+}
+
+impl AsRef<[Id]> for LookupCell<'_> {
+    fn as_ref(&self) -> &[Id] {
+        self.ids
+    }
+}
+
+/// A lookup cell under construction (see [`LookupCell`]), with the fragments that its fragment ids index.
+struct CellParts {
+    ids: Vec<Id>,
+    nodes: Vec<LookupNode>,
+    leaves: CellLeaves,
+}
+
+impl CellParts {
+    /// Build a cell (and its quadtree) from the candidates that intersect its [`Rect`].
+    fn from_candidates(candidates: Vec<NodeCandidate>, rect: Rect<Float>) -> CellParts {
+        let ids = candidates.iter().map(|c| c.id).collect::<Vec<_>>();
+
+        let mut nodes = vec![LookupNode::default()];
+        let mut leaves = CellLeaves::default();
+
+        subdivide_lookup_node(candidates, rect, 0, 0, &mut nodes, &mut leaves);
+
+        // A root that never split says nothing that `ids` doesn't already.
+        if nodes.len() == 1 {
+            nodes.clear();
+            leaves.ids.clear();
+        }
+
+        CellParts { ids, nodes, leaves }
+    }
+
+    /// Borrow the cell as it will be stored.
+    fn as_cell(&self) -> LookupCell<'_> {
+        LookupCell {
+            ids: &self.ids,
+            nodes: &self.nodes,
+            leaf_ids: &self.leaves.ids,
+            fragment_ids: &self.leaves.fragment_ids,
+        }
+    }
+}
+
+/// The lookup cache: a table of every cell, and the clipped geometry fragments that they refer to.
+///
+/// Every cell on the globe is stored, in key order (see [`get_cell_index`]), as a range of each of
+/// the contiguous `ids`, `nodes`, `leaf_ids`, and `fragment_ids` arrays, which `offsets` delimit.  So,
+/// getting a cell is a bit of arithmetic and four slices, and decoding the table is a handful of
+/// arrays, which are borrowed in place from an embedded asset.
+///
+/// The fragments are encoded first, so that, when borrow-decoded from an 8-byte aligned asset,
+/// their coordinates are aligned too (the table holds 4-byte offsets and ids, which would skew them).
+#[derive(Debug)]
+#[cfg_attr(feature = "self-contained", derive(Encode, Decode))]
+pub struct EncodableLookup {
+    /// The leaf candidates' geometries, clipped to their leaves (see [`LookupCell`]).
+    pub fragments: Vec<EncodableGeometry>,
+    /// Where each cell starts in each of the arrays, followed by where the last cell ends.
+    pub offsets: EncodableCellOffsets,
+    /// The concatenated [`LookupCell::ids`] of every cell.
+    pub ids: EncodableIds,
+    /// The concatenated [`LookupCell::nodes`] of every cell.
+    pub nodes: EncodableNodes,
+    /// The concatenated [`LookupCell::leaf_ids`] of every cell.
+    pub leaf_ids: EncodableIds,
+    /// The concatenated [`LookupCell::fragment_ids`] of every cell.
+    pub fragment_ids: EncodableIds,
+}
+
+impl EncodableLookup {
+    /// Build a lookup from the fragments, and from every cell, in key order (see [`get_cell_index`]),
+    /// whose fragment ids index them.
+    ///
+    /// Panics if there is not exactly one cell for every key (see [`LOOKUP_CELL_COUNT`]).
+    pub fn from_cells<'c>(fragments: Vec<EncodableGeometry>, cells: impl IntoIterator<Item = LookupCell<'c>>) -> Self {
+        let mut offsets = vec![CellOffsets::default()];
+        let (mut ids, mut nodes, mut leaf_ids, mut fragment_ids) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+
+        for cell in cells {
+            ids.extend_from_slice(cell.ids);
+            nodes.extend_from_slice(cell.nodes);
+            leaf_ids.extend_from_slice(cell.leaf_ids);
+            fragment_ids.extend_from_slice(cell.fragment_ids);
+
+            offsets.push(CellOffsets {
+                ids: ids.len() as u32,
+                nodes: nodes.len() as u32,
+                leaf_ids: leaf_ids.len() as u32,
+                fragment_ids: fragment_ids.len() as u32,
+            });
+        }
+
+        assert_eq!(offsets.len(), LOOKUP_CELL_COUNT + 1, "A lookup must have exactly one cell for every key.");
+
+        EncodableLookup {
+            fragments,
+            offsets: EncodableCellOffsets(offsets, Backing::Owned),
+            ids: EncodableIds(ids, Backing::Owned),
+            nodes: EncodableNodes(nodes, Backing::Owned),
+            leaf_ids: EncodableIds(leaf_ids, Backing::Owned),
+            fragment_ids: EncodableIds(fragment_ids, Backing::Owned),
+        }
+    }
+
+    /// Get the cell whose south-west corner is `key`, or `None` if it is off the globe.
+    #[inline]
+    pub fn get(&self, key: &RoundLngLat) -> Option<LookupCell<'_>> {
+        let index = get_cell_index(*key)?;
+        let (start, end) = (self.offsets.get(index)?, self.offsets.get(index + 1)?);
+
+        Some(LookupCell {
+            ids: &self.ids[start.ids as usize..end.ids as usize],
+            nodes: &self.nodes[start.nodes as usize..end.nodes as usize],
+            leaf_ids: &self.leaf_ids[start.leaf_ids as usize..end.leaf_ids as usize],
+            fragment_ids: &self.fragment_ids[start.fragment_ids as usize..end.fragment_ids as usize],
+        })
+    }
+
+    /// Get the number of cells in the table, which is [`LOOKUP_CELL_COUNT`] for any valid lookup.
+    pub fn len(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    /// Whether the table has no cells (which no valid lookup does).
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterate over every cell, along with its key, in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (RoundLngLat, LookupCell<'_>)> + '_ {
+        (-180..180).flat_map(|x| (-90..90).map(move |y| (x, y))).filter_map(|key| self.get(&key).map(|cell| (key, cell)))
+    }
+
+    /// Checks that the lookup refers only to `item_count` items and to its own fragments, and that
+    /// the cell table, every cell's quadtree, and leaf ranges are in bounds, so that no lookup can
+    /// index out of bounds (or walk the tree forever) over it.
+    ///
+    /// A generated lookup always passes: this is for lookups that did not come from a trusted build.
+    pub fn validate(&self, item_count: usize) -> Result<(), AssetError> {
+        if self.offsets.len() != LOOKUP_CELL_COUNT + 1 {
+            return Err(AssetError::Invalid(format!(
+                "the cell table has {} offsets, but there are {} cells",
+                self.offsets.len(),
+                LOOKUP_CELL_COUNT
+            )));
+        }
+
+        let lens = CellOffsets {
+            ids: self.ids.len() as u32,
+            nodes: self.nodes.len() as u32,
+            leaf_ids: self.leaf_ids.len() as u32,
+            fragment_ids: self.fragment_ids.len() as u32,
+        };
+        let in_order = |a: &CellOffsets, b: &CellOffsets| a.ids <= b.ids && a.nodes <= b.nodes && a.leaf_ids <= b.leaf_ids && a.fragment_ids <= b.fragment_ids;
+
+        if self.offsets[0] != CellOffsets::default() || self.offsets[LOOKUP_CELL_COUNT] != lens || !self.offsets.windows(2).all(|w| in_order(&w[0], &w[1])) {
+            return Err(AssetError::Invalid("the cell table's offsets are out of order, or do not span its arrays".to_string()));
+        }
+
+        let check_ids = |ids: &[Id], what: &str, key: &RoundLngLat| match ids.iter().find(|&&id| id as usize >= item_count) {
+            Some(id) => Err(AssetError::Invalid(format!("cell {:?} refers to {} {}, but there are {} items", key, what, id, item_count))),
+            None => Ok(()),
+        };
+
+        for (key, cell) in self.iter() {
+            check_ids(cell.ids, "item", &key)?;
+            check_ids(cell.leaf_ids, "leaf item", &key)?;
+
+            let candidates = if cell.nodes.is_empty() { cell.ids.len() } else { cell.leaf_ids.len() };
+            if cell.fragment_ids.len() != candidates {
+                return Err(AssetError::Invalid(format!(
+                    "cell {:?} has {} fragment ids for {} candidates",
+                    key,
+                    cell.fragment_ids.len(),
+                    candidates
+                )));
+            }
+
+            if let Some(id) = cell.fragment_ids.iter().find(|&&id| id != NO_FRAGMENT && id as usize >= self.fragments.len()) {
+                return Err(AssetError::Invalid(format!(
+                    "cell {:?} refers to fragment {}, but there are {} fragments",
+                    key,
+                    id,
+                    self.fragments.len()
+                )));
+            }
+
+            for (k, node) in cell.nodes.iter().enumerate() {
+                // Children always follow their parent, which also rules out cycles.
+                if node.children != 0 && (node.children as usize <= k || node.children as usize + 3 >= cell.nodes.len()) {
+                    return Err(AssetError::Invalid(format!(
+                        "node {} of cell {:?} has children at {} of {} nodes",
+                        k,
+                        key,
+                        node.children,
+                        cell.nodes.len()
+                    )));
+                }
+
+                if node.start.checked_add(node.len).is_none_or(|end| end as usize > cell.leaf_ids.len()) {
+                    return Err(AssetError::Invalid(format!("node {} of cell {:?} has candidates past the {} leaf ids", k, key, cell.leaf_ids.len())));
+                }
+            }
+        }
 
-        Ok(EncodableIds(vec))
+        Ok(())
     }
 }
 
@@ -856,7 +3457,7 @@ impl<'de, Context> BorrowDecode<'de, Context> for EncodableIds {
 mod codec_tests {
     use super::*;
     use crate::base::types::Float;
-    use geo::{Coord, Geometry, LineString, MultiPolygon, Polygon};
+    use geo::{Coord, Geometry, Line, LineString, MultiPolygon, Polygon, Rect};
     use std::borrow::Cow;
 
     fn roundtrip_string(s: &str) {
@@ -890,13 +3491,364 @@ mod codec_tests {
     fn ids_roundtrip_empty_and_many() {
         let cfg = get_global_bincode_config();
         for v in [Vec::<Id>::new(), vec![0u32, 1, 2, 4_000_000]] {
-            let original = EncodableIds(v.clone());
+            let original = EncodableIds(v.clone(), Backing::Owned);
             let bytes = bincode::encode_to_vec(&original, cfg).unwrap();
             let (decoded, _len): (EncodableIds, usize) = bincode::decode_from_slice(&bytes, cfg).unwrap();
             assert_eq!(decoded.0, v);
         }
     }
 
+    fn subdivided_cell() -> LookupCell<'static> {
+        LookupCell {
+            ids: &[1, 2, 3],
+            nodes: &[
+                LookupNode { children: 1, start: 0, len: 0 },
+                LookupNode { children: 0, start: 0, len: 1 },
+                LookupNode { children: 0, start: 1, len: 2 },
+                LookupNode { children: 0, start: 3, len: 0 },
+                LookupNode { children: 0, start: 3, len: 1 },
+            ],
+            leaf_ids: &[1, 2, 3, 3],
+            fragment_ids: &[NO_FRAGMENT, 0, NO_FRAGMENT, 1],
+        }
+    }
+
+    /// A lookup whose only non-empty cells are `cells`, over two unit square fragments.
+    fn lookup_with(cells: &[(RoundLngLat, LookupCell<'_>)]) -> EncodableLookup {
+        let fragment = || EncodableGeometry::new(Geometry::Polygon(Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 1.0, y: 1.0 }).to_polygon()));
+        let table = (-180..180)
+            .flat_map(|x| (-90..90).map(move |y| (x, y)))
+            .map(|key| cells.iter().find(|(k, _)| *k == key).map(|(_, cell)| *cell).unwrap_or_default());
+
+        EncodableLookup::from_cells(vec![fragment(), fragment()], table)
+    }
+
+    #[test]
+    fn lookup_roundtrips_flat_and_subdivided_cells() {
+        let cfg = get_global_bincode_config();
+        let flat = LookupCell {
+            ids: &[3, 7],
+            nodes: &[],
+            leaf_ids: &[],
+            fragment_ids: &[0, NO_FRAGMENT],
+        };
+        let original = lookup_with(&[((10, 20), subdivided_cell()), ((10, 21), flat), ((-180, -90), flat), ((179, 89), subdivided_cell())]);
+
+        let bytes = bincode::encode_to_vec(&original, cfg).unwrap();
+        let (decoded, _len): (EncodableLookup, usize) = bincode::decode_from_slice(&bytes, cfg).unwrap();
+        assert_eq!(decoded.len(), LOOKUP_CELL_COUNT);
+        assert!(decoded.iter().eq(original.iter()));
+
+        assert_eq!(decoded.get(&(10, 20)), Some(subdivided_cell()));
+        assert_eq!(decoded.get(&(10, 21)), Some(flat));
+        assert_eq!(decoded.get(&(-180, -90)), Some(flat));
+        assert_eq!(decoded.get(&(179, 89)), Some(subdivided_cell()));
+        assert_eq!(decoded.get(&(0, 0)), Some(LookupCell::default()));
+        assert_eq!(decoded.get(&(180, 0)), None);
+        assert_eq!(decoded.get(&(0, -91)), None);
+    }
+
+    #[test]
+    fn cell_indices_are_in_key_order() {
+        let keys = (-180..180).flat_map(|x| (-90..90).map(move |y| (x, y)));
+
+        assert!(keys.enumerate().all(|(index, key)| get_cell_index(key) == Some(index)));
+        assert_eq!(get_cell_index((179, 89)), Some(LOOKUP_CELL_COUNT - 1));
+        assert_eq!(get_cell_index((-181, 0)), None);
+        assert_eq!(get_cell_index((0, 90)), None);
+    }
+
+    #[test]
+    fn cell_descends_to_the_leaf_containing_a_point() {
+        let cell = subdivided_cell();
+
+        let sw = cell.candidates_at((10, 20), 10.25, 20.25);
+        assert_eq!(sw.ids, &[1]);
+        assert!(sw.is_covering(0));
+
+        let se = cell.candidates_at((10, 20), 10.75, 20.25);
+        assert_eq!(se.iter().collect::<Vec<_>>(), vec![(2, Some(0)), (3, None)]);
+
+        assert_eq!(cell.candidates_at((10, 20), 10.25, 20.75).ids, &[] as &[Id]);
+        assert_eq!(cell.candidates_at((10, 20), 10.5, 20.5).iter().collect::<Vec<_>>(), vec![(3, Some(1))]);
+    }
+
+    #[test]
+    fn clipped_geometry_agrees_with_the_original_inside_the_rect() {
+        // A comb (so that rings leave and re-enter the rect many times) with a hole straddling the rect's edge.
+        let mut exterior = vec![Coord { x: 0.0, y: 0.0 }, Coord { x: 4.0, y: 0.0 }];
+        for tooth in 0..8 {
+            let x = 4.0 - tooth as Float * 0.5;
+            exterior.extend([Coord { x, y: 4.0 }, Coord { x: x - 0.25, y: 4.0 }, Coord { x: x - 0.25, y: 1.0 }, Coord { x: x - 0.5, y: 1.0 }]);
+        }
+        exterior.push(Coord { x: 0.0, y: 0.0 });
+        let hole = LineString(vec![
+            Coord { x: 0.5, y: 0.25 },
+            Coord { x: 2.5, y: 0.25 },
+            Coord { x: 2.5, y: 0.75 },
+            Coord { x: 0.5, y: 0.75 },
+            Coord { x: 0.5, y: 0.25 },
+        ]);
+        let geometry = Geometry::Polygon(Polygon::new(LineString(exterior), vec![hole]));
+
+        let rect = Rect::new(Coord { x: 1.0, y: 0.5 }, Coord { x: 2.0, y: 1.5 });
+        let clipped = clip_geometry_to_rect(&geometry, get_fragment_rect(rect));
+
+        for i in 0..=64 {
+            for j in 0..=64 {
+                let point = Coord {
+                    x: 1.0 + i as Float / 64.0,
+                    y: 0.5 + j as Float / 64.0,
+                };
+
+                assert_eq!(clipped.contains(&point), geometry.contains(&point), "{point:?}");
+            }
+        }
+
+        // The clip actually dropped most of the comb.
+        let Geometry::MultiPolygon(clipped) = clipped else { unreachable!() };
+        assert!(clipped.0[0].exterior().0.len() < 20);
+    }
+
+    #[test]
+    #[cfg(not(feature = "unsimplified"))]
+    fn simplified_neighbours_keep_their_shared_borders() {
+        let epsilon = 0.01;
+
+        // Two neighbours that tile `(0, 0)` to `(2, 1)`, split by a zigzag up `x = 1` (which the left one starts
+        // halfway along), and an island that fills a hole in the right one (which starts elsewhere along it).
+        let border = (0..=20)
+            .map(|k| Coord {
+                x: if k % 20 == 0 { 1.0 } else { 1.0 + if k % 2 == 0 { -0.01 } else { 0.01 } },
+                y: k as Float / 20.0,
+            })
+            .collect::<Vec<_>>();
+        let island = [(1.4, 0.4), (1.5, 0.395), (1.6, 0.4), (1.605, 0.5), (1.6, 0.6), (1.5, 0.605), (1.4, 0.6), (1.395, 0.5)].map(|(x, y)| Coord { x, y });
+
+        let left = [&border[10..], &[Coord { x: 0.0, y: 1.0 }, Coord { x: 0.0, y: 0.0 }], &border[..=10]].concat();
+        let right = [
+            &[Coord { x: 1.0, y: 0.0 }, Coord { x: 2.0, y: 0.0 }, Coord { x: 2.0, y: 1.0 }],
+            &border.iter().rev().copied().collect::<Vec<_>>()[..],
+        ]
+        .concat();
+        let hole = island[3..].iter().chain(&island[..=3]).rev().copied().collect::<Vec<_>>();
+        let geometries = vec![
+            Geometry::Polygon(Polygon::new(LineString(left), vec![])),
+            Geometry::Polygon(Polygon::new(LineString(right), vec![LineString(hole)])),
+            Geometry::Polygon(Polygon::new(LineString(island.to_vec()), vec![])),
+        ];
+
+        // The points (off every edge) that are not in exactly one of the geometries.
+        let get_misses = |geometries: &[Geometry<Float>]| {
+            (0..128)
+                .flat_map(|i| {
+                    (0..64).map(move |j| Coord {
+                        x: (i as Float + 0.5) / 64.0,
+                        y: (j as Float + 0.5) / 64.0,
+                    })
+                })
+                .filter(|point| geometries.iter().filter(|geometry| geometry.contains(point)).count() != 1)
+                .count()
+        };
+        assert_eq!(get_misses(&geometries), 0);
+
+        // Simplifying each on its own opens gaps and overlaps between them, but simplifying them together does not.
+        let independent = geometries.iter().map(|geometry| simplify_geometry(geometry.clone(), epsilon)).collect::<Vec<_>>();
+        assert!(get_misses(&independent) > 0);
+
+        let simplified = simplify_geometries(geometries.clone(), epsilon);
+        assert_eq!(get_misses(&simplified), 0);
+
+        // The zigzag is straightened, and the island keeps its corners, and its least vertex (where its one, closed arc
+        // starts and ends), as the same ring as the hole.
+        let (Geometry::Polygon(left), Geometry::Polygon(right), Geometry::Polygon(island)) = (&simplified[0], &simplified[1], &simplified[2]) else {
+            unreachable!()
+        };
+        assert_eq!(left.exterior().0.len(), 5);
+        assert_eq!(right.exterior().0.len(), 5);
+        assert_eq!(island.exterior().0.len(), 6);
+        assert_eq!(right.interiors()[0].0.iter().rev().collect::<Vec<_>>(), island.exterior().0.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn repair_fixes_or_drops_broken_geometries() {
+        let json = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                { "type": "Feature", "properties": { "name": "valid" },
+                  "geometry": { "type": "Polygon", "coordinates": [[[0, 0], [2, 0], [2, 2], [0, 2], [0, 0]]] } },
+                { "type": "Feature", "properties": { "name": "clockwise and unclosed" },
+                  "geometry": { "type": "Polygon", "coordinates": [[[0, 0], [0, 2], [2, 2], [2, 0]]] } },
+                { "type": "Feature", "properties": { "name": "bowtie" },
+                  "geometry": { "type": "Polygon", "coordinates": [[[0, 0], [2, 2], [2, 0], [0, 2], [0, 0]]] } },
+                { "type": "Feature", "properties": { "name": "collapsed" },
+                  "geometry": { "type": "Polygon", "coordinates": [[[0, 0], [1, 1], [2, 2], [0, 0]]] } },
+                { "type": "Feature", "properties": { "name": "point" },
+                  "geometry": { "type": "Point", "coordinates": [1, 1] } },
+                { "type": "Feature", "properties": { "name": "collapsed hole" },
+                  "geometry": { "type": "MultiPolygon", "coordinates": [[[[0, 0], [2, 0], [2, 2], [0, 2], [0, 0]], [[1, 1], [1, 1], [1, 1], [1, 1]]]] } }
+            ]
+        }"#;
+        let mut features = get_geojson_features_from_string(json);
+        features.features.push(geojson::Feature {
+            geometry: Some(geojson::Geometry::new(geojson::GeometryValue::Polygon {
+                coordinates: vec![vec![[0.0, 0.0].into(), [f64::NAN, 0.0].into(), [1.0, 1.0].into(), [0.0, 0.0].into()]],
+            })),
+            ..Default::default()
+        });
+
+        let (repaired, report) = repair_features(&mut features.features, 0.0);
+        let get_problems = |feature: usize| {
+            report
+                .features
+                .iter()
+                .find(|repaired| repaired.feature == feature)
+                .map(|repaired| (repaired.problems.clone(), repaired.dropped))
+        };
+
+        assert_eq!(report.feature_count, 7);
+        assert_eq!(report.features.iter().map(|repaired| repaired.feature).collect::<Vec<_>>(), vec![1, 2, 3, 5, 6]);
+        assert_eq!(get_problems(1), Some((vec![GeometryProblem::UnclosedRing, GeometryProblem::WrongOrientation], false)));
+        assert_eq!(get_problems(2), Some((vec![GeometryProblem::SelfIntersection], false)));
+        assert_eq!(get_problems(3), Some((vec![GeometryProblem::EmptyPolygon], true)));
+        assert_eq!(get_problems(5), Some((vec![GeometryProblem::EmptyPolygon], false)));
+        assert_eq!(get_problems(6), Some((vec![GeometryProblem::NonFiniteCoordinate], true)));
+        assert_eq!((report.problem_count(), report.dropped_count()), (6, 2));
+
+        // The collapsed and non-finite features are dropped, the rest repaired, and the point left as it is.
+        assert_eq!(repaired.iter().map(Option::is_some).collect::<Vec<_>>(), vec![true, true, true, false, true, true, false]);
+        let geometries = repaired.into_iter().map(|geometry| geometry.map(Result::unwrap)).collect::<Vec<_>>();
+
+        let Some(Geometry::Polygon(oriented)) = &geometries[1] else { unreachable!() };
+        assert!(get_ring_signed_area(oriented.exterior()) > 0.0);
+
+        let Some(Geometry::Point(_)) = &geometries[4] else { unreachable!() };
+
+        let Some(Geometry::MultiPolygon(bowtie)) = &geometries[2] else { unreachable!() };
+        assert_eq!(bowtie.0.len(), 2);
+        assert!(bowtie.contains(&Coord { x: 0.25, y: 1.0 }) && bowtie.contains(&Coord { x: 1.75, y: 1.0 }));
+        assert!(!bowtie.contains(&Coord { x: 1.0, y: 0.25 }));
+
+        let Some(Geometry::MultiPolygon(without_hole)) = &geometries[5] else { unreachable!() };
+        assert!(without_hole.0[0].interiors().is_empty());
+
+        // The report is machine-readable.
+        assert_eq!(
+            serde_json::to_value(&report.features[0]).unwrap(),
+            serde_json::json!({ "feature": 1, "problems": ["unclosed_ring", "wrong_orientation"], "dropped": false })
+        );
+        assert_eq!(report.to_string(), "6 problem(s) in 5 of 7 feature(s): 3 repaired, 2 dropped, and 0 skipped");
+    }
+
+    #[test]
+    fn ring_self_intersections_ignore_neighbouring_segments() {
+        let ring = |coords: &[(Float, Float)]| LineString::from(coords.to_vec());
+
+        assert!(!ring_has_self_intersection(&ring(&[(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0), (0.0, 0.0)])));
+        // Crossing, touching at a vertex, and doubling back on a neighbour.
+        assert!(ring_has_self_intersection(&ring(&[(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0), (0.0, 0.0)])));
+        assert!(ring_has_self_intersection(&ring(&[(0.0, 0.0), (2.0, 0.0), (1.0, 1.0), (2.0, 2.0), (0.0, 2.0), (1.0, 1.0), (0.0, 0.0)])));
+        assert!(ring_has_self_intersection(&ring(&[(0.0, 0.0), (2.0, 0.0), (3.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 0.0)])));
+    }
+
+    #[test]
+    fn covers_rect_requires_the_closed_rect_in_the_interior() {
+        let rect = get_cell_rect(1, 1);
+        let square = |min: Float, max: Float| {
+            Geometry::Polygon(Polygon::new(
+                LineString(vec![
+                    Coord { x: min, y: min },
+                    Coord { x: max, y: min },
+                    Coord { x: max, y: max },
+                    Coord { x: min, y: max },
+                    Coord { x: min, y: min },
+                ]),
+                vec![],
+            ))
+        };
+
+        assert!(geometry_covers_rect(&square(0.0, 3.0), rect));
+        // Sharing an edge with the rect leaves that edge on the boundary, where `contains` is false.
+        assert!(!geometry_covers_rect(&square(1.0, 3.0), rect));
+        assert!(!geometry_covers_rect(&square(1.25, 1.75), rect));
+        assert!(!geometry_covers_rect(&square(5.0, 6.0), rect));
+    }
+
+    #[test]
+    fn cells_within_distance_wrap_the_antimeridian_and_widen_at_the_poles() {
+        // ~50km is under half a degree, which crosses the antimeridian from here, and the equator from there.
+        assert_eq!(get_cells_within_distance(179.9, 0.5, 50_000.0), vec![(-180, 0), (179, 0)]);
+        assert_eq!(get_cells_within_distance(10.5, 0.1, 50_000.0), vec![(10, -1), (10, 0)]);
+
+        assert_eq!(get_cells_within_distance(10.5, 10.5, 0.0), vec![(10, 10)]);
+        assert_eq!(get_cells_within_distance(10.5, 89.5, 1_000.0).len(), 3);
+        assert_eq!(get_cells_within_distance(10.5, 89.999, 1_000.0).len(), 360);
+    }
+
+    #[test]
+    fn bbox_rects_split_at_the_antimeridian() {
+        let rects = get_bbox_rects(170.0, -10.0, -170.0, 10.0);
+        assert_eq!(rects, vec![get_bbox_rects(170.0, -10.0, 180.0, 10.0)[0], get_bbox_rects(-180.0, -10.0, -170.0, 10.0)[0]]);
+
+        let cells = rects.into_iter().flat_map(get_cells_in_rect).collect::<Vec<_>>();
+        assert_eq!(cells.len(), (10 + 11) * 21);
+        assert!(cells.contains(&(179, 10)) && cells.contains(&(-180, -10)) && !cells.contains(&(0, 0)));
+
+        // Edges touch the cells beyond them, but never past the grid.
+        assert_eq!(get_cells_in_rect(get_bbox_rects(10.0, 89.5, 11.0, 90.0)[0]).collect::<Vec<_>>(), vec![(10, 89), (11, 89)]);
+    }
+
+    #[test]
+    fn lines_split_at_the_antimeridian() {
+        let line = |a: (Float, Float), b: (Float, Float)| Line::new(Coord { x: a.0, y: a.1 }, Coord { x: b.0, y: b.1 });
+
+        assert_eq!(
+            split_line_at_antimeridian(line((179.0, 0.0), (-179.0, 2.0))),
+            vec![line((179.0, 0.0), (180.0, 1.0)), line((-180.0, 1.0), (-179.0, 2.0))]
+        );
+        assert_eq!(
+            split_line_at_antimeridian(line((-170.0, 0.0), (160.0, 30.0))),
+            vec![line((-170.0, 0.0), (-180.0, 10.0)), line((180.0, 10.0), (160.0, 30.0))]
+        );
+        assert_eq!(split_line_at_antimeridian(line((-90.0, 0.0), (90.0, 0.0))), vec![line((-90.0, 0.0), (90.0, 0.0))]);
+    }
+
+    #[test]
+    fn line_crossings_are_parameters_along_the_line() {
+        let square = Geometry::Polygon(Rect::new(Coord { x: 1.0, y: -1.0 }, Coord { x: 2.0, y: 1.0 }).to_polygon());
+        let line = Line::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 4.0, y: 0.0 });
+
+        let mut crossings = get_line_crossings(line, &square);
+        crossings.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(crossings, vec![0.25, 0.5]);
+
+        // Running along an edge meets it from one end of the overlap to the other.
+        let mut crossings = get_line_crossings(Line::new(Coord { x: 0.0, y: 1.0 }, Coord { x: 4.0, y: 1.0 }), &square);
+        crossings.sort_by(|a, b| a.total_cmp(b));
+        crossings.dedup();
+        assert_eq!(crossings, vec![0.25, 0.5]);
+    }
+
+    #[test]
+    fn distance_to_boundary_is_geodesic() {
+        let square = Geometry::Polygon(Polygon::new(
+            LineString(vec![
+                Coord { x: 1.0, y: -1.0 },
+                Coord { x: 2.0, y: -1.0 },
+                Coord { x: 2.0, y: 1.0 },
+                Coord { x: 1.0, y: 1.0 },
+                Coord { x: 1.0, y: -1.0 },
+            ]),
+            vec![],
+        ));
+
+        // One degree of longitude along the WGS84 equator, from both outside and inside.
+        assert!((get_distance_to_boundary(&square, 0.0, 0.0).unwrap() - 111_319.5).abs() < 1.0);
+        assert!((get_distance_to_boundary(&square, 1.5, 0.0).unwrap() - 55_659.7).abs() < 1.0);
+
+        assert_eq!(get_distance_to_boundary(&Geometry::Point(geo::Point::new(0.0, 0.0)), 0.0, 0.0), None);
+    }
+
     #[test]
     fn geometry_roundtrips_polygon_with_interior_and_multipolygon() {
         let cfg = get_global_bincode_config();
@@ -915,25 +3867,409 @@ mod codec_tests {
         ]);
         let poly: Polygon<Float> = Polygon::new(exterior, vec![interior]);
 
-        let original = EncodableGeometry(Geometry::Polygon(poly.clone()));
+        let original = EncodableGeometry::new(Geometry::Polygon(poly.clone()));
         let bytes = bincode::encode_to_vec(&original, cfg).unwrap();
         let (decoded, _len): (EncodableGeometry, usize) = bincode::decode_from_slice(&bytes, cfg).unwrap();
         assert_eq!(decoded.0, original.0);
 
-        let multi = EncodableGeometry(Geometry::MultiPolygon(MultiPolygon::new(vec![poly])));
+        let multi = EncodableGeometry::new(Geometry::MultiPolygon(MultiPolygon::new(vec![poly])));
         let bytes = bincode::encode_to_vec(&multi, cfg).unwrap();
         let (decoded, _len): (EncodableGeometry, usize) = bincode::decode_from_slice(&bytes, cfg).unwrap();
         assert_eq!(decoded.0, multi.0);
     }
 
+    #[test]
+    fn asset_header_roundtrips_and_rejects_mismatches() {
+        let info = DatasetInfo {
+            format_version: ASSET_FORMAT_VERSION,
+            float_bits: FLOAT_BITS,
+            simplification_epsilon: 0.0001,
+            source: "https://example.com/2026c.zip".to_string(),
+            generated_at: 1_784_770_201,
+            item_count: 444,
+            items_checksum: 0x1234_5678,
+        };
+        let header = AssetHeader::new(AssetKind::Lookup, info, &[1, 2, 3]);
+        let mut bytes = header.to_bytes();
+        assert_eq!(bytes.len() % 8, 0);
+        bytes.extend([1, 2, 3]);
+
+        let (decoded, body) = AssetHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(body, &[1, 2, 3]);
+        assert!(decoded.expect_kind(AssetKind::Lookup).is_ok());
+        assert!(matches!(decoded.expect_kind(AssetKind::Items), Err(AssetError::WrongKind { found: AssetKind::Lookup, .. })));
+
+        // A lookup keeps the checksum of the items it was built from, and items record their own.
+        assert_eq!(decoded.info.items_checksum, 0x1234_5678);
+        let items = AssetHeader::new(AssetKind::Items, header.info.clone(), &[1, 2, 3]);
+        assert_eq!(items.info.items_checksum, items.checksum);
+
+        let mut forged = items.clone();
+        forged.info.items_checksum ^= 1;
+        let mut forged_bytes = forged.to_bytes();
+        forged_bytes.extend([1, 2, 3]);
+        assert!(matches!(AssetHeader::from_bytes(&forged_bytes), Err(AssetError::Invalid(_))));
+
+        // Headerless (e.g., pre-header) data, another format version, and unreadable floats are all told apart.
+        assert!(matches!(AssetHeader::from_bytes(&bytes[8..]), Err(AssetError::MissingHeader)));
+
+        let mut other_version = header.clone();
+        other_version.info.format_version += 1;
+        assert!(matches!(
+            AssetHeader::from_bytes(&other_version.to_bytes()),
+            Err(AssetError::UnsupportedFormatVersion { found, expected: ASSET_FORMAT_VERSION }) if found == ASSET_FORMAT_VERSION + 1
+        ));
+
+        // The other float width is read (see `geometry_decodes_from_either_float_width`).
+        let mut other_floats = header.clone();
+        other_floats.info.float_bits = 96 - FLOAT_BITS;
+        let mut other_floats_bytes = other_floats.to_bytes();
+        other_floats_bytes.extend([1, 2, 3]);
+        assert_eq!(AssetHeader::from_bytes(&other_floats_bytes).unwrap().0.info.float_bits, 96 - FLOAT_BITS);
+
+        let mut half_floats = header.clone();
+        half_floats.info.float_bits = 16;
+        assert!(matches!(AssetHeader::from_bytes(&half_floats.to_bytes()), Err(AssetError::UnsupportedFloatWidth { found: 16 })));
+
+        assert!(matches!(AssetHeader::from_bytes(&header.to_bytes()[..20]), Err(AssetError::Decode(_))));
+
+        // A body that was cut off, or corrupted, is caught before it is decoded.
+        assert!(matches!(AssetHeader::from_bytes(&bytes[..bytes.len() - 1]), Err(AssetError::Truncated { expected: 3, found: 2 })));
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x10;
+        assert!(matches!(AssetHeader::from_bytes(&bytes), Err(AssetError::ChecksumMismatch { expected, .. }) if expected == header.checksum));
+    }
+
+    #[test]
+    fn assets_have_one_byte_order_on_every_target() {
+        let cfg = get_global_bincode_config();
+        assert_eq!(bincode::encode_to_vec(0x0102_0304u32, cfg).unwrap(), [4, 3, 2, 1]);
+        assert_eq!(bincode::encode_to_vec(1.0f32, cfg).unwrap(), 1.0f32.to_le_bytes());
+
+        // A header in the opposite byte order is told apart, rather than misread.
+        let info = DatasetInfo {
+            format_version: ASSET_FORMAT_VERSION,
+            float_bits: FLOAT_BITS,
+            simplification_epsilon: 0.0,
+            source: String::new(),
+            generated_at: 0,
+            item_count: 0,
+            items_checksum: 0,
+        };
+        let mut bytes = AssetHeader::new(AssetKind::Items, info, &[]).to_bytes();
+        bytes[8..12].reverse();
+        assert!(matches!(AssetHeader::from_bytes(&bytes), Err(AssetError::UnsupportedFormatVersion { .. })));
+    }
+
+    #[test]
+    fn raw_arrays_decode_from_the_opposite_byte_order() {
+        // What a big-endian target does with the (little-endian) assets, done here with big-endian data.
+        let opposite = bincode::config::legacy().with_big_endian();
+        let skip_len = |bytes: Vec<u8>| bytes[std::mem::size_of::<u64>()..].to_vec();
+
+        let ids = vec![1, 2, 0xdead_beef];
+        let bytes = skip_len(bincode::encode_to_vec(EncodableIds(ids.clone(), Backing::Owned), opposite).unwrap());
+        assert_eq!(copy_swapped_raw_vec::<Id>(&bytes), ids);
+
+        let coords = [Coord { x: 1.5, y: -2.25 }, Coord { x: 179.9968, y: -67.0959 }];
+        let bytes = bincode::encode_to_vec(coords.map(|c| (c.x, c.y)), opposite).unwrap();
+        assert_eq!(copy_swapped_raw_vec::<Coord<Float>>(&bytes), coords);
+
+        let nodes = vec![LookupNode { children: 1, start: 2, len: 3 }, LookupNode { children: 0, start: 70_000, len: 1 }];
+        let bytes = skip_len(bincode::encode_to_vec(EncodableNodes(nodes.clone(), Backing::Owned), opposite).unwrap());
+        assert_eq!(copy_swapped_raw_vec::<LookupNode>(&bytes), nodes);
+
+        let offsets = vec![
+            CellOffsets::default(),
+            CellOffsets {
+                ids: 3,
+                nodes: 5,
+                leaf_ids: 4,
+                fragment_ids: 0x0100_0000,
+            },
+        ];
+        let bytes = skip_len(bincode::encode_to_vec(EncodableCellOffsets(offsets.clone(), Backing::Owned), opposite).unwrap());
+        assert_eq!(copy_swapped_raw_vec::<CellOffsets>(&bytes), offsets);
+    }
+
+    #[test]
+    fn geometry_decodes_from_either_float_width() {
+        let cfg = get_global_bincode_config();
+
+        // A multi-polygon of a triangle, as an `f32` build and an `f64` build encode it (as variants `1` and `5`).
+        let ring = [(-122.4194155, 37.7749295), (0.1, 0.0), (179.9999999, -89.9999999), (-122.4194155, 37.7749295)];
+        let narrow_ring = ring.map(|(x, y)| (x as f32, y as f32));
+        let narrow = bincode::encode_to_vec((1usize, 1usize, narrow_ring.to_vec(), 0usize), cfg).unwrap();
+        let wide = bincode::encode_to_vec((5usize, 1usize, ring.to_vec(), 0usize), cfg).unwrap();
+
+        // Each is read into this build's floats, as if the geometry had been generated by it.
+        let get_geometry = |ring: Vec<(Float, Float)>| Geometry::MultiPolygon(MultiPolygon::new(vec![Polygon::new(LineString::from(ring), vec![])]));
+        let cases = [
+            (&narrow, narrow_ring.map(|(x, y)| (x as Float, y as Float))),
+            (&wide, ring.map(|(x, y): (f64, f64)| (x as Float, y as Float))),
+        ];
+
+        for (bytes, ring) in cases {
+            let (decoded, len): (EncodableGeometry, usize) = bincode::decode_from_slice(bytes, cfg).unwrap();
+            assert_eq!(len, bytes.len());
+            assert_eq!(decoded.0, get_geometry(ring.to_vec()));
+        }
+
+        // The other width cannot be borrowed, so it is decoded into memory, the same as when it is owned.
+        let other = if FLOAT_BITS == 32 { &wide } else { &narrow };
+        let (owned, _): (EncodableGeometry, usize) = bincode::decode_from_slice(other, cfg).unwrap();
+        let (borrowed, _): (EncodableGeometry, usize) = bincode::borrow_decode_from_slice(other, cfg).unwrap();
+        assert_eq!(owned.0, borrowed.0);
+
+        // Re-encoding it writes this build's width.
+        let (variant, _): (usize, usize) = bincode::decode_from_slice(&bincode::encode_to_vec(&owned, cfg).unwrap(), cfg).unwrap();
+        assert_eq!(variant, RAW_GEOMETRY_VARIANT + 1);
+    }
+
+    /// An [`EncodableGeometry`] that always encodes quantized, whatever the build.
+    struct Quantized<'a>(&'a EncodableGeometry);
+
+    impl Encode for Quantized<'_> {
+        fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+            self.0.encode_with(encoder, true)
+        }
+    }
+
+    #[test]
+    fn quantized_geometry_roundtrip() {
+        let cfg = get_global_bincode_config();
+
+        // A dense ring (as borders are), with a vertex at the far end of the range.
+        let mut exterior = (0..64)
+            .map(|k| {
+                let angle = k as f64 * std::f64::consts::TAU / 64.0;
+                Coord {
+                    x: (-122.4194155 + 0.01 * angle.cos()) as Float,
+                    y: (37.7749295 + 0.01 * angle.sin()) as Float,
+                }
+            })
+            .collect::<Vec<_>>();
+        exterior[32] = Coord { x: 179.9999999, y: -89.9999999 };
+        let exterior = LineString(exterior);
+        let interior = LineString(vec![
+            Coord { x: -122.415, y: 37.777 },
+            Coord { x: -122.414, y: 37.777 },
+            Coord { x: -122.414, y: 37.778 },
+            Coord { x: -122.415, y: 37.777 },
+        ]);
+        let poly: Polygon<Float> = Polygon::new(exterior, vec![interior]);
+        let multi = EncodableGeometry::new(Geometry::MultiPolygon(MultiPolygon::new(vec![poly.clone(), poly.clone()])));
+
+        let raw = bincode::encode_to_vec(EncodableGeometry::new(Geometry::Polygon(poly.clone())), cfg).unwrap();
+        let quantized = bincode::encode_to_vec(Quantized(&EncodableGeometry::new(Geometry::Polygon(poly.clone()))), cfg).unwrap();
+        assert_eq!(quantized.len() % 8, 0);
+        assert!(quantized.len() < raw.len() * 3 / 4 || cfg!(feature = "quantized"));
+
+        // Every coordinate comes back within half a unit, and decodes the same owned or borrowed.
+        let tolerance = 0.5 / QUANTIZED_UNITS_PER_DEGREE + Float::EPSILON as f64 * 180.0;
+        let (decoded, len): (EncodableGeometry, usize) = bincode::decode_from_slice(&quantized, cfg).unwrap();
+        assert_eq!(len, quantized.len());
+        let Geometry::Polygon(decoded_poly) = &decoded.0 else { panic!("expected a polygon") };
+        for (a, b) in decoded_poly.coords_iter().zip(poly.coords_iter()) {
+            assert!((a.x - b.x).abs() as f64 <= tolerance && (a.y - b.y).abs() as f64 <= tolerance, "{:?} != {:?}", a, b);
+        }
+        assert_eq!(decoded_poly.interiors().len(), 1);
+
+        let bytes = bincode::encode_to_vec(Quantized(&multi), cfg).unwrap();
+        let (owned, _): (EncodableGeometry, usize) = bincode::decode_from_slice(&bytes, cfg).unwrap();
+        let (borrowed, _): (EncodableGeometry, usize) = bincode::borrow_decode_from_slice(&bytes, cfg).unwrap();
+        assert_eq!(owned.0, borrowed.0);
+        assert!(matches!(&owned.0, Geometry::MultiPolygon(m) if m.0.len() == 2));
+
+        // Re-encoding what was decoded is lossless.
+        assert_eq!(bincode::encode_to_vec(Quantized(&owned), cfg).unwrap(), bytes);
+
+        // A cut off ring, or one with trailing bytes, is an error, rather than a panic.
+        assert!(bincode::decode_from_slice::<EncodableGeometry, _>(&quantized[..quantized.len() - 8], cfg).is_err());
+        let mut padded = quantized.clone();
+        padded[16] += 8;
+        padded.extend([0; 8]);
+        assert!(bincode::decode_from_slice::<EncodableGeometry, _>(&padded, cfg).is_err());
+
+        // Coordinates past the fixed-point range cannot be quantized.
+        let far = EncodableGeometry::new(Geometry::Polygon(Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 1000.0, y: 1.0 }).to_polygon()));
+        assert!(bincode::encode_to_vec(Quantized(&far), cfg).is_err());
+    }
+
+    #[test]
+    fn varints_roundtrip() {
+        for value in [0, 1, -1, 63, -64, 64, 127, -128, 1 << 31, -(1 << 31), i64::MAX, i64::MIN] {
+            let mut data = Vec::new();
+            push_varint(&mut data, value);
+
+            let mut rest = data.as_slice();
+            assert_eq!(take_varint(&mut rest).unwrap(), value);
+            assert!(rest.is_empty());
+        }
+
+        assert!(take_varint(&mut [0x80u8].as_slice()).is_err());
+        assert!(take_varint(&mut [0xffu8; 11].as_slice()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "compressed-assets")]
+    fn compressed_assets_roundtrip() {
+        let info = DatasetInfo {
+            format_version: ASSET_FORMAT_VERSION,
+            float_bits: FLOAT_BITS,
+            simplification_epsilon: 0.0001,
+            source: "https://example.com/2026c.zip".to_string(),
+            generated_at: 1_784_770_201,
+            item_count: 1,
+            items_checksum: 0,
+        };
+        let body = vec![7u8; 4096];
+        let mut bytes = AssetHeader::new(AssetKind::Items, info, &body).to_bytes();
+        bytes.extend(&body);
+
+        let compressed = zstd::bulk::compress(&bytes, ASSET_COMPRESSION_LEVEL).unwrap();
+        assert!(is_compressed_asset(&compressed));
+        assert!(compressed.len() < bytes.len());
+        assert_eq!(get_decompressed_asset_len(&compressed).unwrap(), bytes.len());
+        assert_eq!(decompress_asset(&compressed).unwrap(), bytes);
+
+        // A compressed asset is told apart from an uncompressed one, and a cut off one is an error.
+        assert!(matches!(AssetHeader::from_bytes(&compressed), Err(AssetError::Compressed)));
+        assert!(decompress_asset(&compressed[..compressed.len() - 4]).is_err());
+        assert!(decompress_asset_into(&compressed, &mut vec![0; bytes.len() - 1]).is_err());
+
+        assert_eq!(get_compressed_asset_path("assets/osm_time_zones.bincode"), std::path::PathBuf::from("assets/osm_time_zones.bincode.zst"));
+    }
+
+    #[test]
+    fn corrupt_data_is_an_error_rather_than_a_panic() {
+        let cfg = get_global_bincode_config();
+
+        // Only polygons and multi-polygons are ever encoded, so any other variant is corrupt, both ways.
+        assert!(bincode::encode_to_vec(EncodableGeometry::new(Geometry::Point(geo::Point::new(0.0, 0.0))), cfg).is_err());
+
+        let bytes = bincode::encode_to_vec(6usize, cfg).unwrap();
+        let error = bincode::decode_from_slice::<EncodableGeometry, _>(&bytes, cfg).unwrap_err();
+        assert!(matches!(error, DecodeError::UnexpectedVariant { found: 6, .. }));
+
+        let bytes = bincode::encode_to_vec(2usize, cfg).unwrap();
+        assert!(matches!(
+            bincode::decode_from_slice::<EncodableOptionString, _>(&bytes, cfg).unwrap_err(),
+            DecodeError::UnexpectedVariant { found: 2, .. }
+        ));
+
+        // A length far past the end of the data fails on the limit (or the end of the data), rather than allocating it.
+        let bytes = bincode::encode_to_vec((0usize, usize::MAX / 2), cfg).unwrap();
+        assert!(bincode::decode_from_slice::<EncodableGeometry, _>(&bytes, get_safe_bincode_config()).is_err());
+        assert!(bincode::decode_from_slice::<EncodableIds, _>(&bytes[8..], get_safe_bincode_config()).is_err());
+
+        let bytes = bincode::encode_to_vec((2usize, usize::MAX / 2, 8usize, [0u8; 8]), cfg).unwrap();
+        assert!(bincode::decode_from_slice::<EncodableGeometry, _>(&bytes, get_safe_bincode_config()).is_err());
+
+        // A ring that is not closed (which `Polygon::new` would grow in place) is rejected, without
+        // anything that points into the (local) data escaping the decode.
+        let open_ring = bincode::encode_to_vec(
+            (
+                RAW_GEOMETRY_VARIANT,
+                3usize,
+                (0.0 as Float, 0.0 as Float),
+                (1.0 as Float, 0.0 as Float),
+                (1.0 as Float, 1.0 as Float),
+                0usize,
+            ),
+            cfg,
+        )
+        .unwrap();
+        let mut words = vec![0u64; open_ring.len().div_ceil(8)];
+        // SAFETY: the `u64`s span at least `open_ring.len()` bytes.
+        let aligned = unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, open_ring.len()) };
+        aligned.copy_from_slice(&open_ring);
+        assert!(bincode::borrow_decode_from_slice::<EncodableGeometry, _>(aligned, cfg).is_err());
+    }
+
+    #[test]
+    fn lookup_validation_rejects_out_of_range_references() {
+        let lookup = |cell: LookupCell<'_>| lookup_with(&[((10, 20), cell)]);
+        let cell = subdivided_cell();
+
+        assert!(lookup(cell).validate(4).is_ok());
+        assert!(matches!(lookup(cell).validate(3), Err(AssetError::Invalid(_))));
+
+        let mut fragment_ids = cell.fragment_ids.to_vec();
+        fragment_ids[1] = 2;
+        assert!(lookup(LookupCell { fragment_ids: &fragment_ids, ..cell }).validate(4).is_err());
+
+        let mut nodes = cell.nodes.to_vec();
+        nodes[0].children = 2;
+        assert!(lookup(LookupCell { nodes: &nodes, ..cell }).validate(4).is_err());
+
+        let mut nodes = cell.nodes.to_vec();
+        nodes[4].len = 2;
+        assert!(lookup(LookupCell { nodes: &nodes, ..cell }).validate(4).is_err());
+
+        let fragment_ids = &cell.fragment_ids[1..];
+        assert!(lookup(LookupCell { fragment_ids, ..cell }).validate(4).is_err());
+
+        // The cell table must span exactly every cell, in order.
+        let mut table = lookup(cell);
+        table.offsets.0.pop();
+        assert!(table.validate(4).is_err());
+
+        let mut table = lookup(cell);
+        table.offsets.0[5].ids = 1;
+        assert!(table.validate(4).is_err());
+
+        let mut table = lookup(cell);
+        table.ids.0.push(0);
+        assert!(table.validate(4).is_err());
+    }
+
+    #[test]
+    fn lookup_encoding_is_reproducible() {
+        struct Item(usize, Geometry<Float>);
+
+        impl HasGeometry for Item {
+            fn id(&self) -> usize {
+                self.0
+            }
+
+            fn set_id(&mut self, id: usize) {
+                self.0 = id;
+            }
+
+            fn geometry(&self) -> &Geometry<Float> {
+                &self.1
+            }
+        }
+
+        let rect = |x: Float, y: Float| Geometry::Polygon(Rect::new(Coord { x, y }, Coord { x: x + 2.5, y: y + 1.5 }).to_polygon());
+        let items = ConcreteVec::from(vec![Item(0, rect(-10.2, 40.3)), Item(1, rect(-9.1, 40.9)), Item(2, rect(170.5, -30.2))]);
+
+        // The fragments are gathered in key order, however the cells were scheduled.
+        let cfg = get_global_bincode_config();
+        let first = bincode::encode_to_vec(get_lookup_from_geometries(&items), cfg).unwrap();
+        let second = bincode::encode_to_vec(get_lookup_from_geometries(&items), cfg).unwrap();
+        assert_eq!(first, second);
+
+        let (decoded, _): (EncodableLookup, usize) = bincode::decode_from_slice(&first, cfg).unwrap();
+        assert_eq!(decoded.len(), LOOKUP_CELL_COUNT);
+        assert_eq!(bincode::encode_to_vec(decoded, cfg).unwrap(), first);
+
+        assert_eq!(get_generated_at(Some("1700000000")), 1_700_000_000);
+        assert!(get_generated_at(Some("yesterday")) > 1_700_000_000);
+        assert!(get_generated_at(None) > 1_700_000_000);
+    }
+
     #[test]
     fn string_borrow_decode_matches_owned() {
         // The borrowed decode path builds a `Cow::Borrowed` via an internal transmute to
         // 'static. Exercising it here is sound because the source buffer outlives the decoded
         // value and `Cow::Borrowed` frees nothing on drop. We deliberately do NOT borrow-decode
         // the `Vec::from_raw_parts` types (EncodableIds / EncodableGeometry) from a local buffer:
-        // their decoded Vecs would free borrowed memory on drop. Those borrow paths are already
-        // exercised at runtime against the embedded 'static bincodes by the `geo::*` tests.
+        // their decoded Vecs assume the bytes live for the rest of the program. Those borrow paths
+        // are already exercised at runtime against 'static bincodes by the `geo::*` tests.
         let cfg = get_global_bincode_config();
         let original = EncodableString(Cow::Owned("America/Los_Angeles".to_string()));
         let bytes = bincode::encode_to_vec(&original, cfg).unwrap();
@@ -2,7 +2,8 @@
 
 use std::borrow::Cow;
 
-use geo::Geometry;
+use geo::{BoundingRect, Geometry};
+use geojson::Feature;
 use serde_json::{Map, Value};
 
 #[cfg(feature = "self-contained")]
@@ -14,7 +15,10 @@ use bincode::{
 
 use crate::{
     base::types::Float,
-    geo::shared::{simplify_geometry, EncodableGeometry, EncodableOptionString, EncodableString, HasGeometry, HasProperties, IdFeaturePair},
+    geo::shared::{
+        get_f64_property, get_feature_properties, get_property, get_str_property, try_from_id_feature_pair, EncodableGeometry, EncodableOptionString, EncodableString, HasGeometry, HasProperties,
+        IdFeaturePair, IngestionError, TryFromFeature,
+    },
 };
 
 // Source ingestion is native-only (it downloads and parses GeoJSON), so its imports carry the
@@ -22,7 +26,7 @@ use crate::{
 #[cfg(not(target_family = "wasm"))]
 use crate::geo::shared::{get_geojson_features_from_string, CanGetGeoJsonFeaturesFromSource};
 
-use super::shared::IsTimezone;
+use super::shared::{get_stable_id, IsTimezone};
 
 // Constants.
 
@@ -63,6 +67,7 @@ pub struct NedTimezone {
     /// The index of the [`NedTimezone`] in the global static cache.
     ///
     /// This is is not stable across builds or new data sets.  It is merely unique during a single build.
+    /// See [`IsTimezone::stable_id`] for an identifier that is.
     pub id: usize,
     /// The `identifier` of the [`NedTimezone`] (e.g., `America/Los_Angeles`).
     ///
@@ -151,25 +156,22 @@ impl PartialEq for NedTimezone {
     }
 }
 
-impl From<IdFeaturePair> for NedTimezone {
-    fn from(value: IdFeaturePair) -> NedTimezone {
-        let id = value.0;
-        let properties = value.1.properties.as_ref().unwrap();
-        let geometry = value.1.geometry.as_ref().unwrap();
+impl TryFromFeature for NedTimezone {
+    fn try_from_feature(id: usize, feature: &Feature, geometry: Geometry<Float>) -> Result<NedTimezone, IngestionError> {
+        let properties = get_feature_properties(feature)?;
 
-        let dst_places = EncodableOptionString(properties.get("dst_places").unwrap().as_str().map(ToOwned::to_owned).map(Cow::Owned));
-        let places = EncodableString(Cow::Owned(properties.get("places").unwrap().as_str().unwrap().to_owned()));
+        let dst_places = EncodableOptionString(get_property(properties, "dst_places")?.as_str().map(ToOwned::to_owned).map(Cow::Owned));
+        let places = EncodableString(Cow::Owned(get_str_property(properties, "places")?.to_owned()));
 
-        let time_zone = EncodableString(Cow::Owned(properties.get("time_zone").unwrap().as_str().unwrap().to_owned()));
-        let tz_name1st = EncodableOptionString(properties.get("tz_name1st").unwrap().as_str().map(ToOwned::to_owned).map(Cow::Owned));
-        let zone = properties.get("zone").unwrap().as_f64().unwrap() as f32;
+        let time_zone = EncodableString(Cow::Owned(get_str_property(properties, "time_zone")?.to_owned()));
+        let tz_name1st = EncodableOptionString(get_property(properties, "tz_name1st")?.as_str().map(ToOwned::to_owned).map(Cow::Owned));
+        let zone = get_f64_property(properties, "zone")? as f32;
 
-        let geometry: Geometry<Float> = geometry.value.clone().try_into().unwrap();
-        let geometry = EncodableGeometry(simplify_geometry(geometry, SIMPLIFICATION_EPSILON));
+        let geometry = EncodableGeometry::new(geometry);
 
         let raw_offset = (zone * 3600.0).round() as i32;
 
-        NedTimezone {
+        Ok(NedTimezone {
             id,
             dst_description: dst_places,
             description: places,
@@ -178,7 +180,15 @@ impl From<IdFeaturePair> for NedTimezone {
             zone,
             raw_offset,
             geometry,
-        }
+        })
+    }
+}
+
+impl TryFrom<IdFeaturePair> for NedTimezone {
+    type Error = IngestionError;
+
+    fn try_from(value: IdFeaturePair) -> Result<NedTimezone, IngestionError> {
+        try_from_id_feature_pair(value)
     }
 }
 
@@ -186,6 +196,20 @@ impl IsTimezone for NedTimezone {
     fn identifier(&self) -> &str {
         self.identifier.as_deref().unwrap_or("")
     }
+
+    /// Derived from the identifier, the offset, and the bounding box of the geometry, rounded to whole
+    /// degrees, rather than from the free-text descriptions, which the source edits between releases.
+    /// Many zones (e.g., the oceans) have no identifier, and a few identifiers (e.g., `Pacific/Auckland`,
+    /// either side of the antimeridian) span several zones, so the rounded bounding box tells apart the
+    /// zones that agree on the rest, while surviving simplification and small edits to the borders.
+    fn stable_id(&self) -> u64 {
+        let bbox = match self.geometry.0.bounding_rect() {
+            Some(rect) => [rect.min().x, rect.min().y, rect.max().x, rect.max().y].map(|degrees| (degrees.round() as i32).to_string()).join(","),
+            None => String::new(),
+        };
+
+        get_stable_id(&[self.identifier.as_deref().unwrap_or(""), self.offset.as_ref(), &bbox])
+    }
 }
 
 impl HasGeometry for NedTimezone {
@@ -193,9 +217,21 @@ impl HasGeometry for NedTimezone {
         self.id
     }
 
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+
     fn geometry(&self) -> &Geometry<Float> {
         &self.geometry.0
     }
+
+    fn simplification_epsilon() -> Float {
+        SIMPLIFICATION_EPSILON
+    }
+
+    fn source() -> &'static str {
+        ADDRESS
+    }
 }
 
 impl HasProperties for NedTimezone {
@@ -6,6 +6,7 @@ use std::borrow::Cow;
 use std::io::Read;
 
 use geo::Geometry;
+use geojson::Feature;
 use serde_json::{Map, Value};
 
 #[cfg(feature = "self-contained")]
@@ -17,7 +18,7 @@ use bincode::{
 
 use crate::{
     base::types::Float,
-    geo::shared::{simplify_geometry, EncodableGeometry, EncodableString, HasGeometry, HasProperties, IdFeaturePair},
+    geo::shared::{get_feature_properties, get_str_property, try_from_id_feature_pair, EncodableGeometry, EncodableString, HasGeometry, HasProperties, IdFeaturePair, IngestionError, TryFromFeature},
 };
 
 // Source ingestion is native-only (it downloads and unzips GeoJSON), so its imports carry the
@@ -25,7 +26,7 @@ use crate::{
 #[cfg(not(target_family = "wasm"))]
 use crate::geo::shared::{get_geojson_features_from_string, CanGetGeoJsonFeaturesFromSource};
 
-use super::shared::IsTimezone;
+use super::shared::{get_stable_id, IsTimezone};
 
 // Constants.
 
@@ -67,6 +68,7 @@ pub struct OsmTimezone {
     /// The index of the [`OsmTimezone`] in the global static cache.
     ///
     /// This is is not stable across builds or new data sets.  It is merely unique during a single build.
+    /// See [`IsTimezone::stable_id`] for an identifier that is.
     pub id: usize,
     /// The `identifier` of the [`OsmTimezone`] (e.g., `America/Los_Angeles`).
     ///
@@ -114,19 +116,23 @@ impl PartialEq for OsmTimezone {
     }
 }
 
-impl From<IdFeaturePair> for OsmTimezone {
-    fn from(value: IdFeaturePair) -> OsmTimezone {
-        let id = value.0;
-        let properties = value.1.properties.as_ref().unwrap();
-        let geometry = value.1.geometry.as_ref().unwrap();
+impl TryFromFeature for OsmTimezone {
+    fn try_from_feature(id: usize, feature: &Feature, geometry: Geometry<Float>) -> Result<OsmTimezone, IngestionError> {
+        let properties = get_feature_properties(feature)?;
 
-        let identifier = EncodableString(Cow::Owned(properties.get("tzid").unwrap().as_str().unwrap().to_string()));
+        let identifier = EncodableString(Cow::Owned(get_str_property(properties, "tzid")?.to_string()));
 
-        let geometry: Geometry<Float> = geometry.value.clone().try_into().unwrap();
+        let geometry = EncodableGeometry::new(geometry);
 
-        let geometry = EncodableGeometry(simplify_geometry(geometry, SIMPLIFICATION_EPSILON));
+        Ok(OsmTimezone { id, identifier, geometry })
+    }
+}
 
-        OsmTimezone { id, identifier, geometry }
+impl TryFrom<IdFeaturePair> for OsmTimezone {
+    type Error = IngestionError;
+
+    fn try_from(value: IdFeaturePair) -> Result<OsmTimezone, IngestionError> {
+        try_from_id_feature_pair(value)
     }
 }
 
@@ -134,6 +140,11 @@ impl IsTimezone for OsmTimezone {
     fn identifier(&self) -> &str {
         self.identifier.as_ref()
     }
+
+    /// Derived from the IANA TZ identifier, which is unique in the data set.
+    fn stable_id(&self) -> u64 {
+        get_stable_id(&[self.identifier.as_ref()])
+    }
 }
 
 impl HasGeometry for OsmTimezone {
@@ -141,9 +152,21 @@ impl HasGeometry for OsmTimezone {
         self.id
     }
 
+    fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+
     fn geometry(&self) -> &Geometry<Float> {
         &self.geometry.0
     }
+
+    fn simplification_epsilon() -> Float {
+        SIMPLIFICATION_EPSILON
+    }
+
+    fn source() -> &'static str {
+        ADDRESS
+    }
 }
 
 impl HasProperties for OsmTimezone {
@@ -4,6 +4,26 @@ use crate::geo::shared::{HasGeometry, HasProperties};
 
 // Types.
 
+// Helpers.
+
+/// Derives a stable identifier from the parts of an item's content that identify it (e.g., its
+/// IANA TZ identifier), so that it does not change across builds or regenerations of the data set
+/// unless those parts do.
+///
+/// It is the 64-bit FNV-1a hash of the parts (each followed by a `0xff`, which never appears in
+/// UTF-8), truncated to 53 bits so that it survives a round trip through a JavaScript number.
+pub fn get_stable_id(parts: &[&str]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = parts
+        .iter()
+        .flat_map(|part| part.bytes().chain(std::iter::once(0xff)))
+        .fold(OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(PRIME));
+
+    hash & ((1 << 53) - 1)
+}
+
 // Traits.
 
 /// A trait for types that are a timezone and have a [`Geometry`].
@@ -12,4 +32,10 @@ use crate::geo::shared::{HasGeometry, HasProperties};
 pub trait IsTimezone: HasGeometry + HasProperties {
     /// Get the `identifier` of the [`IsTimezone`].
     fn identifier(&self) -> &str;
+
+    /// Get the stable identifier of the [`IsTimezone`] (see [`get_stable_id`]).
+    ///
+    /// Unlike [`HasGeometry::id`], it does not change across builds or new data sets, so it is
+    /// safe to store.
+    fn stable_id(&self) -> u64;
 }
@@ -2,9 +2,18 @@
 //! fixture, so the pure preprocessing path is covered without any network download.
 #![cfg(feature = "tz-ned")]
 
-use rtz_core::geo::{
-    shared::{get_geojson_features_from_string, get_items_from_features, get_lookup_from_geometries},
-    tz::ned::NedTimezone,
+use std::collections::HashSet;
+
+use geo::{BoundingRect, Contains, Coord, CoordsIter, Geometry, GeometryCollection, Intersects, Winding};
+use rtz_core::{
+    base::types::Float,
+    geo::{
+        shared::{
+            geometry_covers_rect, get_cell_rect, get_geojson_features_from_string, get_items_from_features, get_lookup_from_geometries, get_repaired_items_from_features, simplify_geometries,
+            ConcreteVec, GeometryProblem, HasGeometry, IngestionDiagnostic, IngestionError, NO_FRAGMENT,
+        },
+        tz::ned::NedTimezone,
+    },
 };
 
 const FIXTURE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../test/ne_10m_time_zones.test.geojson"));
@@ -12,6 +21,8 @@ const FIXTURE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../test
 #[test]
 fn builds_items_and_lookup_from_fixture() {
     let features = get_geojson_features_from_string(FIXTURE);
+    assert_eq!(ConcreteVec::<NedTimezone>::try_from(features.clone()).unwrap().len(), 3);
+
     let items = get_items_from_features::<NedTimezone>(features);
 
     // The fixture has exactly three timezone features, order-preserved.
@@ -28,9 +39,226 @@ fn builds_items_and_lookup_from_fixture() {
     assert_eq!(cache.len(), 64_800);
 
     // Every referenced id points at a real item.
-    for ids in cache.values() {
-        for &id in ids.iter() {
+    for (_, cell) in cache.iter() {
+        for &id in cell.iter() {
             assert!((id as usize) < items.len(), "id {id} out of range");
         }
     }
+
+    // Quadtree leaves only ever narrow a cell's candidates.
+    for (_, cell) in cache.iter() {
+        for &id in cell.leaf_ids.iter() {
+            assert!(cell.ids.contains(&id), "leaf id {id} not among the cell's candidates");
+        }
+    }
+
+    // Unsubdivided cells mark exactly the candidates that cover them, and the fixture covers some outright.
+    let mut covered = 0;
+    for ((x, y), cell) in cache.iter().filter(|(_, cell)| cell.nodes.is_empty()) {
+        let candidates = cell.candidates_at((x, y), x as Float, y as Float);
+
+        for (k, &id) in candidates.ids.iter().enumerate() {
+            let covers = candidates.is_covering(k);
+            assert_eq!(covers, geometry_covers_rect(items[id as usize].geometry(), get_cell_rect(x, y)), "cell ({x}, {y}), id {id}");
+            covered += covers as usize;
+        }
+    }
+
+    // Every fragment id points at a real fragment.
+    for (_, cell) in cache.iter() {
+        for &fragment_id in cell.fragment_ids.iter().filter(|&&id| id != NO_FRAGMENT) {
+            assert!((fragment_id as usize) < cache.fragments.len(), "fragment id {fragment_id} out of range");
+        }
+    }
+    assert!(covered > 0);
+}
+
+#[test]
+fn simplified_fixture_has_no_gaps_along_shared_borders() {
+    let geometries = get_geojson_features_from_string(FIXTURE)
+        .features
+        .into_iter()
+        .map(|feature| Geometry::<Float>::try_from(feature.geometry.unwrap().value).unwrap())
+        .collect::<Vec<_>>();
+
+    // The edges that two of the zones (which are all polygons) share, in either direction.
+    let get_edges = |geometry: &Geometry<Float>| match geometry {
+        Geometry::Polygon(polygon) => polygon
+            .exterior()
+            .lines()
+            .map(|line| [line.start, line.end].map(|c| (c.x.to_bits(), c.y.to_bits())))
+            .collect::<HashSet<_>>(),
+        _ => unreachable!(),
+    };
+    let mut seen = HashSet::new();
+    let mut shared = HashSet::new();
+    for geometry in &geometries {
+        for [start, end] in get_edges(geometry) {
+            if !seen.insert([start.min(end), start.max(end)]) {
+                shared.insert([start.min(end), start.max(end)]);
+            }
+        }
+    }
+    assert!(!shared.is_empty());
+
+    let bounds = GeometryCollection::new_from(geometries.clone()).bounding_rect().unwrap();
+
+    // With the NED epsilon, and a far coarser one.
+    for epsilon in [NedTimezone::simplification_epsilon(), 0.1] {
+        let simplified = simplify_geometries(geometries.clone(), epsilon);
+        assert!(simplified.iter().map(|g| g.coords_count()).sum::<usize>() <= geometries.iter().map(|g| g.coords_count()).sum::<usize>());
+
+        // Every point of a shared border is still in one of the zones that share it...
+        for [start, end] in &shared {
+            let (start, end) = ([start.0, start.1].map(Float::from_bits), [end.0, end.1].map(Float::from_bits));
+
+            for k in 0..=16 {
+                let t = k as Float / 16.0;
+                let point = Coord {
+                    x: start[0] + (end[0] - start[0]) * t,
+                    y: start[1] + (end[1] - start[1]) * t,
+                };
+
+                assert!(simplified.iter().any(|geometry| geometry.intersects(&point)), "gap at {point:?} (epsilon {epsilon})");
+            }
+        }
+
+        // ...and no point is in two of them.
+        for i in 0..=256 {
+            for j in 0..=256 {
+                let point = Coord {
+                    x: bounds.min().x + bounds.width() * (i as Float + 0.5) / 257.0,
+                    y: bounds.min().y + bounds.height() * (j as Float + 0.5) / 257.0,
+                };
+
+                assert!(simplified.iter().filter(|geometry| geometry.contains(&point)).count() <= 1, "overlap at {point:?} (epsilon {epsilon})");
+            }
+        }
+    }
+}
+
+#[test]
+fn repairs_the_fixture_orientation_and_reports_it() {
+    let (items, report) = get_repaired_items_from_features::<NedTimezone>(get_geojson_features_from_string(FIXTURE));
+
+    // Natural Earth winds its exteriors clockwise, which is all that ingestion repairs in the fixture.
+    assert_eq!(report.feature_count, 3);
+    assert_eq!(report.dropped_count(), 0);
+    assert!(report.features.iter().all(|feature| feature.problems == [GeometryProblem::WrongOrientation]));
+    assert_eq!(items.len(), 3);
+
+    for item in items.iter() {
+        let polygons = match item.geometry() {
+            Geometry::Polygon(polygon) => vec![polygon.clone()],
+            Geometry::MultiPolygon(multi_polygon) => multi_polygon.0.clone(),
+            _ => unreachable!(),
+        };
+
+        for polygon in polygons {
+            assert!(polygon.exterior().is_ccw());
+            assert!(polygon.interiors().iter().all(|interior| interior.is_cw()));
+        }
+    }
+}
+
+#[test]
+fn skips_features_that_cannot_be_ingested() {
+    let mut features = get_geojson_features_from_string(FIXTURE);
+    features.features[0].properties.as_mut().unwrap().remove("zone");
+    features.features[1].geometry = None;
+
+    // A strict conversion fails with every diagnostic.
+    let strict = ConcreteVec::<NedTimezone>::try_from(features.clone()).unwrap_err();
+    assert_eq!(strict.len(), 2);
+
+    // The rest are renumbered, so ids stay positions.
+    let (items, diagnostics) = ConcreteVec::<NedTimezone>::from_features(features);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, 0);
+    assert_eq!(
+        diagnostics,
+        vec![
+            IngestionDiagnostic {
+                feature: 0,
+                error: IngestionError::MissingProperty { name: "zone".to_string() }
+            },
+            IngestionDiagnostic {
+                feature: 1,
+                error: IngestionError::MissingGeometry
+            },
+        ]
+    );
+    assert_eq!(diagnostics[0].to_string(), "Feature 0: The feature has no `zone` property.");
+
+    // Skipped features are reported by their source index, even when a feature before them was dropped.
+    let mut features = get_geojson_features_from_string(FIXTURE);
+    features.features[0].geometry = Some(geojson::Geometry::new(geojson::GeometryValue::Polygon {
+        coordinates: vec![vec![[0.0, 0.0].into(), [1.0, 1.0].into(), [2.0, 2.0].into(), [0.0, 0.0].into()]],
+    }));
+    features.features[2].properties.as_mut().unwrap().insert("zone".to_string(), "ten".into());
+
+    let (items, report) = get_repaired_items_from_features::<NedTimezone>(features);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, 0);
+    assert_eq!(report.dropped_count(), 1);
+    assert_eq!(
+        report.skipped,
+        vec![IngestionDiagnostic {
+            feature: 2,
+            error: IngestionError::InvalidProperty {
+                name: "zone".to_string(),
+                expected: "a number".to_string()
+            }
+        }]
+    );
+}
+
+#[test]
+#[cfg(feature = "self-contained")]
+fn generating_fails_past_the_problem_threshold() {
+    use rtz_core::geo::shared::{generate_bincodes, read_items_asset};
+
+    let directory = std::env::temp_dir();
+    let items = directory.join(format!("rtz_repaired_items_{}.bincode", std::process::id()));
+    let lookup = directory.join(format!("rtz_repaired_lookup_{}.bincode", std::process::id()));
+
+    // The fixture has three problems, so a threshold below that writes nothing.
+    let error = generate_bincodes::<NedTimezone>(get_geojson_features_from_string(FIXTURE), &items, &lookup, Some(2)).unwrap_err();
+    assert!(error.to_string().contains("more than the 2 allowed"));
+    assert!(!items.exists() && !lookup.exists());
+
+    let report = generate_bincodes::<NedTimezone>(get_geojson_features_from_string(FIXTURE), &items, &lookup, Some(3)).unwrap();
+    assert_eq!(report.problem_count(), 3);
+    assert_eq!(read_items_asset::<NedTimezone>(&items).unwrap().1.len(), 3);
+
+    std::fs::remove_file(&items).unwrap();
+    std::fs::remove_file(&lookup).unwrap();
+}
+
+#[test]
+#[cfg(feature = "self-contained")]
+fn generating_twice_writes_identical_files() {
+    use rtz_core::geo::shared::generate_bincodes;
+
+    // The generation time is the only thing that is not derived from the data, and reproducible builds pin it.
+    std::env::set_var("SOURCE_DATE_EPOCH", "1700000000");
+
+    let directory = std::env::temp_dir();
+    let paths = ["first", "second"].map(|run| {
+        let items = directory.join(format!("rtz_reproducible_items_{}_{}.bincode", run, std::process::id()));
+        let lookup = directory.join(format!("rtz_reproducible_lookup_{}_{}.bincode", run, std::process::id()));
+
+        generate_bincodes::<NedTimezone>(get_geojson_features_from_string(FIXTURE), &items, &lookup, None).unwrap();
+
+        (items, lookup)
+    });
+
+    let [(first_items, first_lookup), (second_items, second_lookup)] = &paths;
+    assert_eq!(std::fs::read(first_items).unwrap(), std::fs::read(second_items).unwrap());
+    assert_eq!(std::fs::read(first_lookup).unwrap(), std::fs::read(second_lookup).unwrap());
+
+    for (items, lookup) in paths {
+        std::fs::remove_file(items).unwrap();
+        std::fs::remove_file(lookup).unwrap();
+    }
 }
@@ -72,6 +72,19 @@ fn admin_osm_bench_cities(c: &mut Criterion) {
     });
 }
 
+fn admin_osm_bench_cities_many(c: &mut Criterion) {
+    let cities = (0..10_000)
+        .map(|_| cities_json::get_random_cities())
+        .map(|city| (city.lng as Float, city.lat as Float))
+        .collect::<Vec<_>>();
+
+    c.bench_function("admin_osm_bench_cities_many", |b| {
+        b.iter(|| {
+            black_box(OsmAdmin::lookup_many(&cities));
+        });
+    });
+}
+
 // TZ NED Features
 
 fn tz_ned_bench_lookup_assisted_sweep(c: &mut Criterion) {
@@ -171,7 +184,7 @@ fn tz_osm_bench_cities(c: &mut Criterion) {
 criterion_group!(
     name = benches;
     config = Criterion::default().sample_size(100);
-    targets = admin_osm_bench_lookup_assisted_sweep, admin_osm_bench_worst_case_full_lookup_single, admin_osm_bench_worst_case_lookup_assisted_single, admin_osm_bench_cities,
+    targets = admin_osm_bench_lookup_assisted_sweep, admin_osm_bench_worst_case_full_lookup_single, admin_osm_bench_worst_case_lookup_assisted_single, admin_osm_bench_cities, admin_osm_bench_cities_many,
               tz_ned_bench_lookup_assisted_sweep, tz_ned_bench_worst_case_full_lookup_single, tz_ned_bench_worst_case_lookup_assisted_single, tz_ned_bench_cities,
               tz_osm_bench_lookup_assisted_sweep, tz_osm_bench_worst_case_full_lookup_single, tz_osm_bench_worst_case_lookup_assisted_single, tz_osm_bench_cities
 );
@@ -3,6 +3,9 @@
 use clap::{Parser, Subcommand};
 use rtz_core::base::types::Void;
 
+#[cfg(any(feature = "tz-ned", feature = "tz-osm", feature = "admin-osm"))]
+use rtz_core::base::types::{Float, Res};
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
@@ -60,6 +63,10 @@ enum NedCommand {
         // `allow_hyphen_values` so a negative longitude (e.g. `-87.62,41.88`) isn't parsed as a flag.
         #[arg(allow_hyphen_values = true)]
         lng_lat: String,
+
+        /// When the pair resolves to nothing (e.g., it lies on a boundary), fall back to the nearest result within this many meters.
+        #[arg(long)]
+        nearest: Option<Float>,
     },
 }
 
@@ -72,6 +79,17 @@ enum OsmCommand {
         // `allow_hyphen_values` so a negative longitude (e.g. `-87.62,41.88`) isn't parsed as a flag.
         #[arg(allow_hyphen_values = true)]
         lng_lat: String,
+
+        /// When the pair resolves to nothing (e.g., it lies on a boundary), fall back to the nearest result within this many meters.
+        #[arg(long)]
+        nearest: Option<Float>,
+    },
+
+    /// Get the time zones that a route passes through, in order, from a GeoJSON `LineString`.
+    #[cfg(feature = "tz-osm")]
+    Route {
+        /// The path to a GeoJSON `LineString` (bare, or as the geometry of a `Feature`), or `-` to read it from stdin.
+        path: String,
     },
 
     /// Get the administrative information of the given lng,lat pair.
@@ -81,6 +99,10 @@ enum OsmCommand {
         // `allow_hyphen_values` so a negative longitude (e.g. `-87.62,41.88`) isn't parsed as a flag.
         #[arg(allow_hyphen_values = true)]
         lng_lat: String,
+
+        /// When the pair resolves to nothing (e.g., it lies on a boundary), fall back to the nearest result within this many meters.
+        #[arg(long)]
+        nearest: Option<Float>,
     },
 }
 
@@ -96,17 +118,15 @@ fn start(args: Args) -> Void {
     match args.command {
         Some(Command::Ned { ned_command }) => match ned_command {
             #[cfg(feature = "tz-ned")]
-            Some(NedCommand::Tz { lng_lat }) => {
-                use rtz_core::base::types::Float;
+            Some(NedCommand::Tz { lng_lat, nearest }) => {
                 use rtz_core::geo::tz::ned::NedTimezone;
-                use rtzlib::geo::shared::CanPerformGeoLookup;
 
                 let Some((lng, lat)) = lng_lat.split_once(',') else {
                     return Err(anyhow::Error::msg("Invalid lng,lat pair."));
                 };
 
                 let (lng, lat) = (lng.parse::<Float>()?, lat.parse::<Float>()?);
-                let tzs = NedTimezone::lookup(lng, lat);
+                let (tzs, distance) = lookup_or_nearest::<NedTimezone>(lng, lat, nearest)?;
 
                 for tz in tzs {
                     println!();
@@ -115,6 +135,7 @@ fn start(args: Args) -> Void {
                     println!("Offset Seconds:  {}", tz.raw_offset);
                     println!("Description:     {}", tz.description);
                     println!("DST Description: {}", tz.dst_description.as_deref().unwrap_or(""));
+                    print_distance("Distance:        ", distance);
                 }
 
                 println!();
@@ -126,42 +147,71 @@ fn start(args: Args) -> Void {
         },
         Some(Command::Osm { osm_command }) => match osm_command {
             #[cfg(feature = "tz-osm")]
-            Some(OsmCommand::Tz { lng_lat }) => {
-                use rtz_core::base::types::Float;
+            Some(OsmCommand::Tz { lng_lat, nearest }) => {
                 use rtz_core::geo::tz::osm::OsmTimezone;
-                use rtzlib::geo::shared::CanPerformGeoLookup;
 
                 let Some((lng, lat)) = lng_lat.split_once(',') else {
                     return Err(anyhow::Error::msg("Invalid lng,lat pair."));
                 };
 
                 let (lng, lat) = (lng.parse::<Float>()?, lat.parse::<Float>()?);
-                let tzs = OsmTimezone::lookup(lng, lat);
+                let (tzs, distance) = lookup_or_nearest::<OsmTimezone>(lng, lat, nearest)?;
 
                 for tz in tzs {
                     println!();
                     println!("Identifier:      {}", tz.identifier);
+                    print_distance("Distance:        ", distance);
+                }
+
+                println!();
+            }
+            #[cfg(feature = "tz-osm")]
+            Some(OsmCommand::Route { path }) => {
+                use geojson::GeoJson;
+                use rtz_core::geo::tz::osm::OsmTimezone;
+                use rtzlib::geo::shared::CanPerformGeoLookup;
+
+                let json = if path == "-" {
+                    std::io::read_to_string(std::io::stdin())?
+                } else {
+                    std::fs::read_to_string(&path)?
+                };
+
+                let geometry = match json.parse::<GeoJson>()? {
+                    GeoJson::Geometry(geometry) => geometry,
+                    GeoJson::Feature(geojson::Feature { geometry: Some(geometry), .. }) => geometry,
+                    _ => return Err(anyhow::Error::msg("Expected a GeoJSON geometry, or a feature with one.")),
+                };
+
+                let Ok(geo::Geometry::LineString(route)) = geo::Geometry::<Float>::try_from(geometry.value) else {
+                    return Err(anyhow::Error::msg("Expected a GeoJSON `LineString`."));
+                };
+
+                for span in OsmTimezone::lookup_route(&route) {
+                    println!();
+                    println!("Identifier:      {}", span.items.iter().map(|tz| tz.identifier.as_ref()).collect::<Vec<_>>().join(", "));
+                    println!("Start:           {},{} ({:.0} m)", span.start.x, span.start.y, span.start_distance);
+                    println!("End:             {},{} ({:.0} m)", span.end.x, span.end.y, span.end_distance);
                 }
 
                 println!();
             }
             #[cfg(feature = "admin-osm")]
-            Some(OsmCommand::Admin { lng_lat }) => {
-                use rtz_core::base::types::Float;
+            Some(OsmCommand::Admin { lng_lat, nearest }) => {
                 use rtz_core::geo::admin::osm::OsmAdmin;
-                use rtzlib::geo::shared::CanPerformGeoLookup;
 
                 let Some((lng, lat)) = lng_lat.split_once(',') else {
                     return Err(anyhow::Error::msg("Invalid lng,lat pair."));
                 };
 
                 let (lng, lat) = (lng.parse::<Float>()?, lat.parse::<Float>()?);
-                let admins = OsmAdmin::lookup(lng, lat);
+                let (admins, distance) = lookup_or_nearest::<OsmAdmin>(lng, lat, nearest)?;
 
                 for admin in admins {
                     println!();
                     println!("Name:      {}", admin.name);
                     println!("Level:     {}", admin.level);
+                    print_distance("Distance:  ", distance);
                 }
 
                 println!();
@@ -222,6 +272,31 @@ fn start(args: Args) -> Void {
     Ok(())
 }
 
+/// Looks up a point, falling back to the nearest result within `nearest` meters when it resolves to nothing.
+///
+/// The distance to the result's boundary is returned only when the fallback was taken, and a
+/// coordinate that is not a point on the globe (e.g., a latitude of `95`) is an error.
+#[cfg(any(feature = "tz-ned", feature = "tz-osm", feature = "admin-osm"))]
+fn lookup_or_nearest<T>(lng: Float, lat: Float, nearest: Option<Float>) -> Res<(Vec<&'static T>, Option<Float>)>
+where
+    T: rtzlib::CanPerformGeoLookup,
+{
+    let results = T::try_lookup(lng, lat)?;
+
+    Ok(match nearest.filter(|_| results.is_empty()).and_then(|max_distance| T::lookup_nearest(lng, lat, max_distance)) {
+        Some((item, distance)) => (vec![item], Some(distance)),
+        None => (results, None),
+    })
+}
+
+/// Prints the distance to a fallback result's boundary, if there is one.
+#[cfg(any(feature = "tz-ned", feature = "tz-osm", feature = "admin-osm"))]
+fn print_distance(label: &str, distance: Option<Float>) {
+    if let Some(distance) = distance {
+        println!("{}{:.0} m", label, distance);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,7 +306,10 @@ mod tests {
     fn can_resolve_ned() {
         start(Args {
             command: Some(Command::Ned {
-                ned_command: Some(NedCommand::Tz { lng_lat: "-87.62,41.88".to_string() }),
+                ned_command: Some(NedCommand::Tz {
+                    lng_lat: "-87.62,41.88".to_string(),
+                    nearest: None,
+                }),
             }),
         })
         .unwrap();
@@ -242,7 +320,10 @@ mod tests {
     fn can_resolve_osm() {
         start(Args {
             command: Some(Command::Osm {
-                osm_command: Some(OsmCommand::Tz { lng_lat: "-87.62,41.88".to_string() }),
+                osm_command: Some(OsmCommand::Tz {
+                    lng_lat: "-87.62,41.88".to_string(),
+                    nearest: None,
+                }),
             }),
         })
         .unwrap();
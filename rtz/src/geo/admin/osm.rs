@@ -1,81 +1,65 @@
 //! The [OpenStreetMap](https://www.openstreetmap.org/) admin lookup module.
 
-use std::{collections::HashMap, sync::OnceLock};
+use std::sync::OnceLock;
 
-use rtz_core::geo::{
-    admin::osm::OsmAdmin,
-    shared::{ConcreteVec, EncodableIds, RoundLngLat},
-};
+use rtz_core::geo::admin::osm::OsmAdmin;
 
 use crate::{
-    geo::shared::{HasItemData, HasLookupData},
+    geo::{
+        index::GeoIndex,
+        shared::{HasGeoIndex, HasItemData, HasLookupData},
+    },
     CanPerformGeoLookup,
 };
 
-#[cfg(feature = "self-contained")]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 use include_bytes_aligned::include_bytes_aligned;
 
 // Trait impls.
 
-impl HasItemData for OsmAdmin {
-    fn get_mem_items() -> &'static ConcreteVec<OsmAdmin> {
-        static TIMEZONES: OnceLock<ConcreteVec<OsmAdmin>> = OnceLock::new();
+impl HasGeoIndex for OsmAdmin {
+    fn get_mem_index_cell() -> &'static OnceLock<GeoIndex<'static, OsmAdmin>> {
+        static INDEX: OnceLock<GeoIndex<'static, OsmAdmin>> = OnceLock::new();
+
+        &INDEX
+    }
 
+    fn load_mem_index() -> GeoIndex<'static, OsmAdmin> {
         #[cfg(feature = "self-contained")]
         {
-            TIMEZONES.get_or_init(|| crate::geo::shared::decode_binary_data(ADMIN_BINCODE))
+            use crate::geo::shared::DecodeMode;
+
+            GeoIndex::from_bytes_with(ADMIN_BINCODE, LOOKUP_BINCODE, DecodeMode::Trusted).unwrap_or_else(|e| panic!("Could not decode the embedded data: {:#}", e))
         }
 
         #[cfg(not(feature = "self-contained"))]
         {
             use rtz_core::geo::{admin::osm::get_geojson_features_from_source, shared::get_items_from_features};
 
-            TIMEZONES.get_or_init(|| {
-                let features = get_geojson_features_from_source();
+            let features = get_geojson_features_from_source();
 
-                get_items_from_features(features)
-            })
+            GeoIndex::from_items(get_items_from_features(features))
         }
     }
 }
 
-impl HasLookupData for OsmAdmin {
-    type Lookup = EncodableIds;
-
-    fn get_mem_lookup() -> &'static HashMap<RoundLngLat, Self::Lookup> {
-        static CACHE: OnceLock<HashMap<RoundLngLat, EncodableIds>> = OnceLock::new();
-
-        #[cfg(feature = "self-contained")]
-        {
-            CACHE.get_or_init(|| crate::geo::shared::decode_binary_data(LOOKUP_BINCODE))
-        }
-
-        #[cfg(not(feature = "self-contained"))]
-        {
-            use rtz_core::geo::shared::get_lookup_from_geometries;
+impl HasItemData for OsmAdmin {}
 
-            CACHE.get_or_init(|| {
-                let cache = get_lookup_from_geometries(OsmAdmin::get_mem_items());
-
-                cache
-            })
-        }
-    }
-}
+impl HasLookupData for OsmAdmin {}
 
 impl CanPerformGeoLookup for OsmAdmin {}
 
 // Statics.
 
-#[cfg(all(host_family_unix, feature = "self-contained"))]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 static ADMIN_BINCODE: &[u8] = include_bytes_aligned!(8, "../../../assets/osm_admins.bincode");
-#[cfg(all(host_family_windows, feature = "self-contained"))]
-static ADMIN_BINCODE: &[u8] = include_bytes_aligned!(8, "..\\..\\..\\assets\\osm_admins.bincode");
+#[cfg(all(feature = "self-contained", feature = "compressed-assets"))]
+static ADMIN_BINCODE: &[u8] = include_bytes!("../../../assets/osm_admins.bincode.zst");
 
-#[cfg(all(host_family_unix, feature = "self-contained"))]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 static LOOKUP_BINCODE: &[u8] = include_bytes_aligned!(8, "../../../assets/osm_admin_lookup.bincode");
-#[cfg(all(host_family_windows, feature = "self-contained"))]
-static LOOKUP_BINCODE: &[u8] = include_bytes_aligned!(8, "..\\..\\..\\assets\\osm_admin_lookup.bincode");
+#[cfg(all(feature = "self-contained", feature = "compressed-assets"))]
+static LOOKUP_BINCODE: &[u8] = include_bytes!("../../../assets/osm_admin_lookup.bincode.zst");
 
 // Tests.
 
@@ -161,6 +145,28 @@ mod tests {
         assert!(tzs.len() >= 10, "too few items in cell (-87, 38): {}", tzs.len());
     }
 
+    #[test]
+    fn can_lookup_many_in_input_order() {
+        let mut points = (0..1_000)
+            .map(|_| (rand::random::<Float>() * 360.0 - 180.0, rand::random::<Float>() * 180.0 - 90.0))
+            .collect::<Vec<_>>();
+        points.extend([(-121.0, 46.0), (-121.0, 46.0), (180.0, 90.0), (Float::NAN, 0.5)]);
+
+        let many = OsmAdmin::lookup_many(&points);
+
+        assert_eq!(many.len(), points.len());
+        assert!(many[points.len() - 1].is_empty());
+        for (&(x, y), items) in points.iter().zip(many) {
+            assert_eq!(
+                OsmAdmin::lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                items.into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
     #[test]
     fn can_verify_lookup_assisted_accuracy() {
         (0..100).into_par_iter().for_each(|_| {
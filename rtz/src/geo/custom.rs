@@ -0,0 +1,155 @@
+//! The custom dataset lookup module, which indexes any GeoJSON feature collection of polygons.
+//!
+//! A custom dataset has no embedded data, so its default, global index is empty until one is set
+//! (see [`HasGeoIndex::set_mem_index`]), after which [`CanPerformGeoLookup`] works on it as on the
+//! built-in datasets.  Any number of other custom datasets can be held as [`GeoIndex`]es.
+
+use std::{path::Path, sync::OnceLock};
+
+use anyhow::Context;
+use geojson::{FeatureCollection, GeoJson};
+use rtz_core::{
+    base::types::Res,
+    geo::{
+        custom::{get_custom_items_from_features, CustomFeature, CustomFeatureMapping},
+        shared::{ConcreteVec, RepairReport},
+    },
+};
+
+use crate::{
+    geo::{
+        index::GeoIndex,
+        shared::{HasGeoIndex, HasItemData, HasLookupData},
+    },
+    CanPerformGeoLookup,
+};
+
+// Trait impls.
+
+impl HasGeoIndex for CustomFeature {
+    fn get_mem_index_cell() -> &'static OnceLock<GeoIndex<'static, CustomFeature>> {
+        static INDEX: OnceLock<GeoIndex<'static, CustomFeature>> = OnceLock::new();
+
+        &INDEX
+    }
+
+    fn load_mem_index() -> GeoIndex<'static, CustomFeature> {
+        GeoIndex::from_items(ConcreteVec::from(Vec::new()))
+    }
+}
+
+impl HasItemData for CustomFeature {}
+
+impl HasLookupData for CustomFeature {}
+
+impl CanPerformGeoLookup for CustomFeature {}
+
+// Index constructors.
+
+impl GeoIndex<'static, CustomFeature> {
+    /// Indexes a custom dataset from its GeoJSON features, read through `mapping`, building the
+    /// lookup cache over them (see [`GeoIndex::from_items`]), and returns it along with the report of
+    /// the features that ingestion repaired, dropped, or skipped (see [`get_custom_items_from_features`]).
+    pub fn from_geojson(features: FeatureCollection, mapping: &CustomFeatureMapping) -> (Self, RepairReport) {
+        let (items, report) = get_custom_items_from_features(features, mapping);
+
+        (Self::from_items(items), report)
+    }
+
+    /// Indexes a custom dataset from a GeoJSON file (see [`GeoIndex::from_geojson`]).
+    pub fn from_geojson_file(path: impl AsRef<Path>, mapping: &CustomFeatureMapping) -> Res<(Self, RepairReport)> {
+        let path = path.as_ref();
+
+        let geojson = std::fs::read_to_string(path).with_context(|| format!("Could not read `{}`.", path.display()))?;
+        let features = geojson
+            .parse::<GeoJson>()
+            .and_then(FeatureCollection::try_from)
+            .with_context(|| format!("`{}` is not a GeoJSON feature collection.", path.display()))?;
+
+        Ok(Self::from_geojson(features, mapping))
+    }
+}
+
+// Tests.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rtz_core::geo::shared::get_geojson_features_from_string;
+
+    fn get_test_index() -> GeoIndex<'static, CustomFeature> {
+        let json = r#"{
+            "type": "FeatureCollection",
+            "features": [
+                { "type": "Feature", "properties": { "code": "W", "region": "West" },
+                  "geometry": { "type": "Polygon", "coordinates": [[[-125, 30], [-110, 30], [-110, 50], [-125, 50], [-125, 30]]] } },
+                { "type": "Feature", "properties": { "code": "E", "region": "East" },
+                  "geometry": { "type": "Polygon", "coordinates": [[[-110, 30], [-70, 30], [-70, 50], [-110, 50], [-110, 30]]] } }
+            ]
+        }"#;
+
+        let mapping = CustomFeatureMapping::default().with_key("code").with_name("region");
+
+        GeoIndex::from_geojson(get_geojson_features_from_string(json), &mapping).0
+    }
+
+    #[test]
+    fn can_index_a_custom_dataset() {
+        let index = get_test_index();
+
+        let regions = index.lookup(-121.0, 46.0);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].key.as_deref(), Some("W"));
+        assert_eq!(regions[0].name.as_deref(), Some("West"));
+
+        assert_eq!(index.lookup(-80.0, 40.0)[0].key.as_deref(), Some("E"));
+        assert!(index.lookup(0.0, 0.0).is_empty());
+        assert_eq!(index.lookup_bbox(-115.0, 35.0, -105.0, 40.0).len(), 2);
+    }
+
+    #[test]
+    fn can_perform_lookups_on_the_global_index() {
+        assert!(CustomFeature::set_mem_index(get_test_index()).is_ok());
+
+        assert_eq!(CustomFeature::lookup(-121.0, 46.0)[0].name.as_deref(), Some("West"));
+        assert_eq!(CustomFeature::lookup_nearest(-130.0, 46.0, 1_000_000.0).unwrap().0.key.as_deref(), Some("W"));
+    }
+
+    #[test]
+    fn can_load_a_custom_dataset_from_a_file() {
+        let path = std::env::temp_dir().join(format!("rtz_custom_{}.geojson", std::process::id()));
+
+        std::fs::write(&path, r#"{ "type": "FeatureCollection", "features": [] }"#).unwrap();
+        assert!(GeoIndex::from_geojson_file(&path, &CustomFeatureMapping::default()).unwrap().0.get_items().is_empty());
+
+        std::fs::write(&path, r#"{ "type": "Point", "coordinates": [0, 0] }"#).unwrap();
+        assert!(GeoIndex::from_geojson_file(&path, &CustomFeatureMapping::default()).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "runtime-data")]
+    fn can_load_generated_custom_bincodes() {
+        use rtz_core::geo::custom::generate_custom_bincodes;
+
+        let json = r#"{ "type": "FeatureCollection", "features": [{ "type": "Feature", "properties": { "region": "West" },
+            "geometry": { "type": "Polygon", "coordinates": [[[-125, 30], [-110, 30], [-110, 50], [-125, 50], [-125, 30]]] } }] }"#;
+        let directory = std::env::temp_dir();
+        let items = directory.join(format!("rtz_custom_items_{}.bincode", std::process::id()));
+        let lookup = directory.join(format!("rtz_custom_lookup_{}.bincode", std::process::id()));
+
+        let mapping = CustomFeatureMapping::default().with_name("region");
+        let report = generate_custom_bincodes(get_geojson_features_from_string(json), &mapping, &items, &lookup, Some(0)).unwrap();
+        assert_eq!(report.problem_count(), 0);
+
+        let index = GeoIndex::<CustomFeature>::from_files(&items, &lookup).unwrap();
+
+        assert_eq!(index.lookup(-121.0, 46.0)[0].name.as_deref(), Some("West"));
+        assert!(index.validate().is_ok());
+
+        std::fs::remove_file(&items).unwrap();
+        std::fs::remove_file(&lookup).unwrap();
+    }
+}
@@ -0,0 +1,640 @@
+//! The [`GeoIndex`], which holds a dataset's items and lookup cache, and performs lookups over them.
+//!
+//! Each dataset has a default, global index (see [`HasGeoIndex`](crate::geo::shared::HasGeoIndex)),
+//! which backs the static methods of [`CanPerformGeoLookup`](crate::CanPerformGeoLookup), but an
+//! index is an ordinary value: two dataset versions can be held side by side, a small custom
+//! dataset can be indexed for a test, and dropping an index frees its memory (all but the bytes that
+//! a decoded index borrows, which must live for the rest of the program).
+
+use std::ops::Deref;
+
+use geo::{BooleanOps, BoundingRect, ChamberlainDuquetteArea, Contains, Coord, Geometry, Intersects, LineString, MultiPolygon};
+use rayon::prelude::{IntoParallelIterator, ParallelIterator, ParallelSliceMut};
+use rtz_core::{
+    base::types::{CoordinateError, Float, LngLat},
+    geo::shared::{
+        clip_geometry_to_rect, get_bbox_rects, get_cells_in_rect, get_cells_within_distance, get_distance_to_boundary, get_fragment_rect, get_geodesic_distance, get_line_crossings,
+        get_lookup_from_geometries, split_line_at_antimeridian, validate_items, AssetError, ConcreteVec, DatasetInfo, EncodableLookup, HasGeometry, Id, RoundDegree,
+    },
+    geo::tz::shared::IsTimezone,
+};
+
+use crate::geo::shared::RouteSpan;
+
+#[cfg(feature = "runtime-data")]
+use crate::geo::shared::read_binary_data;
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+use crate::geo::shared::{try_decode_binary_data, DecodeMode};
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+use anyhow::Context;
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+use rtz_core::{base::types::Res, geo::shared::AssetKind};
+#[cfg(feature = "runtime-data")]
+use std::path::Path;
+
+// Types.
+
+/// Data that a [`GeoIndex`] either owns or borrows.
+#[derive(Debug)]
+enum IndexData<'a, V> {
+    Owned(V),
+    Borrowed(&'a V),
+}
+
+impl<V> Deref for IndexData<'_, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            IndexData::Owned(value) => value,
+            IndexData::Borrowed(value) => value,
+        }
+    }
+}
+
+/// A dataset's items, and the lookup cache over them, which together answer lookups.
+///
+/// The items and the lookup are either owned (see [`GeoIndex::new`] and [`GeoIndex::from_items`])
+/// or borrowed (see [`GeoIndex::borrowed`]), and the items a lookup returns borrow from the index.
+/// Either way, the index knows what the dataset was generated from (see [`GeoIndex::dataset_info`]).
+#[derive(Debug)]
+pub struct GeoIndex<'a, T>
+where
+    T: 'static,
+{
+    items: IndexData<'a, ConcreteVec<T>>,
+    lookup: IndexData<'a, EncodableLookup>,
+    info: DatasetInfo,
+}
+
+impl<T> GeoIndex<'static, T> {
+    /// Creates an index that owns its items and the lookup cache over them.
+    ///
+    /// The lookup must have been built from these items, and the items are described as generated
+    /// now, by this build (see [`GeoIndex::from_bytes`] to keep what a bincode says instead).
+    pub fn new(items: ConcreteVec<T>, lookup: EncodableLookup) -> Self
+    where
+        T: HasGeometry,
+    {
+        Self {
+            info: DatasetInfo::new::<T>(items.len()),
+            items: IndexData::Owned(items),
+            lookup: IndexData::Owned(lookup),
+        }
+    }
+
+    /// Creates an index that owns its items, building the lookup cache over them.
+    ///
+    /// This is how a small custom dataset is indexed; for a large one, building the lookup takes a while.
+    pub fn from_items(items: ConcreteVec<T>) -> Self
+    where
+        T: HasGeometry + Send + Sync,
+    {
+        let lookup = get_lookup_from_geometries(&items);
+
+        Self::new(items, lookup)
+    }
+}
+
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+impl<T> GeoIndex<'static, T>
+where
+    T: HasGeometry,
+    ConcreteVec<T>: bincode::Decode<()> + bincode::BorrowDecode<'static, ()>,
+{
+    /// Decodes an index from the bytes of an items bincode and its lookup bincode (e.g.,
+    /// `osm_time_zones.bincode` and `osm_time_zone_lookup.bincode`), which must come from the same
+    /// build of the dataset, with the same `Float` precision as this build.
+    ///
+    /// Each bincode's header is checked against this build, and against the other (see
+    /// [`AssetError`]), and becomes the index's [`GeoIndex::dataset_info`].
+    ///
+    /// Geometries are borrow-decoded straight over the bytes (unless `owned-decode`), so they must
+    /// live for the rest of the program, and are copied first if they are not 8-byte aligned.
+    ///
+    /// The bytes may come from anywhere, so they are decoded with [`DecodeMode::Safe`]: see
+    /// [`GeoIndex::from_bytes_with`] to decode bytes that came from this build.
+    pub fn from_bytes(items: &'static [u8], lookup: &'static [u8]) -> Res<Self> {
+        Self::from_bytes_with(items, lookup, DecodeMode::Safe)
+    }
+
+    /// Decodes an index from the bytes of an items bincode and its lookup bincode (see [`GeoIndex::from_bytes`]),
+    /// trusting them as much as `mode` says.
+    ///
+    /// Either way, each bincode's checksum is verified, and, with [`DecodeMode::Safe`], the decoded
+    /// index is checked with [`GeoIndex::validate`].
+    pub fn from_bytes_with(items: &'static [u8], lookup: &'static [u8], mode: DecodeMode) -> Res<Self> {
+        let (items_header, items): (_, ConcreteVec<T>) = try_decode_binary_data(items, AssetKind::Items, mode).context("Could not decode the items bincode.")?;
+
+        if items.len() as u64 != items_header.info.item_count {
+            return Err(AssetError::ItemCountMismatch {
+                expected: items_header.info.item_count,
+                found: items.len() as u64,
+            })
+            .context("Could not decode the items bincode.");
+        }
+
+        let (lookup_header, lookup) = try_decode_binary_data(lookup, AssetKind::Lookup, mode).context("Could not decode the lookup bincode.")?;
+
+        if lookup_header.info != items_header.info {
+            return Err(AssetError::MismatchedLookup {
+                items: Box::new(items_header.info),
+                lookup: Box::new(lookup_header.info),
+            }
+            .into());
+        }
+
+        let index = Self {
+            items: IndexData::Owned(items),
+            lookup: IndexData::Owned(lookup),
+            info: items_header.info,
+        };
+
+        if mode == DecodeMode::Safe {
+            index.validate()?;
+        }
+
+        Ok(index)
+    }
+
+    /// Loads an index from an items bincode file and its lookup bincode file (see [`GeoIndex::from_bytes`]),
+    /// so that data updates can ship separately from the binary.
+    ///
+    /// With the `mmap` feature, the files are memory-mapped rather than read into memory.  Either
+    /// way, the data is held for the rest of the program, since geometries point into it.
+    #[cfg(feature = "runtime-data")]
+    pub fn from_files(items: impl AsRef<Path>, lookup: impl AsRef<Path>) -> Res<Self> {
+        let (items, lookup) = (items.as_ref(), lookup.as_ref());
+
+        let items = read_binary_data(items).with_context(|| format!("Could not read `{}`.", items.display()))?;
+        let lookup = read_binary_data(lookup).with_context(|| format!("Could not read `{}`.", lookup.display()))?;
+
+        Self::from_bytes(items, lookup)
+    }
+}
+
+impl<'a, T> GeoIndex<'a, T> {
+    /// Creates an index that borrows its items and the lookup cache over them, described as generated now, by this build.
+    pub fn borrowed(items: &'a ConcreteVec<T>, lookup: &'a EncodableLookup) -> Self
+    where
+        T: HasGeometry,
+    {
+        Self {
+            items: IndexData::Borrowed(items),
+            lookup: IndexData::Borrowed(lookup),
+            info: DatasetInfo::new::<T>(items.len()),
+        }
+    }
+
+    /// Gets what the dataset was generated from, and with which build parameters.
+    pub fn dataset_info(&self) -> &DatasetInfo {
+        &self.info
+    }
+
+    /// Checks that the items are addressed by their ids, and that the lookup refers only to them
+    /// (and to its own fragments), so that no lookup can index out of bounds over the index.
+    ///
+    /// An index decoded with [`DecodeMode::Safe`](crate::geo::shared::DecodeMode::Safe) has already been checked.
+    pub fn validate(&self) -> Result<(), AssetError>
+    where
+        T: HasGeometry,
+    {
+        validate_items(&self.items)?;
+
+        self.lookup.validate(self.items.len())
+    }
+
+    /// Gets the items of the index.
+    pub fn get_items(&self) -> &ConcreteVec<T> {
+        &self.items
+    }
+
+    /// Gets the lookup cell table (and the geometry fragments it refers to) of the index.
+    pub fn get_lookup(&self) -> &EncodableLookup {
+        &self.lookup
+    }
+
+    /// Maps item ids into the items to which they refer.
+    fn get_items_by_id(&self, ids: impl IntoIterator<Item = Id>) -> Vec<&T> {
+        ids.into_iter().map(|id| &self.items[id as usize]).collect()
+    }
+}
+
+impl<T> GeoIndex<'_, T>
+where
+    T: HasGeometry,
+{
+    /// Get the cache-driven item for a given longitude (x) and latitude (y).
+    ///
+    /// Some data sources allow for multiple results, so this is a vector.
+    ///
+    /// A coordinate that is not a point on the globe (e.g., `NaN`, or a latitude of `95`) has no
+    /// items; use [`GeoIndex::try_lookup`] to tell it apart from a point with none.
+    pub fn lookup(&self, xf: Float, yf: Float) -> Vec<&T> {
+        self.try_lookup(xf, yf).unwrap_or_default()
+    }
+
+    /// Get the cache-driven item for a given longitude (x) and latitude (y), or the reason the
+    /// coordinate is not a point on the globe (see [`LngLat::new`]).
+    pub fn try_lookup(&self, xf: Float, yf: Float) -> Result<Vec<&T>, CoordinateError> {
+        LngLat::new(xf, yf).map(|point| self.lookup_at(point))
+    }
+
+    /// Get the cache-driven item for a validated point.
+    ///
+    /// Candidates that the lookup cache knows to fully cover the point's quadtree leaf are returned
+    /// without a `contains` check, and the rest are checked against their geometry clipped to the leaf.
+    pub fn lookup_at(&self, point: LngLat) -> Vec<&T> {
+        let (xf, yf) = (point.lng(), point.lat());
+        let (x, y) = point.cell();
+
+        let lookup = self.get_lookup();
+        let Some(cell) = lookup.get(&(x, y)) else {
+            return Vec::new();
+        };
+
+        let items = self.get_items();
+
+        cell.candidates_at((x, y), xf, yf)
+            .iter()
+            .filter(|&(_, fragment_id)| fragment_id.is_none_or(|f| lookup.fragments[f as usize].0.contains(&Coord { x: xf, y: yf })))
+            .map(|(id, _)| &items[id as usize])
+            .collect()
+    }
+
+    /// Get the cache-driven items for many longitudes (x) and latitudes (y) at once, in input order.
+    ///
+    /// The points are sorted by cell (so that each cell's candidates and fragments stay hot while
+    /// its points resolve) and then resolved in parallel, which makes this far faster than calling
+    /// [`GeoIndex::lookup`] in a loop for bulk workloads.
+    pub fn lookup_many(&self, points: &[(Float, Float)]) -> Vec<Vec<&T>>
+    where
+        T: Sync,
+    {
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        order.par_sort_unstable_by_key(|&i| LngLat::try_from(points[i]).ok().map(|point| point.cell()));
+
+        let resolved = order.into_par_iter().map(|i| (i, self.lookup(points[i].0, points[i].1))).collect::<Vec<_>>();
+
+        let mut results = vec![Vec::new(); points.len()];
+        for (i, items) in resolved {
+            results[i] = items;
+        }
+
+        results
+    }
+
+    /// Get the item nearest to a given longitude (x) and latitude (y), along with the geodesic
+    /// distance (in meters) to its boundary, as long as that is within `max_distance` meters.
+    ///
+    /// A point that [`GeoIndex::lookup`] resolves yields its first item at distance `0`.
+    /// Otherwise (e.g., on a boundary, or just off a coastline), every item in the cells within
+    /// reach is measured, so the cost of a miss grows with `max_distance`.
+    pub fn lookup_nearest(&self, xf: Float, yf: Float, max_distance: Float) -> Option<(&T, Float)> {
+        let point = LngLat::new(xf, yf).ok()?;
+
+        if let Some(item) = self.lookup_at(point).into_iter().next() {
+            return Some((item, 0.0));
+        }
+
+        let (xf, yf) = (point.lng(), point.lat());
+
+        let lookup = self.get_lookup();
+        let mut ids = get_cells_within_distance(xf, yf, max_distance)
+            .into_iter()
+            .filter_map(|cell| lookup.get(&cell))
+            .flat_map(|cell| cell.ids.iter().copied())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        self.get_items_by_id(ids)
+            .into_iter()
+            .filter_map(|item| get_distance_to_boundary(item.geometry(), xf, yf).map(|distance| (item, distance)))
+            .filter(|&(_, distance)| distance <= max_distance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Get every item that intersects a `(west, south, east, north)` bounding box, edges included.
+    ///
+    /// A `west` greater than `east` crosses the antimeridian (as in GeoJSON).  Candidates come from
+    /// the cells the box touches, and each is returned once, in id order, if its geometry
+    /// intersects the box.
+    pub fn lookup_bbox(&self, west: Float, south: Float, east: Float, north: Float) -> Vec<&T> {
+        if [west, south, east, north].iter().any(|v| v.is_nan()) {
+            return Vec::new();
+        }
+
+        let rects = get_bbox_rects(west, south, east, north);
+
+        let lookup = self.get_lookup();
+        let mut ids = rects
+            .iter()
+            .flat_map(|&rect| get_cells_in_rect(rect))
+            .filter_map(|cell| lookup.get(&cell))
+            .flat_map(|cell| cell.ids.iter().copied())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        self.get_items_by_id(ids).into_iter().filter(|item| rects.iter().any(|rect| item.geometry().intersects(rect))).collect()
+    }
+
+    /// Get every item that overlaps a (multi) polygon, along with the fraction of the polygon's area
+    /// that falls in it; any other geometry overlaps nothing.
+    ///
+    /// Candidates come from the cells the polygon's bounding box touches, and each is clipped to
+    /// that box before the exact intersection, so a large item only pays for its nearby edges.
+    /// Areas are measured on the sphere, so fractions hold at any latitude.
+    pub fn lookup_geometry(&self, geometry: &Geometry<Float>) -> Vec<(&T, Float)> {
+        let Some(polygon) = get_multi_polygon(geometry) else {
+            return Vec::new();
+        };
+
+        let area = polygon.chamberlain_duquette_unsigned_area();
+        let Some(bbox) = polygon.bounding_rect().filter(|_| area > 0.0) else {
+            return Vec::new();
+        };
+
+        let lookup = self.get_lookup();
+        let mut ids = get_cells_in_rect(bbox)
+            .filter_map(|cell| lookup.get(&cell))
+            .flat_map(|cell| cell.ids.iter().copied())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        // The clip is exact strictly inside the rect, and the padding keeps the whole polygon strictly inside it.
+        let rect = get_fragment_rect(bbox);
+
+        self.get_items_by_id(ids)
+            .into_iter()
+            .filter(|item| item.geometry().intersects(&bbox))
+            .filter_map(|item| {
+                let Geometry::MultiPolygon(clipped) = clip_geometry_to_rect(item.geometry(), rect) else {
+                    return None;
+                };
+                let fraction = polygon.intersection(&clipped).chamberlain_duquette_unsigned_area() / area;
+
+                (fraction > 0.0).then_some((item, fraction.min(1.0)))
+            })
+            .collect()
+    }
+
+    /// Get the stretches of a route that lie in each item, in order along the route.
+    ///
+    /// Segments run straight in longitude and latitude (as in GeoJSON), and take the short way
+    /// round across the antimeridian.  Each segment is cut wherever it crosses the boundary of a
+    /// candidate from the cells under it, and each piece is looked up once, so no zone is missed
+    /// however narrow.  Consecutive pieces in the same items merge into one [`RouteSpan`].
+    pub fn lookup_route(&self, route: &LineString<Float>) -> Vec<RouteSpan<'_, T>> {
+        let lookup = self.get_lookup();
+        let items = self.get_items();
+
+        let mut spans: Vec<RouteSpan<'_, T>> = Vec::new();
+        let mut distance = 0.0;
+
+        for line in route.lines().flat_map(split_line_at_antimeridian) {
+            if line.start == line.end || [line.start.x, line.start.y, line.end.x, line.end.y].iter().any(|v| v.is_nan()) {
+                continue;
+            }
+
+            let mut ids = get_cells_in_rect(line.bounding_rect())
+                .filter_map(|cell| lookup.get(&cell))
+                .flat_map(|cell| cell.ids.iter().copied())
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids.dedup();
+
+            let mut crossings = ids.into_iter().flat_map(|id| get_line_crossings(line, items[id as usize].geometry())).collect::<Vec<_>>();
+            crossings.extend([0.0, 1.0]);
+            crossings.sort_by(|a, b| a.total_cmp(b));
+            crossings.dedup();
+
+            let point_at = |t: Float| Coord {
+                x: line.start.x + t * line.dx(),
+                y: line.start.y + t * line.dy(),
+            };
+
+            for window in crossings.windows(2) {
+                let (start, end) = (point_at(window[0]), point_at(window[1]));
+                if start == end {
+                    continue;
+                }
+
+                let middle = point_at((window[0] + window[1]) / 2.0);
+                let found = self.lookup(middle.x, middle.y);
+                let end_distance = distance + get_geodesic_distance(line.start, end);
+
+                match spans.last_mut() {
+                    Some(span) if span.items.iter().map(|i| i.id()).eq(found.iter().map(|i| i.id())) => {
+                        span.end = end;
+                        span.end_distance = end_distance;
+                    }
+                    _ => spans.push(RouteSpan {
+                        items: found,
+                        start,
+                        start_distance: distance + get_geodesic_distance(line.start, start),
+                        end,
+                        end_distance,
+                    }),
+                }
+            }
+
+            distance += get_geodesic_distance(line.start, line.end);
+        }
+
+        spans
+    }
+
+    /// Get the exact item for a given longitude (x) and latitude (y), by checking every item.
+    pub fn lookup_slow(&self, xf: Float, yf: Float) -> Vec<&T> {
+        self.get_items().iter().filter(|&i| i.geometry().contains(&Coord { x: xf, y: yf })).collect()
+    }
+
+    /// Get all of the items that intersect the 1x1 degree cell at `(x, y)`.
+    pub fn get_lookup_suggestions(&self, x: RoundDegree, y: RoundDegree) -> Option<Vec<&T>> {
+        self.get_lookup().get(&(x, y)).map(|cell| self.get_items_by_id(cell.iter().copied()))
+    }
+
+    /// Get the narrowest suggestions for a given longitude (x) and latitude (y).
+    ///
+    /// These are the items that intersect the cell's quadtree leaf containing the point, which is
+    /// usually far fewer than [`GeoIndex::get_lookup_suggestions`] in busy cells.
+    pub fn get_lookup_suggestions_at(&self, xf: Float, yf: Float) -> Option<Vec<&T>> {
+        let point = LngLat::new(xf, yf).ok()?;
+        let (xf, yf) = (point.lng(), point.lat());
+        let (x, y) = point.cell();
+
+        self.get_lookup().get(&(x, y)).map(|cell| self.get_items_by_id(cell.candidates_at((x, y), xf, yf).ids.iter().copied()))
+    }
+}
+
+impl<T> GeoIndex<'_, T>
+where
+    T: IsTimezone,
+{
+    /// Get the time zone with a stable identifier (see [`IsTimezone::stable_id`]), e.g., one stored
+    /// from an earlier lookup, or the first (by `id`), if several share it.
+    pub fn get_by_stable_id(&self, stable_id: u64) -> Option<&T> {
+        self.get_items().iter().find(|item| item.stable_id() == stable_id)
+    }
+}
+
+// Helpers.
+
+/// Get a geometry as a [`MultiPolygon`], if it is a polygon of some sort.
+fn get_multi_polygon(geometry: &Geometry<Float>) -> Option<MultiPolygon<Float>> {
+    match geometry {
+        Geometry::Polygon(polygon) => Some(MultiPolygon::new(vec![polygon.clone()])),
+        Geometry::MultiPolygon(multi_polygon) => Some(multi_polygon.clone()),
+        _ => None,
+    }
+}
+
+// Tests.
+
+#[cfg(test)]
+#[cfg(feature = "tz-ned")]
+mod tests {
+    use super::*;
+    use crate::{geo::shared::HasGeoIndex, CanPerformGeoLookup};
+    use geojson::FeatureCollection;
+    use pretty_assertions::assert_eq;
+    use rtz_core::geo::{shared::get_items_from_features, tz::ned::NedTimezone};
+
+    /// Two side-by-side, 10x10 degree zones, with the properties the NED source has.
+    fn get_test_index() -> GeoIndex<'static, NedTimezone> {
+        let zone = |name: &str, west: Float, zone: Float| {
+            serde_json::json!({
+                "type": "Feature",
+                "properties": { "dst_places": null, "places": name, "time_zone": "UTC+00:00", "tz_name1st": name, "zone": zone },
+                "geometry": { "type": "Polygon", "coordinates": [[[west, 0.0], [west + 10.0, 0.0], [west + 10.0, 10.0], [west, 10.0], [west, 0.0]]] },
+            })
+        };
+        let features: FeatureCollection = serde_json::from_value(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [zone("Test/West", 0.0, 0.0), zone("Test/East", 10.0, 1.0)],
+        }))
+        .unwrap();
+
+        GeoIndex::from_items(get_items_from_features(features))
+    }
+
+    fn identifiers(items: Vec<&NedTimezone>) -> Vec<&str> {
+        items.into_iter().map(|t| t.identifier.as_deref().unwrap()).collect()
+    }
+
+    #[test]
+    fn can_index_a_custom_dataset() {
+        let index = get_test_index();
+
+        assert_eq!(index.get_items().len(), 2);
+        assert_eq!(identifiers(index.lookup(5.0, 5.0)), vec!["Test/West"]);
+        assert_eq!(identifiers(index.lookup(15.0, 5.0)), vec!["Test/East"]);
+        assert_eq!(identifiers(index.lookup_slow(15.0, 5.0)), vec!["Test/East"]);
+        assert!(index.lookup(-121.0, 46.0).is_empty());
+        assert!(index.try_lookup(5.0, 95.0).is_err());
+
+        assert_eq!(identifiers(index.lookup_bbox(8.0, 1.0, 12.0, 2.0)), vec!["Test/West", "Test/East"]);
+        assert_eq!(identifiers(index.get_lookup_suggestions(5, 5).unwrap()), vec!["Test/West"]);
+        assert_eq!(identifiers(index.lookup_many(&[(15.0, 5.0), (5.0, 5.0)]).concat()), vec!["Test/East", "Test/West"]);
+
+        let (nearest, distance) = index.lookup_nearest(21.0, 5.0, 200_000.0).unwrap();
+        assert_eq!(nearest.identifier.as_deref(), Some("Test/East"));
+        assert!(distance > 100_000.0);
+
+        let route = index.lookup_route(&LineString::from(vec![(5.0, 5.0), (15.0, 5.0)]));
+        assert_eq!(route.iter().map(|s| identifiers(s.items.clone())).collect::<Vec<_>>(), vec![vec!["Test/West"], vec!["Test/East"]]);
+    }
+
+    #[test]
+    fn can_hold_indexes_side_by_side() {
+        let custom = get_test_index();
+        let global = NedTimezone::get_mem_index();
+
+        // The custom index leaves the global one (and the static lookups over it) alone.
+        assert_eq!(identifiers(custom.lookup(-121.0, 46.0)), Vec::<&str>::new());
+        assert_eq!(identifiers(global.lookup(-121.0, 46.0)), vec!["America/Los_Angeles"]);
+        assert_eq!(identifiers(NedTimezone::lookup(-121.0, 46.0)), vec!["America/Los_Angeles"]);
+
+        // A borrowed index answers exactly like the one it borrows from.
+        let borrowed = GeoIndex::borrowed(global.get_items(), global.get_lookup());
+        for (x, y) in [(-121.0, 46.0), (5.0, 5.0), (179.9968, -67.0959), (-68.5, -67.5)] {
+            assert_eq!(
+                borrowed.lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                global.lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+
+        drop(custom);
+    }
+
+    #[test]
+    #[cfg(feature = "runtime-data")]
+    fn can_load_an_index_at_runtime() {
+        use rtz_core::geo::tz::ned::{LOOKUP_BINCODE_DESTINATION_NAME, TIMEZONE_BINCODE_DESTINATION_NAME};
+
+        let assets = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let loaded = GeoIndex::<NedTimezone>::from_files(assets.join(TIMEZONE_BINCODE_DESTINATION_NAME), assets.join(LOOKUP_BINCODE_DESTINATION_NAME)).unwrap();
+        let global = NedTimezone::get_mem_index();
+
+        assert_eq!(loaded.get_items().len(), global.get_items().len());
+        for (x, y) in [(-121.0, 46.0), (179.9968, -67.0959), (-68.5, -67.5)] {
+            assert_eq!(
+                loaded.lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                global.lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+
+        // Bytes that are not 8-byte aligned are copied before they are decoded.
+        let read = |name: &str| {
+            let mut bytes = vec![0u8];
+            bytes.extend(std::fs::read(assets.join(name)).unwrap());
+            &Vec::leak(bytes)[1..]
+        };
+        let (items, lookup) = (read(TIMEZONE_BINCODE_DESTINATION_NAME), read(LOOKUP_BINCODE_DESTINATION_NAME));
+        assert_ne!(items.as_ptr().align_offset(8), 0);
+        assert_ne!(crate::geo::shared::get_aligned_data(items).as_ptr(), items.as_ptr());
+        assert_eq!(
+            identifiers(GeoIndex::<NedTimezone>::from_bytes(items, lookup).unwrap().lookup(-121.0, 46.0)),
+            vec!["America/Los_Angeles"]
+        );
+
+        // Truncated, swapped, mismatched, or missing data is an error, rather than a panic.
+        assert!(GeoIndex::<NedTimezone>::from_bytes(&items[..items.len() / 2], lookup).is_err());
+        assert!(format!("{:#}", GeoIndex::<NedTimezone>::from_bytes(lookup, items).unwrap_err()).contains("Expected the items bincode, but found the lookup bincode."));
+
+        let osm_lookup = read("osm_time_zone_lookup.bincode");
+        assert!(format!("{:#}", GeoIndex::<NedTimezone>::from_bytes(items, osm_lookup).unwrap_err()).contains("must come from the same build"));
+
+        // A lookup built from other items is caught by their checksum, even when everything else agrees.
+        let (mut header, body) = rtz_core::geo::shared::AssetHeader::from_bytes(lookup).map(|(header, body)| (header, body.to_vec())).unwrap();
+        header.info.items_checksum ^= 1;
+        let mut other_lookup = header.to_bytes();
+        other_lookup.extend(body);
+        let other_lookup = crate::geo::shared::get_aligned_data(Vec::leak(other_lookup));
+        assert!(matches!(
+            GeoIndex::<NedTimezone>::from_bytes(items, other_lookup).unwrap_err().downcast_ref::<AssetError>(),
+            Some(AssetError::MismatchedLookup { .. })
+        ));
+        assert!(GeoIndex::<NedTimezone>::from_files(assets.join("missing.bincode"), assets.join(LOOKUP_BINCODE_DESTINATION_NAME)).is_err());
+
+        // A single flipped bit fails the checksum, before anything is decoded.
+        let corrupt = Vec::leak(items.to_vec());
+        let last = corrupt.len() - 1;
+        corrupt[last] ^= 0x01;
+        assert!(matches!(
+            GeoIndex::<NedTimezone>::from_bytes(corrupt, lookup).unwrap_err().downcast_ref::<AssetError>(),
+            Some(AssetError::ChecksumMismatch { .. })
+        ));
+
+        assert!(loaded.validate().is_ok());
+    }
+}
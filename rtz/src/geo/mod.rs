@@ -4,5 +4,9 @@
 //! sources, but for now it only supports timezone data.
 
 pub mod admin;
+pub mod index;
 pub mod shared;
 pub mod tz;
+
+#[cfg(feature = "custom")]
+pub mod custom;
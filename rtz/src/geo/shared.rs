@@ -2,42 +2,75 @@
 
 // Traits.
 
-use geo::{Contains, Coord};
+use std::sync::OnceLock;
+
+use geo::{Coord, Geometry, LineString};
 use rtz_core::{
-    base::types::Float,
-    geo::shared::{ConcreteVec, HasGeometry, HasProperties, Id, RoundDegree, RoundLngLat, ToGeoJson},
+    base::types::{CoordinateError, Float, LngLat},
+    geo::shared::{get_boundary_tolerance, get_distance_to_boundary, ConcreteVec, DatasetInfo, EncodableLookup, HasGeometry, HasProperties, RoundDegree, ToGeoJson},
 };
-use std::collections::HashMap;
 
-/// Trait that abstracts away getting the in-memory items.
-pub trait HasItemData
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+use rtz_core::geo::shared::{AssetError, AssetHeader, AssetKind};
+
+use crate::geo::index::GeoIndex;
+
+/// Trait that abstracts away getting the default, global [`GeoIndex`] for a type.
+///
+/// The static methods of [`HasItemData`], [`HasLookupData`] and [`CanPerformGeoLookup`] are thin
+/// wrappers over this index.
+pub trait HasGeoIndex
 where
-    Self: Sized,
+    Self: Sized + 'static,
 {
+    /// Gets the cell that holds the default, global index for the given type.
+    fn get_mem_index_cell() -> &'static OnceLock<GeoIndex<'static, Self>>;
+
+    /// Loads the default index for the given type (e.g., from the embedded assets).
+    fn load_mem_index() -> GeoIndex<'static, Self>;
+
+    /// Gets the default, global index (the in-memory items and lookup cache) for the given type,
+    /// loading it on first use unless one was set with [`HasGeoIndex::set_mem_index`].
+    fn get_mem_index() -> &'static GeoIndex<'static, Self> {
+        Self::get_mem_index_cell().get_or_init(Self::load_mem_index)
+    }
+
+    /// Sets the default, global index for the given type (e.g., one loaded at runtime with
+    /// [`GeoIndex::from_files`]), which must happen before its first use.
+    ///
+    /// Returns the index back if the global index was already set or loaded.
+    #[allow(clippy::result_large_err)]
+    fn set_mem_index(index: GeoIndex<'static, Self>) -> Result<(), GeoIndex<'static, Self>> {
+        Self::get_mem_index_cell().set(index)
+    }
+}
+
+/// Trait that abstracts away getting the in-memory items.
+pub trait HasItemData: HasGeoIndex {
     /// Gets the items from the in-memory cache for the given type.
-    fn get_mem_items() -> &'static ConcreteVec<Self>;
+    fn get_mem_items() -> &'static ConcreteVec<Self> {
+        Self::get_mem_index().get_items()
+    }
 }
 
 /// Trait that abstracts away getting the in-memory timezones / cache.
-pub trait HasLookupData: HasItemData
-where
-    Self: Sized,
-{
-    /// The type to which the lookup hash table resolves.
-    type Lookup: AsRef<[Id]>;
-
-    /// Gets the lookup hash table from the in-memory cache for the given type.
-    fn get_mem_lookup() -> &'static HashMap<RoundLngLat, Self::Lookup>;
+pub trait HasLookupData: HasItemData {
+    /// Gets the lookup cell table (and the geometry fragments it refers to) from the in-memory cache for the given type.
+    fn get_mem_lookup() -> &'static EncodableLookup {
+        Self::get_mem_index().get_lookup()
+    }
 }
 
 /// Trait that allows converting a [`u16`] into the items to which the ids refer (from the global list).
+#[cfg(test)]
 pub(crate) trait MapIntoItems<T> {
     fn map_into_items(self) -> Option<Vec<&'static T>>;
 }
 
+#[cfg(test)]
 impl<A, T> MapIntoItems<T> for Option<A>
 where
-    A: AsRef<[Id]>,
+    A: AsRef<[rtz_core::geo::shared::Id]>,
     T: HasItemData,
 {
     fn map_into_items(self) -> Option<Vec<&'static T>> {
@@ -56,49 +89,214 @@ where
     }
 }
 
-/// Perform a decode of binary data.
-#[cfg(feature = "self-contained")]
-pub fn decode_binary_data<T>(data: &'static [u8]) -> T
+/// How much to trust the bytes of an asset that is being decoded.
+///
+/// Either way, the header and the body's checksum are verified, and a malformed body is an error
+/// rather than undefined behavior.
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecodeMode {
+    /// The bytes came from this build (e.g., the embedded assets), so they are decoded as they are.
+    Trusted,
+    /// The bytes may come from anywhere (e.g., a file loaded at runtime), so the decode is limited to
+    /// [`SAFE_DECODE_LIMIT`](rtz_core::geo::shared::SAFE_DECODE_LIMIT) bytes of containers, and a
+    /// [`GeoIndex`] checks that its items and lookup are consistent (see [`GeoIndex::validate`]).
+    #[default]
+    Safe,
+}
+
+/// Perform a decode of an asset of the given kind (see [`AssetHeader`]).
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+pub fn decode_binary_data<T>(data: &'static [u8], kind: AssetKind) -> T
 where
     T: bincode::Decode<()> + bincode::BorrowDecode<'static, ()>,
 {
+    try_decode_binary_data(data, kind, DecodeMode::Trusted)
+        .unwrap_or_else(|e| panic!("Could not decode binary data: {}", e))
+        .1
+}
+
+/// Perform a decode of an asset of the given kind, failing (rather than panicking) if its header
+/// does not match this build (or the kind), its checksum does not match its body, or it cannot be decoded.
+///
+/// Borrow-decoded geometries point straight into `data`, which must therefore be 8-byte aligned:
+/// data that is not is first copied into an aligned buffer (see [`get_aligned_data`]).  With
+/// `compressed-assets`, `data` may also be a compressed asset, which is decompressed into one.
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+pub fn try_decode_binary_data<T>(data: &'static [u8], kind: AssetKind, mode: DecodeMode) -> Result<(AssetHeader, T), AssetError>
+where
+    T: bincode::Decode<()> + bincode::BorrowDecode<'static, ()>,
+{
+    // A compressed asset is decompressed into a new (never freed, aligned) buffer, which stands in for it.
+    #[cfg(feature = "compressed-assets")]
+    let data = if rtz_core::geo::shared::is_compressed_asset(data) { get_decompressed_data(data)? } else { data };
+
     // INVARIANT: this is the single selector of borrow-vs-owned decode. With `owned-decode` off we
-    // borrow directly over the embedded bytes, which is what makes `EncodableGeometry`'s leak-on-drop
-    // correct (see its `Drop` in `rtz-core`). Do not introduce a second, differently-gated decode of
-    // geometry data, or the two can desync and free static memory (UB).
+    // borrow directly over the (`'static`) bytes, and the borrow-decoded geometries and lookup arrays
+    // mark themselves as borrowed, so that their `Drop`s (in `rtz-core`) forget, rather than free,
+    // the memory that points into them.
     #[cfg(not(feature = "owned-decode"))]
-    let (value, _len): (T, usize) = bincode::borrow_decode_from_slice(data, rtz_core::geo::shared::get_global_bincode_config())
-        .expect("Could not decode binary data: try rebuilding with `force-rebuild` due to a likely precision difference between the generated assets and the current build.");
+    let data = get_aligned_data(data);
+
+    let (header, body) = AssetHeader::from_bytes(data)?;
+    header.expect_kind(kind)?;
+
+    #[cfg(not(feature = "owned-decode"))]
+    let (value, _len): (T, usize) = match mode {
+        DecodeMode::Trusted => bincode::borrow_decode_from_slice(body, rtz_core::geo::shared::get_global_bincode_config())?,
+        DecodeMode::Safe => bincode::borrow_decode_from_slice(body, rtz_core::geo::shared::get_safe_bincode_config())?,
+    };
     #[cfg(feature = "owned-decode")]
-    let (value, _len): (T, usize) = bincode::decode_from_slice(data, rtz_core::geo::shared::get_global_bincode_config())
-        .expect("Could not decode binary data: try rebuilding with `force-rebuild` due to a likely precision difference between the generated assets and the current build.");
+    let (value, _len): (T, usize) = match mode {
+        DecodeMode::Trusted => bincode::decode_from_slice(body, rtz_core::geo::shared::get_global_bincode_config())?,
+        DecodeMode::Safe => bincode::decode_from_slice(body, rtz_core::geo::shared::get_safe_bincode_config())?,
+    };
+
+    Ok((header, value))
+}
+
+/// Get binary data at an 8-byte aligned address, copying it into a new (never freed) buffer if it is not.
+#[cfg(any(feature = "self-contained", feature = "runtime-data"))]
+pub fn get_aligned_data(data: &'static [u8]) -> &'static [u8] {
+    if data.as_ptr().align_offset(8) == 0 {
+        return data;
+    }
+
+    let words = Vec::leak(vec![0u64; data.len().div_ceil(8)]);
 
-    value
+    // SAFETY: the `u64`s span at least `data.len()` bytes, and any bytes are valid `u64` storage.
+    let aligned = unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, data.len()) };
+    aligned.copy_from_slice(data);
+
+    aligned
+}
+
+/// Decompress a compressed asset into a new (never freed) 8-byte aligned buffer.
+#[cfg(feature = "compressed-assets")]
+pub fn get_decompressed_data(data: &[u8]) -> Result<&'static [u8], AssetError> {
+    let len = rtz_core::geo::shared::get_decompressed_asset_len(data)?;
+    let words = Vec::leak(vec![0u64; len.div_ceil(8)]);
+
+    // SAFETY: the `u64`s span at least `len` bytes, and any bytes are valid `u64` storage.
+    let aligned = unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, len) };
+    rtz_core::geo::shared::decompress_asset_into(data, aligned)?;
+
+    Ok(aligned)
+}
+
+/// Read binary data from a file, for the rest of the program's life (borrow-decoded geometries point into it).
+///
+/// With `mmap`, the file is memory-mapped, so, after the checksum is verified on load, only the
+/// pages that lookups touch stay resident; the file must then not change while the program runs.
+#[cfg(feature = "runtime-data")]
+pub fn read_binary_data(path: impl AsRef<std::path::Path>) -> rtz_core::base::types::Res<&'static [u8]> {
+    #[cfg(feature = "mmap")]
+    {
+        let file = std::fs::File::open(path)?;
+
+        // SAFETY: the file must not be modified while it is mapped (see above).
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+
+        Ok(&**Box::leak(Box::new(map)))
+    }
+
+    #[cfg(not(feature = "mmap"))]
+    {
+        Ok(get_aligned_data(Vec::leak(std::fs::read(path)?)))
+    }
+}
+
+/// A stretch of a route that lies in the same items, as found by [`GeoIndex::lookup_route`].
+#[derive(Debug, Clone)]
+pub struct RouteSpan<'a, T> {
+    /// The items this stretch lies in (none, in a gap between items).
+    pub items: Vec<&'a T>,
+    /// Where the stretch starts: the start of the route, or the point where it crosses into `items`.
+    pub start: Coord<Float>,
+    /// The geodesic distance along the route to `start`, in meters.
+    pub start_distance: Float,
+    /// Where the stretch ends: the point where the route crosses out of `items`, or the end of the route.
+    pub end: Coord<Float>,
+    /// The geodesic distance along the route to `end`, in meters.
+    pub end_distance: Float,
 }
 
 /// Trait that abstracts away the primary end-user functionality of geo lookups.
+///
+/// These are all thin wrappers over the type's default, global [`GeoIndex`] (see [`HasGeoIndex`]).
 pub trait CanPerformGeoLookup: HasLookupData + HasGeometry + HasProperties
 where
     Self: 'static,
 {
-    /// Get the cache-driven item for a given longitude (x) and latitude (y).
+    /// Get what the dataset was generated from, and with which build parameters (see [`GeoIndex::dataset_info`]).
+    fn dataset_info() -> &'static DatasetInfo {
+        Self::get_mem_index().dataset_info()
+    }
+
+    /// Get the cache-driven item for a given longitude (x) and latitude (y) (see [`GeoIndex::lookup`]).
     ///
     /// Some data sources allow for multiple results, so this is a vector.
     fn lookup(xf: Float, yf: Float) -> Vec<&'static Self> {
-        let x = xf.floor() as RoundDegree;
-        let y = yf.floor() as RoundDegree;
+        Self::get_mem_index().lookup(xf, yf)
+    }
 
-        let Some(suggestions) = Self::get_lookup_suggestions(x, y) else {
-            return Vec::new();
-        };
+    /// Get the cache-driven item for a given longitude (x) and latitude (y), or the reason the
+    /// coordinate is not a point on the globe (see [`GeoIndex::try_lookup`]).
+    fn try_lookup(xf: Float, yf: Float) -> Result<Vec<&'static Self>, CoordinateError> {
+        Self::get_mem_index().try_lookup(xf, yf)
+    }
+
+    /// Get the cache-driven item for a validated point (see [`GeoIndex::lookup_at`]).
+    fn lookup_at(point: LngLat) -> Vec<&'static Self> {
+        Self::get_mem_index().lookup_at(point)
+    }
+
+    /// Get the cache-driven items for many longitudes (x) and latitudes (y) at once, in input order
+    /// (see [`GeoIndex::lookup_many`]).
+    fn lookup_many(points: &[(Float, Float)]) -> Vec<Vec<&'static Self>>
+    where
+        Self: Sync,
+    {
+        Self::get_mem_index().lookup_many(points)
+    }
+
+    /// Get the item nearest to a given longitude (x) and latitude (y), along with the geodesic
+    /// distance (in meters) to its boundary, as long as that is within `max_distance` meters
+    /// (see [`GeoIndex::lookup_nearest`]).
+    fn lookup_nearest(xf: Float, yf: Float, max_distance: Float) -> Option<(&'static Self, Float)> {
+        Self::get_mem_index().lookup_nearest(xf, yf, max_distance)
+    }
+
+    /// Get every item that intersects a `(west, south, east, north)` bounding box, edges included
+    /// (see [`GeoIndex::lookup_bbox`]).
+    fn lookup_bbox(west: Float, south: Float, east: Float, north: Float) -> Vec<&'static Self> {
+        Self::get_mem_index().lookup_bbox(west, south, east, north)
+    }
 
-        suggestions.into_iter().filter(|&i| i.geometry().contains(&Coord { x: xf, y: yf })).collect()
+    /// Get every item that overlaps a (multi) polygon, along with the fraction of the polygon's area
+    /// that falls in it (see [`GeoIndex::lookup_geometry`]).
+    fn lookup_geometry(geometry: &Geometry<Float>) -> Vec<(&'static Self, Float)> {
+        Self::get_mem_index().lookup_geometry(geometry)
+    }
+
+    /// Get the stretches of a route that lie in each item, in order along the route (see [`GeoIndex::lookup_route`]).
+    fn lookup_route(route: &LineString<Float>) -> Vec<RouteSpan<'static, Self>> {
+        Self::get_mem_index().lookup_route(route)
+    }
+
+    /// Get the geodesic distance, in meters, from a point to this item's nearest boundary edge, and
+    /// whether it is within [`get_boundary_tolerance`]: close enough that build-time simplification
+    /// (and [`Float`] rounding) may have put the point on the wrong side of the boundary.
+    fn get_boundary_distance(&self, xf: Float, yf: Float) -> Option<(Float, bool)> {
+        let distance = get_distance_to_boundary(self.geometry(), xf, yf)?;
+
+        Some((distance, distance <= get_boundary_tolerance(Self::simplification_epsilon())))
     }
 
     /// Get the exact item for a given longitude (x) and latitude (y).
     #[allow(dead_code)]
     fn lookup_slow(xf: Float, yf: Float) -> Vec<&'static Self> {
-        Self::get_mem_items().into_iter().filter(|&i| i.geometry().contains(&Coord { x: xf, y: yf })).collect()
+        Self::get_mem_index().lookup_slow(xf, yf)
     }
 
     /// Gets the geojson representation of the memory cache.
@@ -108,8 +306,15 @@ where
     }
 
     /// Get value from the static memory cache.
+    ///
+    /// These are all of the items that intersect the 1x1 degree cell at `(x, y)`.
     fn get_lookup_suggestions(x: RoundDegree, y: RoundDegree) -> Option<Vec<&'static Self>> {
-        let cache = Self::get_mem_lookup();
-        cache.get(&(x, y)).map_into_items()
+        Self::get_mem_index().get_lookup_suggestions(x, y)
+    }
+
+    /// Get the narrowest suggestions for a given longitude (x) and latitude (y) from the static memory cache
+    /// (see [`GeoIndex::get_lookup_suggestions_at`]).
+    fn get_lookup_suggestions_at(xf: Float, yf: Float) -> Option<Vec<&'static Self>> {
+        Self::get_mem_index().get_lookup_suggestions_at(xf, yf)
     }
 }
@@ -1,104 +1,68 @@
 //! The [Natural Earth Data](https://github.com/nvkelso/natural-earth-vector) timezone lookup module.
 
-use std::{collections::HashMap, sync::OnceLock};
+use std::sync::OnceLock;
 
-use geo::{Contains, Coord};
-use rtz_core::{
-    base::types::Float,
-    geo::{
-        shared::{ConcreteVec, EncodableIds, HasGeometry, RoundLngLat},
-        tz::ned::NedTimezone,
-    },
-};
+use rtz_core::geo::tz::ned::NedTimezone;
 
 use crate::{
-    geo::shared::{HasItemData, HasLookupData},
+    geo::{
+        index::GeoIndex,
+        shared::{HasGeoIndex, HasItemData, HasLookupData},
+        tz::shared::CanGetByStableId,
+    },
     CanPerformGeoLookup,
 };
 
-#[cfg(feature = "self-contained")]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 use include_bytes_aligned::include_bytes_aligned;
 
 // Trait impls.
 
-impl HasItemData for NedTimezone {
-    fn get_mem_items() -> &'static ConcreteVec<NedTimezone> {
-        static TIMEZONES: OnceLock<ConcreteVec<NedTimezone>> = OnceLock::new();
+impl HasGeoIndex for NedTimezone {
+    fn get_mem_index_cell() -> &'static OnceLock<GeoIndex<'static, NedTimezone>> {
+        static INDEX: OnceLock<GeoIndex<'static, NedTimezone>> = OnceLock::new();
 
-        #[cfg(feature = "self-contained")]
-        {
-            TIMEZONES.get_or_init(|| crate::geo::shared::decode_binary_data(TZ_BINCODE))
-        }
-
-        #[cfg(not(feature = "self-contained"))]
-        {
-            use rtz_core::geo::{shared::get_items_from_features, tz::ned::get_geojson_features_from_source};
-
-            TIMEZONES.get_or_init(|| {
-                let features = get_geojson_features_from_source();
-                get_items_from_features(features)
-            })
-        }
+        &INDEX
     }
-}
-
-impl HasLookupData for NedTimezone {
-    type Lookup = EncodableIds;
-
-    fn get_mem_lookup() -> &'static HashMap<RoundLngLat, Self::Lookup> {
-        static CACHE: OnceLock<HashMap<RoundLngLat, EncodableIds>> = OnceLock::new();
 
+    fn load_mem_index() -> GeoIndex<'static, NedTimezone> {
         #[cfg(feature = "self-contained")]
         {
-            CACHE.get_or_init(|| crate::geo::shared::decode_binary_data(LOOKUP_BINCODE))
+            use crate::geo::shared::DecodeMode;
+
+            GeoIndex::from_bytes_with(TZ_BINCODE, LOOKUP_BINCODE, DecodeMode::Trusted).unwrap_or_else(|e| panic!("Could not decode the embedded data: {:#}", e))
         }
 
         #[cfg(not(feature = "self-contained"))]
         {
-            use rtz_core::geo::shared::get_lookup_from_geometries;
+            use rtz_core::geo::{shared::get_items_from_features, tz::ned::get_geojson_features_from_source};
 
-            CACHE.get_or_init(|| {
-                let cache = get_lookup_from_geometries(NedTimezone::get_mem_items());
+            let features = get_geojson_features_from_source();
 
-                cache
-            })
+            GeoIndex::from_items(get_items_from_features(features))
         }
     }
 }
 
-// Special implementation of this for timezones since our timezone data covers the whole world.
-// Therefore, we can use the special optimization.
-impl CanPerformGeoLookup for NedTimezone {
-    fn lookup(xf: Float, yf: Float) -> Vec<&'static Self> {
-        let x = xf.floor() as i16;
-        let y = yf.floor() as i16;
+impl HasItemData for NedTimezone {}
 
-        let Some(suggestions) = Self::get_lookup_suggestions(x, y) else {
-            return Vec::new();
-        };
+impl HasLookupData for NedTimezone {}
 
-        // [ARoney] Optimization: If there is only one item, we can skip the more expensive
-        // intersection check.  Edges are weird, so we still need to check if the point is in the
-        // polygon at thg edges of the polar space.
-        if suggestions.len() == 1 && xf > -179. && xf < 179. && yf > -89. && yf < 89. {
-            return suggestions;
-        }
+impl CanPerformGeoLookup for NedTimezone {}
 
-        suggestions.into_iter().filter(|&i| i.geometry().contains(&Coord { x: xf, y: yf })).collect()
-    }
-}
+impl CanGetByStableId for NedTimezone {}
 
 // Statics.
 
-#[cfg(all(host_family_unix, feature = "self-contained"))]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 static TZ_BINCODE: &[u8] = include_bytes_aligned!(8, "../../../assets/ned_time_zones.bincode");
-#[cfg(all(host_family_windows, feature = "self-contained"))]
-static TZ_BINCODE: &[u8] = include_bytes_aligned!(8, "..\\..\\..\\assets\\ned_time_zones.bincode");
+#[cfg(all(feature = "self-contained", feature = "compressed-assets"))]
+static TZ_BINCODE: &[u8] = include_bytes!("../../../assets/ned_time_zones.bincode.zst");
 
-#[cfg(all(host_family_unix, feature = "self-contained"))]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 static LOOKUP_BINCODE: &[u8] = include_bytes_aligned!(8, "../../../assets/ned_time_zone_lookup.bincode");
-#[cfg(all(host_family_windows, feature = "self-contained"))]
-static LOOKUP_BINCODE: &[u8] = include_bytes_aligned!(8, "..\\..\\..\\assets\\ned_time_zone_lookup.bincode");
+#[cfg(all(feature = "self-contained", feature = "compressed-assets"))]
+static LOOKUP_BINCODE: &[u8] = include_bytes!("../../../assets/ned_time_zone_lookup.bincode.zst");
 
 // Tests.
 
@@ -106,9 +70,14 @@ static LOOKUP_BINCODE: &[u8] = include_bytes_aligned!(8, "..\\..\\..\\assets\\ne
 mod tests {
     use super::*;
     use crate::geo::shared::{CanPerformGeoLookup, MapIntoItems};
+    use geo::Intersects;
     use pretty_assertions::assert_eq;
     use rayon::prelude::{IntoParallelIterator, ParallelIterator};
-    use rtz_core::base::types::Float;
+    use rtz_core::{
+        base::types::{CoordinateError, Float},
+        geo::shared::{get_distance_to_boundary, HasGeometry},
+        geo::tz::shared::IsTimezone,
+    };
 
     #[test]
     fn can_get_timezones() {
@@ -116,6 +85,17 @@ mod tests {
         assert_eq!(timezones.len(), 120);
     }
 
+    #[test]
+    fn can_get_dataset_info() {
+        let info = NedTimezone::dataset_info();
+
+        assert_eq!(info.format_version, rtz_core::geo::shared::ASSET_FORMAT_VERSION);
+        assert!(info.float_bits == 32 || info.float_bits == 64);
+        assert_eq!(info.item_count, 120);
+        assert_eq!(info.source, rtz_core::geo::tz::ned::ADDRESS);
+        assert!(info.generated_at > 0);
+    }
+
     #[test]
     fn can_get_lookup() {
         let cache = NedTimezone::get_mem_lookup();
@@ -136,6 +116,29 @@ mod tests {
         assert_eq!(NedTimezone::lookup_slow(179.9968, -67.0959).len(), 0);
     }
 
+    #[test]
+    fn can_get_by_stable_id() {
+        let tz = NedTimezone::lookup(-121.0, 46.0)[0];
+
+        assert_eq!(NedTimezone::get_by_stable_id(tz.stable_id()).unwrap().identifier, tz.identifier);
+        assert!(NedTimezone::get_by_stable_id(0).is_none());
+
+        // Only what identifies a zone goes into its stable id: its identifier, its offset, and its rounded bounding box.
+        assert_eq!(tz.stable_id(), rtz_core::geo::tz::shared::get_stable_id(&["America/Los_Angeles", "UTC-08:00", "-141,-74,-107,70"]));
+    }
+
+    #[test]
+    fn stable_ids_are_unique() {
+        let items = NedTimezone::get_mem_items();
+        let stable_ids = items.iter().map(|item| item.stable_id()).collect::<std::collections::HashSet<_>>();
+
+        assert_eq!(stable_ids.len(), items.len());
+
+        for item in items.iter() {
+            assert_eq!(NedTimezone::get_by_stable_id(item.stable_id()).unwrap().id, item.id);
+        }
+    }
+
     #[test]
     fn can_access_lookup() {
         let cache = NedTimezone::get_mem_lookup();
@@ -153,6 +156,122 @@ mod tests {
         assert_eq!(tzs.len(), 5);
     }
 
+    #[test]
+    fn can_skip_contains_for_covering_candidates() {
+        // The whole cell lies inside `America/Los_Angeles`, so its one candidate needs no `contains` check.
+        let cell = NedTimezone::get_mem_lookup().get(&(-121, 46)).unwrap();
+        assert!(cell.candidates_at((-121, 46), -120.5, 46.5).is_covering(0));
+
+        // The poles and the antimeridian need no special casing: an item only covers a leaf if no edge touches it.
+        for (x, y) in [(179.9968, -67.0959), (-179.9, 0.0), (179.9, 0.0), (0.0, 89.9), (0.0, -89.9), (-180.0, -90.0)] {
+            assert_eq!(
+                NedTimezone::lookup_slow(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                NedTimezone::lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn can_try_lookup() {
+        let ids = |items: Vec<&NedTimezone>| items.into_iter().map(|t| t.id).collect::<Vec<_>>();
+
+        assert_eq!(NedTimezone::try_lookup(-121.0, 95.0).unwrap_err(), CoordinateError::LatitudeOutOfRange { lat: 95.0 });
+        assert!(NedTimezone::try_lookup(Float::NAN, 46.0).is_err());
+        assert!(NedTimezone::lookup(-121.0, 95.0).is_empty());
+
+        // Longitudes wrap, and the antimeridian is `-180`, rather than off the grid (where, like any boundary, it may need `lookup_nearest`).
+        assert_eq!(ids(NedTimezone::try_lookup(239.0, 46.0).unwrap()), ids(NedTimezone::lookup(-121.0, 46.0)));
+        assert_eq!(ids(NedTimezone::lookup(180.0, -30.0)), ids(NedTimezone::lookup(-180.0, -30.0)));
+        assert_eq!(
+            NedTimezone::lookup_nearest(180.0, -30.0, 1.0).map(|(t, _)| t.id),
+            NedTimezone::lookup_nearest(-180.0, -30.0, 1.0).map(|(t, _)| t.id)
+        );
+        assert!(NedTimezone::lookup_nearest(180.0, -30.0, 1.0).is_some());
+
+        // Each pole is one point, whatever the longitude, and it is on the grid (on the edge of every zone that touches it).
+        for y in [90.0, -90.0] {
+            assert_eq!(ids(NedTimezone::lookup(123.0, y)), ids(NedTimezone::lookup(0.0, y)), "{}", y);
+
+            let pole = NedTimezone::lookup_nearest(0.0, y, 1.0).map(|(t, _)| t.id);
+            assert!(pole.is_some(), "{}", y);
+            assert_eq!(NedTimezone::lookup_nearest(123.0, y, 1.0).map(|(t, _)| t.id), pole, "{}", y);
+        }
+    }
+
+    #[test]
+    fn can_lookup_many_in_input_order() {
+        let mut points = (0..1_000)
+            .map(|_| (rand::random::<Float>() * 360.0 - 180.0, rand::random::<Float>() * 180.0 - 90.0))
+            .collect::<Vec<_>>();
+        points.extend([(-121.0, 46.0), (-121.0, 46.0), (180.0, 90.0), (Float::NAN, 0.5)]);
+
+        let many = NedTimezone::lookup_many(&points);
+
+        assert_eq!(many.len(), points.len());
+        assert!(many[points.len() - 1].is_empty());
+        for (&(x, y), items) in points.iter().zip(many) {
+            assert_eq!(
+                NedTimezone::lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                items.into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn can_lookup_nearest() {
+        let (tz, distance) = NedTimezone::lookup_nearest(-121.0, 46.0, 0.0).unwrap();
+        assert_eq!(tz.identifier.as_ref().unwrap(), "America/Los_Angeles");
+        assert_eq!(distance, 0.0);
+
+        // A miss falls back to the nearest boundary in reach, across the antimeridian if need be, and agrees with a full scan.
+        for (x, y) in [(179.9968, -67.0959), (-177.0, -15.0)] {
+            assert!(NedTimezone::lookup(x, y).is_empty());
+
+            let (tz, distance) = NedTimezone::lookup_nearest(x, y, 10_000.0).unwrap();
+            let (id, full_distance) = NedTimezone::get_mem_items()
+                .into_iter()
+                .filter_map(|t| get_distance_to_boundary(t.geometry(), x, y).map(|d| (t.id, d)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+
+            assert_eq!((tz.id, distance), (id, full_distance), "({}, {})", x, y);
+            assert!(distance > 0.0);
+            assert!(NedTimezone::lookup_nearest(x, y, distance / 2.0).is_none());
+        }
+
+        assert!(NedTimezone::lookup_nearest(Float::NAN, 0.0, 10_000.0).is_none());
+    }
+
+    #[test]
+    fn can_lookup_bbox() {
+        let ids = |items: Vec<&NedTimezone>| items.into_iter().map(|t| t.id).collect::<Vec<_>>();
+        let slow = |west: Float, south: Float, east: Float, north: Float| {
+            let rect = geo::Rect::new(geo::Coord { x: west, y: south }, geo::Coord { x: east, y: north });
+            NedTimezone::get_mem_items().into_iter().filter(|t| t.geometry().intersects(&rect)).map(|t| t.id).collect::<Vec<_>>()
+        };
+
+        let pacific_northwest = ids(NedTimezone::lookup_bbox(-124.0, 45.0, -118.0, 49.0));
+        assert_eq!(pacific_northwest, slow(-124.0, 45.0, -118.0, 49.0));
+        assert!(pacific_northwest.contains(&NedTimezone::lookup(-121.0, 46.0)[0].id));
+
+        // Across the antimeridian, the box is the union of its two halves, with no duplicates.
+        let across = ids(NedTimezone::lookup_bbox(170.0, -20.0, -170.0, 0.0));
+        let mut halves = [slow(170.0, -20.0, 180.0, 0.0), slow(-180.0, -20.0, -170.0, 0.0)].concat();
+        halves.sort_unstable();
+        halves.dedup();
+        assert_eq!(across, halves);
+        assert!(across.len() > slow(170.0, -20.0, 180.0, 0.0).len());
+
+        assert_eq!(ids(NedTimezone::lookup_bbox(-180.0, -90.0, 180.0, 90.0)).len(), NedTimezone::get_mem_items().len());
+        assert!(NedTimezone::lookup_bbox(Float::NAN, 0.0, 1.0, 1.0).is_empty());
+    }
+
     #[test]
     fn can_verify_lookup_assisted_accuracy() {
         (0..1_000).into_par_iter().for_each(|_| {
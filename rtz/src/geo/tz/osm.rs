@@ -1,105 +1,68 @@
 //! The [OpenStreetMap](https://www.openstreetmap.org/) timezone lookup module.
 
-use std::{collections::HashMap, sync::OnceLock};
+use std::sync::OnceLock;
 
-use geo::{Contains, Coord};
-use rtz_core::{
-    base::types::Float,
-    geo::{
-        shared::{ConcreteVec, EncodableIds, HasGeometry, RoundLngLat},
-        tz::osm::OsmTimezone,
-    },
-};
+use rtz_core::geo::tz::osm::OsmTimezone;
 
 use crate::{
-    geo::shared::{HasItemData, HasLookupData},
+    geo::{
+        index::GeoIndex,
+        shared::{HasGeoIndex, HasItemData, HasLookupData},
+        tz::shared::CanGetByStableId,
+    },
     CanPerformGeoLookup,
 };
 
-#[cfg(feature = "self-contained")]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 use include_bytes_aligned::include_bytes_aligned;
 
 // Trait impls.
 
-impl HasItemData for OsmTimezone {
-    fn get_mem_items() -> &'static ConcreteVec<OsmTimezone> {
-        static TIMEZONES: OnceLock<ConcreteVec<OsmTimezone>> = OnceLock::new();
+impl HasGeoIndex for OsmTimezone {
+    fn get_mem_index_cell() -> &'static OnceLock<GeoIndex<'static, OsmTimezone>> {
+        static INDEX: OnceLock<GeoIndex<'static, OsmTimezone>> = OnceLock::new();
 
+        &INDEX
+    }
+
+    fn load_mem_index() -> GeoIndex<'static, OsmTimezone> {
         #[cfg(feature = "self-contained")]
         {
-            TIMEZONES.get_or_init(|| crate::geo::shared::decode_binary_data(TZ_BINCODE))
+            use crate::geo::shared::DecodeMode;
+
+            GeoIndex::from_bytes_with(TZ_BINCODE, LOOKUP_BINCODE, DecodeMode::Trusted).unwrap_or_else(|e| panic!("Could not decode the embedded data: {:#}", e))
         }
 
         #[cfg(not(feature = "self-contained"))]
         {
             use rtz_core::geo::{shared::get_items_from_features, tz::osm::get_geojson_features_from_source};
 
-            TIMEZONES.get_or_init(|| {
-                let features = get_geojson_features_from_source();
+            let features = get_geojson_features_from_source();
 
-                get_items_from_features(features)
-            })
+            GeoIndex::from_items(get_items_from_features(features))
         }
     }
 }
 
-impl HasLookupData for OsmTimezone {
-    type Lookup = EncodableIds;
-
-    fn get_mem_lookup() -> &'static HashMap<RoundLngLat, Self::Lookup> {
-        static CACHE: OnceLock<HashMap<RoundLngLat, EncodableIds>> = OnceLock::new();
+impl HasItemData for OsmTimezone {}
 
-        #[cfg(feature = "self-contained")]
-        {
-            CACHE.get_or_init(|| crate::geo::shared::decode_binary_data(LOOKUP_BINCODE))
-        }
-
-        #[cfg(not(feature = "self-contained"))]
-        {
-            use rtz_core::geo::shared::get_lookup_from_geometries;
-
-            CACHE.get_or_init(|| {
-                let cache = get_lookup_from_geometries(OsmTimezone::get_mem_items());
-
-                cache
-            })
-        }
-    }
-}
+impl HasLookupData for OsmTimezone {}
 
-// Special implementation of this for timezones since our timezone data covers the whole world.
-// Therefore, we can use the special optimization.
-impl CanPerformGeoLookup for OsmTimezone {
-    fn lookup(xf: Float, yf: Float) -> Vec<&'static Self> {
-        let x = xf.floor() as i16;
-        let y = yf.floor() as i16;
-
-        let Some(suggestions) = Self::get_lookup_suggestions(x, y) else {
-            return Vec::new();
-        };
-
-        // [ARoney] Optimization: If there is only one item, we can skip the more expensive
-        // intersection check.  Edges are weird, so we still need to check if the point is in the
-        // polygon at thg edges of the polar space.
-        if suggestions.len() == 1 && xf > -179. && xf < 179. && yf > -89. && yf < 89. {
-            return suggestions;
-        }
+impl CanPerformGeoLookup for OsmTimezone {}
 
-        suggestions.into_iter().filter(|&i| i.geometry().contains(&Coord { x: xf, y: yf })).collect()
-    }
-}
+impl CanGetByStableId for OsmTimezone {}
 
 // Statics.
 
-#[cfg(all(host_family_unix, feature = "self-contained"))]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 static TZ_BINCODE: &[u8] = include_bytes_aligned!(8, "../../../assets/osm_time_zones.bincode");
-#[cfg(all(host_family_windows, feature = "self-contained"))]
-static TZ_BINCODE: &[u8] = include_bytes_aligned!(8, "..\\..\\..\\assets\\osm_time_zones.bincode");
+#[cfg(all(feature = "self-contained", feature = "compressed-assets"))]
+static TZ_BINCODE: &[u8] = include_bytes!("../../../assets/osm_time_zones.bincode.zst");
 
-#[cfg(all(host_family_unix, feature = "self-contained"))]
+#[cfg(all(feature = "self-contained", not(feature = "compressed-assets")))]
 static LOOKUP_BINCODE: &[u8] = include_bytes_aligned!(8, "../../../assets/osm_time_zone_lookup.bincode");
-#[cfg(all(host_family_windows, feature = "self-contained"))]
-static LOOKUP_BINCODE: &[u8] = include_bytes_aligned!(8, "..\\..\\..\\assets\\osm_time_zone_lookup.bincode");
+#[cfg(all(feature = "self-contained", feature = "compressed-assets"))]
+static LOOKUP_BINCODE: &[u8] = include_bytes!("../../../assets/osm_time_zone_lookup.bincode.zst");
 
 // Tests.
 
@@ -108,9 +71,14 @@ mod tests {
     use crate::geo::shared::{CanPerformGeoLookup, HasItemData, MapIntoItems};
 
     use super::*;
+    use geo::{BooleanOps, ChamberlainDuquetteArea, Coord, Geometry, LineString, MultiPolygon, Rect};
     use pretty_assertions::assert_eq;
     use rayon::prelude::{IntoParallelIterator, ParallelIterator};
-    use rtz_core::base::types::Float;
+    use rtz_core::{
+        base::types::Float,
+        geo::shared::{get_boundary_tolerance, get_distance_to_boundary, get_geodesic_distance, HasGeometry},
+        geo::tz::shared::IsTimezone,
+    };
 
     #[test]
     fn can_get_timezones() {
@@ -138,6 +106,16 @@ mod tests {
         assert_eq!(OsmTimezone::lookup_slow(179.9968, -67.0959).len(), 1);
     }
 
+    #[test]
+    fn can_get_by_stable_id() {
+        let tz = OsmTimezone::lookup(-121.0, 46.0)[0];
+        // Derived from the identifier alone, so it is pinned across builds and data sets.
+        assert_eq!(tz.stable_id(), 8179554650343313);
+
+        assert_eq!(OsmTimezone::get_by_stable_id(tz.stable_id()).unwrap().identifier, tz.identifier);
+        assert!(OsmTimezone::get_by_stable_id(0).is_none());
+    }
+
     #[test]
     fn can_access_lookup() {
         let cache = OsmTimezone::get_mem_lookup();
@@ -155,6 +133,167 @@ mod tests {
         assert_eq!(tzs.len(), 7);
     }
 
+    #[test]
+    fn can_narrow_lookup_within_cell() {
+        let cell = OsmTimezone::get_lookup_suggestions(-87, 38).unwrap();
+        let leaf = OsmTimezone::get_lookup_suggestions_at(-86.5, 38.5).unwrap();
+
+        assert!(leaf.len() < cell.len());
+        assert!(leaf.iter().all(|t| cell.iter().any(|c| c.id == t.id)));
+
+        // Sweep the busy cell so that its quadtree leaves are exercised.
+        for i in 0..16 {
+            for j in 0..16 {
+                let x = -87.0 + (i as Float + 0.5) / 16.0;
+                let y = 38.0 + (j as Float + 0.5) / 16.0;
+
+                assert_eq!(
+                    OsmTimezone::lookup_slow(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                    OsmTimezone::lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                    "({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn can_lookup_geometry() {
+        // A square straddling the California / Arizona border along the Colorado River.
+        let square = Rect::new(Coord { x: -115.0, y: 33.0 }, Coord { x: -114.0, y: 35.0 }).to_polygon();
+        let area = square.chamberlain_duquette_unsigned_area();
+
+        let coverage = OsmTimezone::lookup_geometry(&Geometry::Polygon(square.clone()));
+        let identifiers = coverage.iter().map(|(t, _)| t.identifier.as_ref()).collect::<Vec<_>>();
+        assert!(identifiers.contains(&"America/Los_Angeles") && identifiers.contains(&"America/Phoenix"), "{identifiers:?}");
+
+        // Every point is in exactly one zone, and clipping to the square changes no fraction.
+        assert!((coverage.iter().map(|(_, f)| f).sum::<Float>() - 1.0).abs() < 1e-3);
+        for (tz, fraction) in coverage {
+            let geometry = match tz.geometry() {
+                Geometry::Polygon(p) => MultiPolygon(vec![p.clone()]),
+                Geometry::MultiPolygon(m) => m.clone(),
+                _ => unreachable!(),
+            };
+            let full = square.intersection(&geometry).chamberlain_duquette_unsigned_area() / area;
+
+            assert!((fraction - full).abs() < 1e-4, "{}: {} vs {}", tz.identifier, fraction, full);
+        }
+
+        assert!(OsmTimezone::lookup_geometry(&Geometry::Point(Coord { x: -114.5, y: 34.0 }.into())).is_empty());
+    }
+
+    #[test]
+    fn can_get_boundary_distance() {
+        let tolerance = get_boundary_tolerance(OsmTimezone::simplification_epsilon());
+
+        // Deep inside `America/Los_Angeles`, the result is not in doubt.
+        let tz = OsmTimezone::lookup(-119.5, 37.5)[0];
+        let (distance, near) = tz.get_boundary_distance(-119.5, 37.5).unwrap();
+        assert!(distance > tolerance);
+        assert!(!near);
+
+        // Just inside the Arizona border, it is.
+        let tz = OsmTimezone::lookup(-114.45, 34.0)[0];
+        assert_eq!(tz.identifier.as_ref(), "America/Phoenix");
+        let (distance, near) = tz.get_boundary_distance(-114.45, 34.0).unwrap();
+        assert_eq!(distance, get_distance_to_boundary(tz.geometry(), -114.45, 34.0).unwrap());
+        assert!(distance <= tolerance);
+        assert!(near);
+    }
+
+    #[test]
+    fn can_lookup_route() {
+        let route = LineString::from(vec![(-118.0, 34.0), (-112.0, 33.5), (-104.0, 39.7)]);
+        let spans = OsmTimezone::lookup_route(&route);
+
+        let identifiers = spans.iter().map(|s| s.items[0].identifier.as_ref()).collect::<Vec<_>>();
+        assert_eq!(identifiers.first(), Some(&"America/Los_Angeles"));
+        assert!(identifiers.contains(&"America/Phoenix"));
+        assert_eq!(identifiers.last(), Some(&"America/Denver"));
+
+        // The spans tile the route end to end.
+        assert_eq!(spans[0].start_distance, 0.0);
+        for pair in spans.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+            assert_eq!(pair[0].end_distance, pair[1].start_distance);
+        }
+
+        // Dense sampling (the old workaround) finds nothing that the spans do not, at the same distance along the route.
+        let mut distance = 0.0;
+        for line in route.lines() {
+            for i in 0..500 {
+                let t = (i as Float + 0.5) / 500.0;
+                let point = Coord {
+                    x: line.start.x + t * line.dx(),
+                    y: line.start.y + t * line.dy(),
+                };
+                let along = distance + get_geodesic_distance(line.start, point);
+                let tz = OsmTimezone::lookup(point.x, point.y)[0];
+
+                let span = spans.iter().find(|s| s.start_distance <= along && along <= s.end_distance).unwrap();
+                let near_boundary = (along - span.start_distance).min(span.end_distance - along) < 10.0;
+                assert!(near_boundary || span.items[0].id == tz.id, "{} at {}m", tz.identifier, along);
+            }
+
+            distance += get_geodesic_distance(line.start, line.end);
+        }
+        assert!((spans.last().unwrap().end_distance - distance).abs() < 1.0);
+    }
+
+    #[test]
+    fn can_lookup_route_across_the_antimeridian() {
+        let spans = OsmTimezone::lookup_route(&LineString::from(vec![(179.5, -30.0), (-179.5, -30.0)]));
+
+        let identifiers = spans.iter().map(|s| s.items[0].identifier.as_ref()).collect::<Vec<_>>();
+        assert_eq!(identifiers, vec!["Etc/GMT-12", "Etc/GMT+12"]);
+        assert_eq!((spans[0].end.x, spans[1].start.x), (180.0, -180.0));
+
+        // Going the short way round is about one degree of longitude, not 359.
+        assert!(spans[1].end_distance < 100_000.0);
+    }
+
+    #[test]
+    fn can_skip_contains_for_covering_candidates() {
+        // The whole cell lies inside `America/Los_Angeles`, so its one candidate needs no `contains` check.
+        let cell = OsmTimezone::get_mem_lookup().get(&(-121, 46)).unwrap();
+        assert!(cell.candidates_at((-121, 46), -120.5, 46.5).is_covering(0));
+
+        // The poles and the antimeridian need no special casing: an item only covers a leaf if no edge touches it.
+        for (x, y) in [(179.9968, -67.0959), (-179.9, 0.0), (179.9, 0.0), (0.0, 89.9), (0.0, -89.9), (-180.0, -90.0)] {
+            assert_eq!(
+                OsmTimezone::lookup_slow(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                OsmTimezone::lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn can_lookup_many_in_input_order() {
+        let mut points = (0..1_000)
+            .map(|_| (rand::random::<Float>() * 360.0 - 180.0, rand::random::<Float>() * 180.0 - 90.0))
+            .collect::<Vec<_>>();
+        points.extend([(-121.0, 46.0), (-121.0, 46.0), (180.0, 90.0), (Float::NAN, 0.5)]);
+
+        let many = OsmTimezone::lookup_many(&points);
+
+        assert_eq!(many.len(), points.len());
+        assert!(many[points.len() - 1].is_empty());
+        for (&(x, y), items) in points.iter().zip(many) {
+            assert_eq!(
+                OsmTimezone::lookup(x, y).into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                items.into_iter().map(|t| t.id).collect::<Vec<_>>(),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+    }
+
     #[test]
     fn can_verify_lookup_assisted_accuracy() {
         let x = rand::random::<Float>() * 360.0 - 180.0;
@@ -1 +1,17 @@
-//! The shared functionality for the timezone lookup module.
+//! The shared functionality for the timezone lookup module.
+
+use rtz_core::geo::tz::shared::IsTimezone;
+
+use crate::CanPerformGeoLookup;
+
+// Traits.
+
+/// Trait that allows fetching a time zone by its stable identifier (see [`IsTimezone::stable_id`]).
+///
+/// This is a thin wrapper over the type's default, global [`GeoIndex`](crate::GeoIndex).
+pub trait CanGetByStableId: CanPerformGeoLookup + IsTimezone {
+    /// Get the time zone with a stable identifier (see [`GeoIndex::get_by_stable_id`](crate::GeoIndex::get_by_stable_id)).
+    fn get_by_stable_id(stable_id: u64) -> Option<&'static Self> {
+        Self::get_mem_index().get_by_stable_id(stable_id)
+    }
+}
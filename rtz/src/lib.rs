@@ -36,7 +36,9 @@ assert_eq!(
 
 pub mod geo;
 pub mod shared;
+pub use crate::geo::index::GeoIndex;
 pub use crate::geo::shared::CanPerformGeoLookup;
+pub use crate::geo::tz::shared::CanGetByStableId;
 
 #[cfg(feature = "tz-ned")]
 pub use rtz_core::geo::tz::ned::NedTimezone;
@@ -47,6 +49,9 @@ pub use rtz_core::geo::tz::osm::OsmTimezone;
 #[cfg(feature = "admin-osm")]
 pub use rtz_core::geo::admin::osm::OsmAdmin;
 
+#[cfg(feature = "custom")]
+pub use rtz_core::geo::custom::{CustomFeature, CustomFeatureMapping};
+
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
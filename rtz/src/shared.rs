@@ -1,6 +1,6 @@
 //! Shared functionality for the `rtz` crate.
 
-#[cfg(any(feature = "admin-osm", feature = "tz-ned", feature = "tz-osm"))]
+#[cfg(any(feature = "admin-osm", feature = "tz-ned", feature = "tz-osm", feature = "custom"))]
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "web")]
@@ -9,12 +9,20 @@ use utoipa::ToSchema;
 #[cfg(feature = "wasm")]
 use tsify::Tsify;
 
+#[cfg(any(feature = "admin-osm", feature = "tz-ned", feature = "tz-osm", feature = "custom"))]
+use rtz_core::base::types::Float;
 #[cfg(feature = "admin-osm")]
 use rtz_core::geo::admin::osm::OsmAdmin;
+#[cfg(feature = "custom")]
+use rtz_core::geo::custom::CustomFeature;
+#[cfg(feature = "custom")]
+use rtz_core::geo::shared::HasProperties;
 #[cfg(feature = "tz-ned")]
 use rtz_core::geo::tz::ned::NedTimezone;
 #[cfg(feature = "tz-osm")]
 use rtz_core::geo::tz::osm::OsmTimezone;
+#[cfg(any(feature = "tz-ned", feature = "tz-osm"))]
+use rtz_core::geo::tz::shared::IsTimezone;
 
 /// The response type for the NED timezone endpoint when found.
 ///
@@ -29,6 +37,9 @@ pub struct NedTimezoneResponse1 {
     ///
     /// This is not stable across builds or new data sets.  It is merely unique during a single build.
     pub id: usize,
+    /// An identifier derived from this timezone's contents, which is stable across builds and
+    /// simplification settings (e.g., for storing, and later fetching, this timezone by id).
+    pub stable_id: u64,
     /// The IANA time zone identifier (e.g., `America/Los_Angeles`).
     pub identifier: Option<&'static str>,
 
@@ -44,6 +55,11 @@ pub struct NedTimezoneResponse1 {
     pub zone: f32,
     /// The UTC offset in seconds (e.g., `-28800`).
     pub raw_offset: i32,
+
+    /// The geodesic distance, in meters, from the query point to this timezone's boundary, when the point
+    /// resolved to nothing and this is the nearest timezone instead.  Only present then (e.g., `?nearest=1000`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
 }
 
 #[cfg(feature = "tz-ned")]
@@ -51,16 +67,29 @@ impl From<&'static NedTimezone> for NedTimezoneResponse1 {
     fn from(value: &'static NedTimezone) -> NedTimezoneResponse1 {
         NedTimezoneResponse1 {
             id: value.id,
+            stable_id: value.stable_id(),
             identifier: value.identifier.as_deref(),
             description: value.description.as_ref(),
             dst_description: value.dst_description.as_deref(),
             offset: value.offset.as_ref(),
             zone: value.zone,
             raw_offset: value.raw_offset,
+            distance: None,
         }
     }
 }
 
+#[cfg(feature = "tz-ned")]
+impl NedTimezoneResponse1 {
+    /// Attach the distance from the query point to the timezone's boundary, when the timezone is the
+    /// nearest one (see [`CanPerformGeoLookup::lookup_nearest`](crate::CanPerformGeoLookup::lookup_nearest)).
+    pub fn with_distance(mut self, distance: Option<Float>) -> Self {
+        self.distance = distance.map(|distance| distance as f64);
+
+        self
+    }
+}
+
 /// The response type for the OSM timezone endpoint when found.
 ///
 /// Currently ingested version of this data set is [here](https://github.com/evansiroky/timezone-boundary-builder/releases/download/2023b/timezones-with-oceans.geojson.zip).
@@ -74,6 +103,9 @@ pub struct OsmTimezoneResponse1 {
     ///
     /// This is not stable across builds or new data sets.  It is merely unique during a single build.
     pub id: usize,
+    /// An identifier derived from this timezone's contents, which is stable across builds and
+    /// simplification settings (e.g., for storing, and later fetching, this timezone by id).
+    pub stable_id: u64,
     /// The IANA time zone identifier (e.g., `America/Los_Angeles`).
     pub identifier: &'static str,
     /// The abbreviated name of the offset currently in effect (e.g., `PDT`).
@@ -94,6 +126,20 @@ pub struct OsmTimezoneResponse1 {
 
     /// The current time in this timezone, as an RFC 3339 timestamp.
     pub current_time: String,
+
+    /// The geodesic distance, in meters, from the query point to this timezone's nearest boundary
+    /// edge.  Only present when requested (e.g., `?boundary=true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boundary_distance: Option<f64>,
+    /// Whether the query point is close enough to a boundary that build-time simplification may
+    /// have put it on the wrong side.  Only present when requested (e.g., `?boundary=true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub near_boundary: Option<bool>,
+
+    /// The geodesic distance, in meters, from the query point to this timezone's boundary, when the point
+    /// resolved to nothing and this is the nearest timezone instead.  Only present then (e.g., `?nearest=1000`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
 }
 
 #[cfg(feature = "tz-osm")]
@@ -120,6 +166,7 @@ impl From<&'static OsmTimezone> for OsmTimezoneResponse1 {
 
         OsmTimezoneResponse1 {
             id: value.id,
+            stable_id: value.stable_id(),
             identifier: value.identifier.as_ref(),
             short_identifier,
             offset,
@@ -128,10 +175,33 @@ impl From<&'static OsmTimezone> for OsmTimezoneResponse1 {
             raw_dst_offset,
             zone,
             current_time,
+            boundary_distance: None,
+            near_boundary: None,
+            distance: None,
         }
     }
 }
 
+#[cfg(feature = "tz-osm")]
+impl OsmTimezoneResponse1 {
+    /// Attach the distance from the query point to the timezone's boundary, and whether that is
+    /// near enough to doubt the result (see [`CanPerformGeoLookup::get_boundary_distance`](crate::CanPerformGeoLookup::get_boundary_distance)).
+    pub fn with_boundary(mut self, boundary: Option<(Float, bool)>) -> Self {
+        self.boundary_distance = boundary.map(|(distance, _)| distance as f64);
+        self.near_boundary = boundary.map(|(_, near)| near);
+
+        self
+    }
+
+    /// Attach the distance from the query point to the timezone's boundary, when the timezone is the
+    /// nearest one (see [`CanPerformGeoLookup::lookup_nearest`](crate::CanPerformGeoLookup::lookup_nearest)).
+    pub fn with_distance(mut self, distance: Option<Float>) -> Self {
+        self.distance = distance.map(|distance| distance as f64);
+
+        self
+    }
+}
+
 /// The response type for the OSM admin endpoint when found.
 ///
 /// Results are returned broadest-first: ascending by `level`, so a point inside nested areas
@@ -158,6 +228,20 @@ pub struct OsmAdminResponse1 {
 
     /// The OSM admin level of the area (e.g., `2` for a country).
     pub level: usize,
+
+    /// The geodesic distance, in meters, from the query point to this area's nearest boundary edge.
+    /// Only present when requested (e.g., `?boundary=true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boundary_distance: Option<f64>,
+    /// Whether the query point is close enough to a boundary that build-time simplification may
+    /// have put it on the wrong side.  Only present when requested (e.g., `?boundary=true`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub near_boundary: Option<bool>,
+
+    /// The geodesic distance, in meters, from the query point to this area's boundary, when the point
+    /// resolved to nothing and this is the nearest area instead.  Only present then (e.g., `?nearest=1000`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
 }
 
 #[cfg(feature = "admin-osm")]
@@ -168,6 +252,91 @@ impl From<&'static OsmAdmin> for OsmAdminResponse1 {
             relation_id: (value.relation_id != 0).then_some(value.relation_id),
             name: value.name.as_ref(),
             level: value.level,
+            boundary_distance: None,
+            near_boundary: None,
+            distance: None,
         }
     }
 }
+
+#[cfg(feature = "admin-osm")]
+impl OsmAdminResponse1 {
+    /// Attach the distance from the query point to the area's boundary, and whether that is near
+    /// enough to doubt the result (see [`CanPerformGeoLookup::get_boundary_distance`](crate::CanPerformGeoLookup::get_boundary_distance)).
+    pub fn with_boundary(mut self, boundary: Option<(Float, bool)>) -> Self {
+        self.boundary_distance = boundary.map(|(distance, _)| distance as f64);
+        self.near_boundary = boundary.map(|(_, near)| near);
+
+        self
+    }
+
+    /// Attach the distance from the query point to the area's boundary, when the area is the nearest
+    /// one (see [`CanPerformGeoLookup::lookup_nearest`](crate::CanPerformGeoLookup::lookup_nearest)).
+    pub fn with_distance(mut self, distance: Option<Float>) -> Self {
+        self.distance = distance.map(|distance| distance as f64);
+
+        self
+    }
+}
+
+/// The response type for the custom dataset endpoints when found.
+///
+/// The dataset, and which of its properties become the key and name, are configured on the server.
+#[cfg(feature = "custom")]
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "web", derive(ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFeatureResponse1 {
+    /// The index of this feature in its dataset.
+    ///
+    /// This is not stable across builds or new data sets.  It is merely unique during a single build.
+    pub id: usize,
+    /// The value of the feature's key property (e.g., a region code), if it has one.
+    pub key: Option<&'static str>,
+    /// The value of the feature's name property, if it has one.
+    pub name: Option<&'static str>,
+    /// The kept properties of the feature.
+    #[cfg_attr(feature = "web", schema(value_type = Object))]
+    pub properties: serde_json::Map<String, serde_json::Value>,
+
+    /// The geodesic distance, in meters, from the query point to this feature's boundary, when the point
+    /// resolved to nothing and this is the nearest feature instead.  Only present then (e.g., `?nearest=1000`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+}
+
+#[cfg(feature = "custom")]
+impl From<&'static CustomFeature> for CustomFeatureResponse1 {
+    fn from(value: &'static CustomFeature) -> CustomFeatureResponse1 {
+        CustomFeatureResponse1 {
+            id: value.id,
+            key: value.key.as_deref(),
+            name: value.name.as_deref(),
+            properties: value.properties(),
+            distance: None,
+        }
+    }
+}
+
+#[cfg(feature = "custom")]
+impl CustomFeatureResponse1 {
+    /// Attach the distance from the query point to the feature's boundary, when the feature is the
+    /// nearest one (see [`CanPerformGeoLookup::lookup_nearest`](crate::CanPerformGeoLookup::lookup_nearest)).
+    pub fn with_distance(mut self, distance: Option<Float>) -> Self {
+        self.distance = distance.map(|distance| distance as f64);
+
+        self
+    }
+}
+
+/// The response type for the geometry coverage endpoints: an item that the query geometry overlaps,
+/// and the fraction (from `0` to `1`) of the query geometry's area that falls in it.
+#[cfg(feature = "web")]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageResponse1<T> {
+    /// The overlapped item.
+    pub item: T,
+    /// The fraction of the query geometry's area that falls in the item (e.g., `0.25`).
+    pub fraction: f64,
+}
@@ -1,7 +1,9 @@
 //! The configuration module.
 
+use std::collections::HashMap;
+
 use config::{Environment, File};
-use rtz_core::base::types::Res;
+use rtz_core::{base::types::Res, geo::custom::CustomFeatureMapping};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -10,6 +12,8 @@ struct OptionalConfig {
     port: Option<u16>,
     should_log: Option<bool>,
     analytics_api_key: Option<String>,
+    data_path: Option<String>,
+    custom_layers: Option<HashMap<String, CustomLayerConfig>>,
 }
 
 /// The configuration type.
@@ -19,6 +23,32 @@ pub struct Config {
     pub(crate) port: u16,
     pub(crate) should_log: bool,
     pub(crate) analytics_api_key: Option<String>,
+    /// A directory of dataset bincodes to load at startup, in place of the embedded ones (e.g., `RTZ_DATA_PATH`).
+    pub(crate) data_path: Option<String>,
+    /// The custom datasets to index at startup, by the name they are served under (e.g., `/api/v1/custom/{name}/{lng}/{lat}`).
+    pub(crate) custom_layers: HashMap<String, CustomLayerConfig>,
+}
+
+/// The configuration of a custom dataset: either a GeoJSON feature collection to index at
+/// startup, or the bincodes generated from one (e.g., by `generate_custom_bincodes`).
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CustomLayerConfig {
+    /// The GeoJSON feature collection of the dataset.
+    #[serde(default)]
+    pub(crate) geojson: Option<String>,
+    /// The items bincode of the dataset.
+    #[serde(default)]
+    pub(crate) items: Option<String>,
+    /// The lookup bincode of the dataset.
+    #[serde(default)]
+    pub(crate) lookup: Option<String>,
+    /// How the GeoJSON features are read.
+    #[serde(default)]
+    pub(crate) mapping: CustomFeatureMapping,
+    /// The most geometry problems (see `RepairReport`) that the GeoJSON features may have before the
+    /// server refuses to start, or `None` to only log them.
+    #[serde(default)]
+    pub(crate) max_problems: Option<usize>,
 }
 
 impl Config {
@@ -53,6 +83,8 @@ impl Config {
                 })
             }),
             analytics_api_key: optional_config.analytics_api_key,
+            data_path: optional_config.data_path,
+            custom_layers: optional_config.custom_layers.unwrap_or_default(),
         };
 
         Ok(config)
@@ -59,7 +59,7 @@ mod tests {
     fn get_client() -> Router {
         let config = Config::new("", None, None, Some(false)).unwrap();
 
-        server::create_axum_app(&config)
+        server::create_axum_app(&config).unwrap()
     }
 
     #[tokio::test]
@@ -72,7 +72,7 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
 
         let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
-        let expected = r#"[{"id":20,"identifier":"America/Los_Angeles","description":"Canada (most of British Columbia), Mexico (Baja California), United States (California, most of Nevada, most of Oregon, Washington (state))","dstDescription":"Canada (most of British Columbia), Mexico (Baja California), United States (California, most of Nevada, most of Oregon, Washington (state))","offset":"UTC-08:00","zone":-8.0,"rawOffset":-28800}]"#;
+        let expected = r#"[{"id":20,"stableId":7560146186358997,"identifier":"America/Los_Angeles","description":"Canada (most of British Columbia), Mexico (Baja California), United States (California, most of Nevada, most of Oregon, Washington (state))","dstDescription":"Canada (most of British Columbia), Mexico (Baja California), United States (California, most of Nevada, most of Oregon, Washington (state))","offset":"UTC-08:00","zone":-8.0,"rawOffset":-28800}]"#;
 
         assert_eq!(body, expected);
     }
@@ -92,6 +92,32 @@ mod tests {
         assert_eq!(body, expected);
     }
 
+    #[tokio::test]
+    async fn can_get_nearest_ned_timezone_v1() {
+        let client = get_client();
+
+        // The same boundary point as above, but allowed to fall back to a zone within 1km.
+        let request = Request::get("/api/v1/ned/tz/179.9968/-67.0959?nearest=1000").body(Body::empty()).unwrap();
+        let response = client.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed.as_array().unwrap().len(), 1);
+
+        // The fallback says how far away the zone is.
+        let distance = parsed[0]["distance"].as_f64().unwrap();
+        assert!((0.0..=1000.0).contains(&distance));
+
+        // A point that resolves has no distance.
+        let request = Request::get("/api/v1/ned/tz/-121.0/46.0?nearest=1000").body(Body::empty()).unwrap();
+        let response = get_client().oneshot(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(parsed[0].get("distance").is_none());
+    }
+
     #[tokio::test]
     async fn can_get_not_modified_ned_timezone_v1() {
         let mut client = get_client();
@@ -127,6 +153,94 @@ mod tests {
         assert!(identifiers.contains(&"America/Phoenix"), "expected America/Phoenix in {identifiers:?}");
     }
 
+    #[tokio::test]
+    async fn can_get_osm_timezone_boundary_v1() {
+        let client = get_client();
+
+        // Without `?boundary=true`, the response has no boundary fields.
+        let request = Request::get("/api/v1/osm/tz/-112/33").body(Body::empty()).unwrap();
+        let response = client.clone().oneshot(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(parsed[0].get("boundaryDistance").is_none());
+        assert!(parsed[0].get("nearBoundary").is_none());
+
+        // Just inside the Arizona border, the result is flagged as near it.
+        let request = Request::get("/api/v1/osm/tz/-114.45/34?boundary=true").body(Body::empty()).unwrap();
+        let response = client.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(parsed[0]["boundaryDistance"].as_f64().unwrap() > 0.0);
+        assert_eq!(parsed[0]["nearBoundary"], true);
+    }
+
+    #[tokio::test]
+    async fn can_get_osm_timezone_by_stable_id_v1() {
+        let client = get_client();
+
+        let request = Request::get("/api/v1/osm/tz/-112/33").body(Body::empty()).unwrap();
+        let response = client.clone().oneshot(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let stable_id = parsed[0]["stableId"].as_u64().unwrap();
+
+        let request = Request::get(format!("/api/v1/osm/tz/id/{stable_id}")).body(Body::empty()).unwrap();
+        let response = client.clone().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let fetched: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(fetched["identifier"], parsed[0]["identifier"]);
+        assert_eq!(fetched["stableId"], stable_id);
+
+        let request = Request::get("/api/v1/osm/tz/id/0").body(Body::empty()).unwrap();
+        let response = client.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed["code"], "unknown_stable_id");
+    }
+
+    #[tokio::test]
+    async fn can_post_osm_timezone_geometry_v1() {
+        let client = get_client();
+
+        let geometry = r#"{"type":"Polygon","coordinates":[[[-115,33],[-114,33],[-114,35],[-115,35],[-115,33]]]}"#;
+        let request = Request::post("/api/v1/osm/tz").header("Content-Type", "application/json").body(Body::from(geometry)).unwrap();
+        let response = client.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let coverage = parsed
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| (v["item"]["identifier"].as_str().unwrap(), v["fraction"].as_f64().unwrap()))
+            .collect::<Vec<_>>();
+        assert!(coverage.iter().any(|&(identifier, _)| identifier == "America/Phoenix"), "{coverage:?}");
+        assert!((coverage.iter().map(|&(_, fraction)| fraction).sum::<f64>() - 1.0).abs() < 1e-3, "{coverage:?}");
+    }
+
+    #[tokio::test]
+    async fn non_polygon_geometry_is_bad_request() {
+        let client = get_client();
+
+        for body in [r#"{"type":"Point","coordinates":[-114.5,34]}"#, "not json"] {
+            let request = Request::post("/api/v1/osm/tz").body(Body::from(body)).unwrap();
+            let response = client.clone().oneshot(request).await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
     #[tokio::test]
     async fn can_get_osm_admin_v1() {
         let client = get_client();
@@ -151,6 +265,42 @@ mod tests {
         assert!(admins.contains(&("مطروح", 4, 3061826)), "expected Matrouh (level 4, rel 3061826) in {admins:?}");
     }
 
+    #[tokio::test]
+    async fn can_post_osm_admin_geometry_v1() {
+        let client = get_client();
+
+        let geometry = r#"{"type":"Polygon","coordinates":[[[29.5,29.5],[30.5,29.5],[30.5,30.5],[29.5,30.5],[29.5,29.5]]]}"#;
+        let request = Request::post("/api/v1/osm/admin").header("Content-Type", "application/json").body(Body::from(geometry)).unwrap();
+        let response = client.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Assert the shape of the coverage, not its names: those depend on the committed admin bincodes (see
+        // `can_get_osm_admin_v1`). The square lies well inside Egypt, so some district covers all of it.
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let coverage = parsed
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| (v["item"]["name"].as_str().unwrap(), v["item"]["level"].as_u64().unwrap(), v["fraction"].as_f64().unwrap()))
+            .collect::<Vec<_>>();
+        assert!(coverage.iter().any(|&(_, _, fraction)| (fraction - 1.0).abs() < 1e-3), "{coverage:?}");
+        assert!(coverage.iter().all(|&(_, _, fraction)| fraction > 0.0 && fraction <= 1.0 + 1e-3), "{coverage:?}");
+    }
+
+    #[tokio::test]
+    async fn non_polygon_admin_geometry_is_bad_request() {
+        let client = get_client();
+
+        for body in [r#"{"type":"Point","coordinates":[30,30]}"#, "not json"] {
+            let request = Request::post("/api/v1/osm/admin").body(Body::from(body)).unwrap();
+            let response = client.clone().oneshot(request).await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
     #[tokio::test]
     async fn unversioned_ned_matches_v1_body() {
         let client = get_client();
@@ -174,6 +324,102 @@ mod tests {
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[tokio::test]
+    async fn invalid_coordinate_is_unprocessable() {
+        for (uri, code) in [("/api/v1/ned/tz/-121/95", "latitude_out_of_range"), ("/api/v1/osm/tz/NaN/46", "coordinate_not_finite")] {
+            let request = Request::get(uri).body(Body::empty()).unwrap();
+            let response = get_client().oneshot(request).await.unwrap();
+
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY, "{}", uri);
+
+            let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+            let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(parsed["status"], 422);
+            assert_eq!(parsed["code"], code);
+        }
+
+        // Longitudes wrap, so the antimeridian and beyond are still on the grid.
+        let request = Request::get("/api/v1/ned/tz/239/46").body(Body::empty()).unwrap();
+        let response = get_client().oneshot(request).await.unwrap();
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed[0]["identifier"], "America/Los_Angeles");
+    }
+
+    #[tokio::test]
+    async fn can_get_custom_v1() {
+        use rtz_core::geo::custom::CustomFeatureMapping;
+
+        use crate::web::config::CustomLayerConfig;
+
+        let path = std::env::temp_dir().join(format!("rtz_web_custom_{}.geojson", std::process::id()));
+        let geojson = r#"{ "type": "FeatureCollection", "features": [{ "type": "Feature", "properties": { "code": "W", "name": "West", "quota": 3 },
+            "geometry": { "type": "Polygon", "coordinates": [[[-125, 30], [-110, 30], [-110, 50], [-125, 50], [-125, 30]]] } }] }"#;
+        std::fs::write(&path, geojson).unwrap();
+
+        let mut config = Config::new("", None, None, Some(false)).unwrap();
+        config.custom_layers.insert(
+            "regions".to_string(),
+            CustomLayerConfig {
+                geojson: Some(path.to_string_lossy().to_string()),
+                mapping: CustomFeatureMapping::default().with_key("code").with_properties(["quota"]),
+                ..Default::default()
+            },
+        );
+        let client = server::create_axum_app(&config).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let request = Request::get("/api/v1/custom/regions/-121.0/46.0").body(Body::empty()).unwrap();
+        let response = client.clone().oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap_or_default().to_bytes();
+        let expected = r#"[{"id":0,"key":"W","name":"West","properties":{"quota":3}}]"#;
+
+        assert_eq!(body, expected);
+
+        let request = Request::get("/api/v1/custom/territories/-121.0/46.0").body(Body::empty()).unwrap();
+        let response = client.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn custom_layer_without_data_is_an_error() {
+        use crate::web::config::CustomLayerConfig;
+
+        let mut config = Config::new("", None, None, Some(false)).unwrap();
+        config.custom_layers.insert("regions".to_string(), CustomLayerConfig::default());
+
+        assert!(server::create_axum_app(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn custom_layer_past_its_problem_limit_is_an_error() {
+        use crate::web::config::CustomLayerConfig;
+
+        let path = std::env::temp_dir().join(format!("rtz_web_custom_problems_{}.geojson", std::process::id()));
+        let geojson = r#"{ "type": "FeatureCollection", "features": [{ "type": "Feature", "properties": { "name": "Bowtie" },
+            "geometry": { "type": "Polygon", "coordinates": [[[0, 0], [2, 2], [2, 0], [0, 2], [0, 0]]] } }] }"#;
+        std::fs::write(&path, geojson).unwrap();
+
+        let mut config = Config::new("", None, None, Some(false)).unwrap();
+        let layer = CustomLayerConfig {
+            geojson: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+
+        // Without a limit, the bowtie is repaired (and logged).
+        config.custom_layers.insert("regions".to_string(), layer.clone());
+        assert!(server::create_axum_app(&config).is_ok());
+
+        config.custom_layers.insert("regions".to_string(), CustomLayerConfig { max_problems: Some(0), ..layer });
+        assert!(server::create_axum_app(&config).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[tokio::test]
     async fn unknown_route_is_not_found() {
         let client = get_client();
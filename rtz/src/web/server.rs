@@ -3,13 +3,21 @@ use std::{
     sync::{Arc, OnceLock},
 };
 
-use axum::{extract::Path, routing::get, Json, Router};
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
 use axum_insights::AppInsights;
+use geo::Geometry;
+use geojson::GeoJson;
 use http::{Method, StatusCode};
 use rtz_core::{
-    base::types::{Float, Void},
+    base::types::{Float, Res, Void},
     geo::{
         admin::osm::OsmAdmin,
+        custom::CustomFeature,
+        shared::{ConcreteVec, HasGeometry},
         tz::{ned::NedTimezone, osm::OsmTimezone},
     },
 };
@@ -21,14 +29,15 @@ use utoipa_redoc::{Redoc, Servable};
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
-    shared::{NedTimezoneResponse1, OsmAdminResponse1, OsmTimezoneResponse1},
-    CanPerformGeoLookup,
+    geo::shared::HasGeoIndex,
+    shared::{CoverageResponse1, CustomFeatureResponse1, NedTimezoneResponse1, OsmAdminResponse1, OsmTimezoneResponse1},
+    CanGetByStableId, CanPerformGeoLookup, GeoIndex,
 };
 
 use super::{
-    config::Config,
+    config::{Config, CustomLayerConfig},
     response_types::LookupResponse,
-    types::{get_last_modified_time, AppState, IfModifiedSince, WebError, WebResult, WebVoid},
+    types::{get_last_modified_time, AppState, BoundaryQuery, IfModifiedSince, LookupQuery, WebError, WebResult, WebVoid},
     utilities::shutdown_signal,
 };
 
@@ -40,7 +49,11 @@ static FLY_PUBLIC_IP: OnceLock<String> = OnceLock::new();
 
 /// Starts the web server.
 pub async fn start(config: &Config) -> Void {
-    let app = create_axum_app(config);
+    if let Some(data_path) = &config.data_path {
+        load_data(std::path::Path::new(data_path))?;
+    }
+
+    let app = create_axum_app(config)?;
 
     let bind_address = format!("{}:{}", config.bind_address, config.port);
     let listener = tokio::net::TcpListener::bind(bind_address).await.unwrap();
@@ -49,10 +62,11 @@ pub async fn start(config: &Config) -> Void {
     Ok(())
 }
 
-pub fn create_axum_app(config: &Config) -> Router {
-    let state = AppState { config: Arc::new(config.clone()) };
+pub fn create_axum_app(config: &Config) -> Res<Router> {
+    let layers = Box::leak(Box::new(load_custom_layers(&config.custom_layers)?));
+    let state = AppState { config: Arc::new(config.clone()), layers };
 
-    let cors_layer = CorsLayer::new().allow_methods([Method::GET]).allow_origin(Any);
+    let cors_layer = CorsLayer::new().allow_methods([Method::GET, Method::POST]).allow_headers(Any).allow_origin(Any);
 
     let name = std::env::var("FLY_REGION").unwrap_or_else(|_| "server".to_string());
     let _ = FLY_REGION.set(name.clone());
@@ -84,6 +98,7 @@ pub fn create_axum_app(config: &Config) -> Router {
                     status: 500,
                     message: format!("A panic occurred: {:?}", e),
                     backtrace: None,
+                    code: None,
                 },
             )
         })
@@ -98,25 +113,53 @@ pub fn create_axum_app(config: &Config) -> Router {
         .route("/health", get(health))
         .route("/ned/tz/{lng}/{lat}", get(timezone_ned))
         .route("/v1/ned/tz/{lng}/{lat}", get(timezone_ned_v1))
+        .route("/ned/tz/id/{stable_id}", get(timezone_ned_by_id))
+        .route("/v1/ned/tz/id/{stable_id}", get(timezone_ned_by_id_v1))
         .route("/osm/tz/{lng}/{lat}", get(timezone_osm))
         .route("/v1/osm/tz/{lng}/{lat}", get(timezone_osm_v1))
+        .route("/osm/tz/id/{stable_id}", get(timezone_osm_by_id))
+        .route("/v1/osm/tz/id/{stable_id}", get(timezone_osm_by_id_v1))
         .route("/osm/admin/{lng}/{lat}", get(admin_osm))
-        .route("/v1/osm/admin/{lng}/{lat}", get(admin_osm_v1));
+        .route("/v1/osm/admin/{lng}/{lat}", get(admin_osm_v1))
+        .route("/osm/tz", post(timezone_osm_geometry))
+        .route("/v1/osm/tz", post(timezone_osm_geometry_v1))
+        .route("/osm/admin", post(admin_osm_geometry))
+        .route("/v1/osm/admin", post(admin_osm_geometry_v1))
+        .route("/custom/{layer}/{lng}/{lat}", get(custom))
+        .route("/v1/custom/{layer}/{lng}/{lat}", get(custom_v1));
 
-    Router::new()
+    Ok(Router::new()
         .merge(SwaggerUi::new("/swagger").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .merge(Redoc::with_url("/redoc", ApiDoc::openapi()))
         .merge(RapiDoc::new("/api-docs/openapi.json").path("/rapidoc"))
         .nest("/api", api_router)
         .layer(cors_layer)
         .layer(telemetry_layer)
-        .with_state(state)
+        .with_state(state))
 }
 
 #[derive(OpenApi)]
 #[openapi(
-    paths(health, timezone_ned, timezone_ned_v1, timezone_osm, timezone_osm_v1, admin_osm, admin_osm_v1),
-    components(schemas(NedTimezoneResponse1, OsmTimezoneResponse1, OsmAdminResponse1))
+    paths(
+        health,
+        timezone_ned,
+        timezone_ned_v1,
+        timezone_ned_by_id,
+        timezone_ned_by_id_v1,
+        timezone_osm,
+        timezone_osm_v1,
+        timezone_osm_by_id,
+        timezone_osm_by_id_v1,
+        admin_osm,
+        admin_osm_v1,
+        timezone_osm_geometry,
+        timezone_osm_geometry_v1,
+        admin_osm_geometry,
+        admin_osm_geometry_v1,
+        custom,
+        custom_v1
+    ),
+    components(schemas(NedTimezoneResponse1, OsmTimezoneResponse1, OsmAdminResponse1, CustomFeatureResponse1))
 )]
 struct ApiDoc;
 
@@ -135,7 +178,7 @@ struct ApiDoc;
 )]
 #[instrument]
 async fn health(if_modified_since: IfModifiedSince) -> WebVoid {
-    timezone_ned_v1(Path((30.0, 30.0)), if_modified_since).await?;
+    timezone_ned_v1(Path((30.0, 30.0)), Query(LookupQuery::default()), if_modified_since).await?;
 
     Ok(())
 }
@@ -151,16 +194,17 @@ async fn health(if_modified_since: IfModifiedSince) -> WebVoid {
     context_path = "/api", 
     path = "/ned/tz/{lng}/{lat}", 
     tag = "TZ", 
-    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude.")), 
+    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude."), LookupQuery), 
     responses(
         (status = 200, description = "List all found timezones successfully.", body = Vec<NedTimezoneResponse1>),
         (status = 304, description = "Not modified."),
         (status = 404, description = "No timezone results: location likely resides on a boundary."),
+        (status = 422, description = "The coordinate is not a point on the globe (e.g., a latitude outside of `[-90, 90]`).", body = WebError),
     )
 )]
 #[instrument]
-async fn timezone_ned(Path((lng, lat)): Path<(Float, Float)>, if_modified_since: IfModifiedSince) -> WebResult<LookupResponse<Vec<NedTimezoneResponse1>>> {
-    timezone_ned_v1(Path((lng, lat)), if_modified_since).await
+async fn timezone_ned(Path((lng, lat)): Path<(Float, Float)>, query: Query<LookupQuery>, if_modified_since: IfModifiedSince) -> WebResult<LookupResponse<Vec<NedTimezoneResponse1>>> {
+    timezone_ned_v1(Path((lng, lat)), query, if_modified_since).await
 }
 
 /// Gets time zone information from the NED dataset.
@@ -174,25 +218,79 @@ async fn timezone_ned(Path((lng, lat)): Path<(Float, Float)>, if_modified_since:
     context_path = "/api", 
     path = "/v1/ned/tz/{lng}/{lat}", 
     tag = "TZv1", 
-    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude.")), 
+    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude."), LookupQuery), 
     responses(
         (status = 200, description = "List all found timezones successfully.", body = Vec<NedTimezoneResponse1>),
         (status = 304, description = "Not modified."),
         (status = 404, description = "No timezone results: location likely resides on a boundary."),
+        (status = 422, description = "The coordinate is not a point on the globe (e.g., a latitude outside of `[-90, 90]`).", body = WebError),
     )
 )]
 #[instrument]
-async fn timezone_ned_v1(Path((lng, lat)): Path<(Float, Float)>, if_modified_since: IfModifiedSince) -> WebResult<LookupResponse<Vec<NedTimezoneResponse1>>> {
+async fn timezone_ned_v1(Path((lng, lat)): Path<(Float, Float)>, Query(query): Query<LookupQuery>, if_modified_since: IfModifiedSince) -> WebResult<LookupResponse<Vec<NedTimezoneResponse1>>> {
     if if_modified_since.as_str() == get_last_modified_time() {
         log::warn!("Not modified.");
         return Ok(LookupResponse::NotModified);
     }
 
-    let tzs = NedTimezone::lookup(lng, lat).into_iter().map(|tz| tz.into()).collect::<Vec<_>>();
+    let tzs = lookup_or_nearest::<NedTimezone>(lng, lat, &query)?
+        .into_iter()
+        .map(|(tz, distance)| NedTimezoneResponse1::from(tz).with_distance(distance))
+        .collect::<Vec<_>>();
 
     Ok(LookupResponse::Ok(Json(tzs)))
 }
 
+/// Gets a NED time zone by its stable id.
+///
+/// Returns the time zone with the given `stableId` (as returned by a lookup) from the [Natural Earth Data](https://www.naturalearthdata.com/) dataset.
+///
+/// The `stableId` is derived from the `identifier`, the `offset`, and the bounding box of the zone, rounded to whole degrees,
+/// so no two features share it, even those without an `identifier` (e.g., the oceans), or those that share one.
+///
+/// This API endpoint is provided under the same [license](https://github.com/twitchax/rtz/blob/main/LICENSE) as the
+/// [project](https://github.com/twitchax/rtz) itself.  It is provided as-is, with no warranty (as of today).
+#[utoipa::path(
+    get,
+    context_path = "/api", 
+    path = "/ned/tz/id/{stable_id}", 
+    tag = "TZ", 
+    params(("stable_id" = u64, Path, description = "The stable id of the time zone.")), 
+    responses(
+        (status = 200, description = "Found the time zone successfully.", body = NedTimezoneResponse1),
+        (status = 404, description = "No time zone has this stable id.", body = WebError),
+    )
+)]
+#[instrument]
+async fn timezone_ned_by_id(stable_id: Path<u64>) -> WebResult<Json<NedTimezoneResponse1>> {
+    timezone_ned_by_id_v1(stable_id).await
+}
+
+/// Gets a NED time zone by its stable id.
+///
+/// Returns the time zone with the given `stableId` (as returned by a lookup) from the [Natural Earth Data](https://www.naturalearthdata.com/) dataset.
+///
+/// The `stableId` is derived from the `identifier`, the `offset`, and the bounding box of the zone, rounded to whole degrees,
+/// so no two features share it, even those without an `identifier` (e.g., the oceans), or those that share one.
+///
+/// This API endpoint is provided under the same [license](https://github.com/twitchax/rtz/blob/main/LICENSE) as the
+/// [project](https://github.com/twitchax/rtz) itself.  It is provided as-is, with no warranty (as of today).
+#[utoipa::path(
+    get,
+    context_path = "/api", 
+    path = "/v1/ned/tz/id/{stable_id}", 
+    tag = "TZv1", 
+    params(("stable_id" = u64, Path, description = "The stable id of the time zone.")), 
+    responses(
+        (status = 200, description = "Found the time zone successfully.", body = NedTimezoneResponse1),
+        (status = 404, description = "No time zone has this stable id.", body = WebError),
+    )
+)]
+#[instrument]
+async fn timezone_ned_by_id_v1(Path(stable_id): Path<u64>) -> WebResult<Json<NedTimezoneResponse1>> {
+    Ok(Json(get_by_stable_id::<NedTimezone>(stable_id)?.into()))
+}
+
 /// Gets time zone information from the OSM dataset.
 ///
 /// Returns the time zone information for the given `(lng,lat)` from the [OpenStreetMap](https://www.openstreetmap.org/) dataset.
@@ -204,16 +302,17 @@ async fn timezone_ned_v1(Path((lng, lat)): Path<(Float, Float)>, if_modified_sin
     context_path = "/api", 
     path = "/osm/tz/{lng}/{lat}", 
     tag = "TZ", 
-    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude.")), 
+    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude."), LookupQuery, BoundaryQuery), 
     responses(
         (status = 200, description = "List all found timezones successfully.", body = Vec<OsmTimezoneResponse1>),
         (status = 304, description = "Not modified."),
         (status = 404, description = "No timezone results: location likely resides on a boundary."),
+        (status = 422, description = "The coordinate is not a point on the globe (e.g., a latitude outside of `[-90, 90]`).", body = WebError),
     )
 )]
 #[instrument]
-async fn timezone_osm(Path((lng, lat)): Path<(Float, Float)>) -> WebResult<LookupResponse<Vec<OsmTimezoneResponse1>>> {
-    timezone_osm_v1(Path((lng, lat))).await
+async fn timezone_osm(Path((lng, lat)): Path<(Float, Float)>, query: Query<LookupQuery>, boundary: Query<BoundaryQuery>) -> WebResult<LookupResponse<Vec<OsmTimezoneResponse1>>> {
+    timezone_osm_v1(Path((lng, lat)), query, boundary).await
 }
 
 /// Gets time zone information from the OSM dataset.
@@ -227,20 +326,68 @@ async fn timezone_osm(Path((lng, lat)): Path<(Float, Float)>) -> WebResult<Looku
     context_path = "/api", 
     path = "/v1/osm/tz/{lng}/{lat}", 
     tag = "TZv1", 
-    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude.")), 
+    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude."), LookupQuery, BoundaryQuery), 
     responses(
         (status = 200, description = "List all found timezones successfully.", body = Vec<OsmTimezoneResponse1>),
         (status = 304, description = "Not modified."),
         (status = 404, description = "No timezone results: location likely resides on a boundary."),
+        (status = 422, description = "The coordinate is not a point on the globe (e.g., a latitude outside of `[-90, 90]`).", body = WebError),
     )
 )]
 #[instrument]
-async fn timezone_osm_v1(Path((lng, lat)): Path<(Float, Float)>) -> WebResult<LookupResponse<Vec<OsmTimezoneResponse1>>> {
-    let tzs = OsmTimezone::lookup(lng, lat).into_iter().map(|tz| tz.into()).collect::<Vec<_>>();
+async fn timezone_osm_v1(Path((lng, lat)): Path<(Float, Float)>, Query(query): Query<LookupQuery>, Query(boundary): Query<BoundaryQuery>) -> WebResult<LookupResponse<Vec<OsmTimezoneResponse1>>> {
+    let tzs = lookup_or_nearest::<OsmTimezone>(lng, lat, &query)?
+        .into_iter()
+        .map(|(tz, distance)| OsmTimezoneResponse1::from(tz).with_boundary(get_boundary(tz, lng, lat, &boundary)).with_distance(distance))
+        .collect::<Vec<_>>();
 
     Ok(LookupResponse::Ok(Json(tzs)))
 }
 
+/// Gets an OSM time zone by its stable id.
+///
+/// Returns the time zone with the given `stableId` (as returned by a lookup) from the [OpenStreetMap](https://www.openstreetmap.org/) dataset.
+///
+/// This API endpoint is provided under the same [license](https://github.com/twitchax/rtz/blob/main/LICENSE) as the
+/// [project](https://github.com/twitchax/rtz) itself.  It is provided as-is, with no warranty (as of today).
+#[utoipa::path(
+    get,
+    context_path = "/api", 
+    path = "/osm/tz/id/{stable_id}", 
+    tag = "TZ", 
+    params(("stable_id" = u64, Path, description = "The stable id of the time zone.")), 
+    responses(
+        (status = 200, description = "Found the time zone successfully.", body = OsmTimezoneResponse1),
+        (status = 404, description = "No time zone has this stable id.", body = WebError),
+    )
+)]
+#[instrument]
+async fn timezone_osm_by_id(stable_id: Path<u64>) -> WebResult<Json<OsmTimezoneResponse1>> {
+    timezone_osm_by_id_v1(stable_id).await
+}
+
+/// Gets an OSM time zone by its stable id.
+///
+/// Returns the time zone with the given `stableId` (as returned by a lookup) from the [OpenStreetMap](https://www.openstreetmap.org/) dataset.
+///
+/// This API endpoint is provided under the same [license](https://github.com/twitchax/rtz/blob/main/LICENSE) as the
+/// [project](https://github.com/twitchax/rtz) itself.  It is provided as-is, with no warranty (as of today).
+#[utoipa::path(
+    get,
+    context_path = "/api", 
+    path = "/v1/osm/tz/id/{stable_id}", 
+    tag = "TZv1", 
+    params(("stable_id" = u64, Path, description = "The stable id of the time zone.")), 
+    responses(
+        (status = 200, description = "Found the time zone successfully.", body = OsmTimezoneResponse1),
+        (status = 404, description = "No time zone has this stable id.", body = WebError),
+    )
+)]
+#[instrument]
+async fn timezone_osm_by_id_v1(Path(stable_id): Path<u64>) -> WebResult<Json<OsmTimezoneResponse1>> {
+    Ok(Json(get_by_stable_id::<OsmTimezone>(stable_id)?.into()))
+}
+
 /// Gets the admin information from the OSM dataset.
 ///
 /// Returns the admin information for the given `(lng,lat)` from the [OpenStreetMap](https://www.openstreetmap.org/) dataset.
@@ -252,16 +399,17 @@ async fn timezone_osm_v1(Path((lng, lat)): Path<(Float, Float)>) -> WebResult<Lo
     context_path = "/api", 
     path = "/osm/admin/{lng}/{lat}", 
     tag = "Admin", 
-    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude.")), 
+    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude."), LookupQuery, BoundaryQuery), 
     responses(
         (status = 200, description = "List all found administrative districts successfully.", body = Vec<OsmAdminResponse1>),
         (status = 304, description = "Not modified."),
         (status = 404, description = "No results: location likely resides on a boundary."),
+        (status = 422, description = "The coordinate is not a point on the globe (e.g., a latitude outside of `[-90, 90]`).", body = WebError),
     )
 )]
 #[instrument]
-async fn admin_osm(Path((lng, lat)): Path<(Float, Float)>) -> WebResult<LookupResponse<Vec<OsmAdminResponse1>>> {
-    admin_osm_v1(Path((lng, lat))).await
+async fn admin_osm(Path((lng, lat)): Path<(Float, Float)>, query: Query<LookupQuery>, boundary: Query<BoundaryQuery>) -> WebResult<LookupResponse<Vec<OsmAdminResponse1>>> {
+    admin_osm_v1(Path((lng, lat)), query, boundary).await
 }
 
 /// Gets the admin information from the OSM dataset.
@@ -275,16 +423,326 @@ async fn admin_osm(Path((lng, lat)): Path<(Float, Float)>) -> WebResult<LookupRe
     context_path = "/api", 
     path = "/v1/osm/admin/{lng}/{lat}", 
     tag = "Adminv1", 
-    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude.")), 
+    params(("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude."), LookupQuery, BoundaryQuery), 
     responses(
         (status = 200, description = "List all found administrative districts successfully.", body = Vec<OsmAdminResponse1>),
         (status = 304, description = "Not modified."),
         (status = 404, description = "No results: location likely resides on a boundary."),
+        (status = 422, description = "The coordinate is not a point on the globe (e.g., a latitude outside of `[-90, 90]`).", body = WebError),
     )
 )]
 #[instrument]
-async fn admin_osm_v1(Path((lng, lat)): Path<(Float, Float)>) -> WebResult<LookupResponse<Vec<OsmAdminResponse1>>> {
-    let admins = OsmAdmin::lookup(lng, lat).into_iter().map(|a| a.into()).collect::<Vec<_>>();
+async fn admin_osm_v1(Path((lng, lat)): Path<(Float, Float)>, Query(query): Query<LookupQuery>, Query(boundary): Query<BoundaryQuery>) -> WebResult<LookupResponse<Vec<OsmAdminResponse1>>> {
+    let admins = lookup_or_nearest::<OsmAdmin>(lng, lat, &query)?
+        .into_iter()
+        .map(|(a, distance)| OsmAdminResponse1::from(a).with_boundary(get_boundary(a, lng, lat, &boundary)).with_distance(distance))
+        .collect::<Vec<_>>();
 
     Ok(LookupResponse::Ok(Json(admins)))
 }
+
+/// Gets the time zones that a geometry overlaps from the OSM dataset.
+///
+/// Takes a GeoJSON `Polygon` or `MultiPolygon` (bare, or as the geometry of a `Feature`), and returns every time zone it overlaps
+/// from the [OpenStreetMap](https://www.openstreetmap.org/) dataset, along with the fraction of its area that falls in each.
+///
+/// This API endpoint is provided under the same [license](https://github.com/twitchax/rtz/blob/main/LICENSE) as the
+/// [project](https://github.com/twitchax/rtz) itself.  It is provided as-is, with no warranty (as of today).
+#[utoipa::path(
+    post,
+    context_path = "/api", 
+    path = "/osm/tz", 
+    tag = "TZ", 
+    request_body(content = String, description = "A GeoJSON `Polygon` or `MultiPolygon`, bare or as the geometry of a `Feature`.", content_type = "application/json"), 
+    responses(
+        (status = 200, description = "List all overlapped timezones successfully.", body = [CoverageResponse1<OsmTimezoneResponse1>]),
+        (status = 400, description = "The body is not a GeoJSON polygon.", body = WebError),
+    )
+)]
+#[instrument]
+async fn timezone_osm_geometry(body: String) -> WebResult<LookupResponse<Vec<CoverageResponse1<OsmTimezoneResponse1>>>> {
+    timezone_osm_geometry_v1(body).await
+}
+
+/// Gets the time zones that a geometry overlaps from the OSM dataset.
+///
+/// Takes a GeoJSON `Polygon` or `MultiPolygon` (bare, or as the geometry of a `Feature`), and returns every time zone it overlaps
+/// from the [OpenStreetMap](https://www.openstreetmap.org/) dataset, along with the fraction of its area that falls in each.
+///
+/// This API endpoint is provided under the same [license](https://github.com/twitchax/rtz/blob/main/LICENSE) as the
+/// [project](https://github.com/twitchax/rtz) itself.  It is provided as-is, with no warranty (as of today).
+#[utoipa::path(
+    post,
+    context_path = "/api", 
+    path = "/v1/osm/tz", 
+    tag = "TZv1", 
+    request_body(content = String, description = "A GeoJSON `Polygon` or `MultiPolygon`, bare or as the geometry of a `Feature`.", content_type = "application/json"), 
+    responses(
+        (status = 200, description = "List all overlapped timezones successfully.", body = [CoverageResponse1<OsmTimezoneResponse1>]),
+        (status = 400, description = "The body is not a GeoJSON polygon.", body = WebError),
+    )
+)]
+#[instrument]
+async fn timezone_osm_geometry_v1(body: String) -> WebResult<LookupResponse<Vec<CoverageResponse1<OsmTimezoneResponse1>>>> {
+    let geometry = get_geometry_from_body(&body)?;
+    let tzs = OsmTimezone::lookup_geometry(&geometry)
+        .into_iter()
+        .map(|(item, fraction)| CoverageResponse1 {
+            item: item.into(),
+            fraction: fraction as f64,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(LookupResponse::Ok(Json(tzs)))
+}
+
+/// Gets the admin areas that a geometry overlaps from the OSM dataset.
+///
+/// Takes a GeoJSON `Polygon` or `MultiPolygon` (bare, or as the geometry of a `Feature`), and returns every administrative district it overlaps
+/// from the [OpenStreetMap](https://www.openstreetmap.org/) dataset, along with the fraction of its area that falls in each.
+///
+/// This API endpoint is provided under the same [license](https://github.com/twitchax/rtz/blob/main/LICENSE) as the
+/// [project](https://github.com/twitchax/rtz) itself.  It is provided as-is, with no warranty (as of today).
+#[utoipa::path(
+    post,
+    context_path = "/api", 
+    path = "/osm/admin", 
+    tag = "Admin", 
+    request_body(content = String, description = "A GeoJSON `Polygon` or `MultiPolygon`, bare or as the geometry of a `Feature`.", content_type = "application/json"), 
+    responses(
+        (status = 200, description = "List all overlapped administrative districts successfully.", body = [CoverageResponse1<OsmAdminResponse1>]),
+        (status = 400, description = "The body is not a GeoJSON polygon.", body = WebError),
+    )
+)]
+#[instrument]
+async fn admin_osm_geometry(body: String) -> WebResult<LookupResponse<Vec<CoverageResponse1<OsmAdminResponse1>>>> {
+    admin_osm_geometry_v1(body).await
+}
+
+/// Gets the admin areas that a geometry overlaps from the OSM dataset.
+///
+/// Takes a GeoJSON `Polygon` or `MultiPolygon` (bare, or as the geometry of a `Feature`), and returns every administrative district it overlaps
+/// from the [OpenStreetMap](https://www.openstreetmap.org/) dataset, along with the fraction of its area that falls in each.
+///
+/// This API endpoint is provided under the same [license](https://github.com/twitchax/rtz/blob/main/LICENSE) as the
+/// [project](https://github.com/twitchax/rtz) itself.  It is provided as-is, with no warranty (as of today).
+#[utoipa::path(
+    post,
+    context_path = "/api", 
+    path = "/v1/osm/admin", 
+    tag = "Adminv1", 
+    request_body(content = String, description = "A GeoJSON `Polygon` or `MultiPolygon`, bare or as the geometry of a `Feature`.", content_type = "application/json"), 
+    responses(
+        (status = 200, description = "List all overlapped administrative districts successfully.", body = [CoverageResponse1<OsmAdminResponse1>]),
+        (status = 400, description = "The body is not a GeoJSON polygon.", body = WebError),
+    )
+)]
+#[instrument]
+async fn admin_osm_geometry_v1(body: String) -> WebResult<LookupResponse<Vec<CoverageResponse1<OsmAdminResponse1>>>> {
+    let geometry = get_geometry_from_body(&body)?;
+    let admins = OsmAdmin::lookup_geometry(&geometry)
+        .into_iter()
+        .map(|(item, fraction)| CoverageResponse1 {
+            item: item.into(),
+            fraction: fraction as f64,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(LookupResponse::Ok(Json(admins)))
+}
+
+/// Gets the features of a custom dataset.
+///
+/// Returns the features of the custom dataset named `layer` (see the server's `custom_layers`
+/// configuration) for the given `(lng,lat)`.
+#[utoipa::path(
+    get,
+    context_path = "/api", 
+    path = "/custom/{layer}/{lng}/{lat}", 
+    tag = "Custom", 
+    params(("layer" = String, Path, description = "The name of the custom dataset."), ("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude."), LookupQuery), 
+    responses(
+        (status = 200, description = "List all found features successfully.", body = Vec<CustomFeatureResponse1>),
+        (status = 404, description = "No custom dataset has this name.", body = WebError),
+        (status = 422, description = "The coordinate is not a point on the globe (e.g., a latitude outside of `[-90, 90]`).", body = WebError),
+    )
+)]
+#[instrument(skip(state))]
+async fn custom(state: State<AppState>, path: Path<(String, Float, Float)>, query: Query<LookupQuery>) -> WebResult<LookupResponse<Vec<CustomFeatureResponse1>>> {
+    custom_v1(state, path, query).await
+}
+
+/// Gets the features of a custom dataset.
+///
+/// Returns the features of the custom dataset named `layer` (see the server's `custom_layers`
+/// configuration) for the given `(lng,lat)`.
+#[utoipa::path(
+    get,
+    context_path = "/api", 
+    path = "/v1/custom/{layer}/{lng}/{lat}", 
+    tag = "Customv1", 
+    params(("layer" = String, Path, description = "The name of the custom dataset."), ("lng" = f32, Path, description = "The longitude."), ("lat" = f32, Path, description = "The latitude."), LookupQuery), 
+    responses(
+        (status = 200, description = "List all found features successfully.", body = Vec<CustomFeatureResponse1>),
+        (status = 404, description = "No custom dataset has this name.", body = WebError),
+        (status = 422, description = "The coordinate is not a point on the globe (e.g., a latitude outside of `[-90, 90]`).", body = WebError),
+    )
+)]
+#[instrument(skip(state))]
+async fn custom_v1(State(state): State<AppState>, Path((layer, lng, lat)): Path<(String, Float, Float)>, Query(query): Query<LookupQuery>) -> WebResult<LookupResponse<Vec<CustomFeatureResponse1>>> {
+    let index = state.layers.get(&layer).ok_or_else(|| WebError {
+        status: 404,
+        message: format!("No custom dataset is named `{}`.", layer),
+        backtrace: None,
+        code: Some("unknown_layer".to_string()),
+    })?;
+
+    let features = lookup_or_nearest_in(index, lng, lat, &query)?
+        .into_iter()
+        .map(|(feature, distance)| CustomFeatureResponse1::from(feature).with_distance(distance))
+        .collect::<Vec<_>>();
+
+    Ok(LookupResponse::Ok(Json(features)))
+}
+
+// Helpers.
+
+/// Gets an item's distance to its boundary, if the query asks for it.
+fn get_boundary<T>(item: &T, lng: Float, lat: Float, query: &BoundaryQuery) -> Option<(Float, bool)>
+where
+    T: CanPerformGeoLookup,
+{
+    query.boundary.unwrap_or(false).then(|| item.get_boundary_distance(lng, lat)).flatten()
+}
+
+/// Parses a GeoJSON body into the geometry of a coverage query.
+fn get_geometry_from_body(body: &str) -> WebResult<Geometry<Float>> {
+    let bad_request = |message: String| WebError {
+        status: 400,
+        message,
+        backtrace: None,
+        code: None,
+    };
+
+    let geometry = match body.parse::<GeoJson>().map_err(|e| bad_request(format!("Invalid GeoJSON: {}", e)))? {
+        GeoJson::Geometry(geometry) => geometry,
+        GeoJson::Feature(geojson::Feature { geometry: Some(geometry), .. }) => geometry,
+        _ => return Err(bad_request("Expected a GeoJSON geometry, or a feature with one.".to_string())),
+    };
+
+    match Geometry::<Float>::try_from(geometry.value) {
+        Ok(geometry @ (Geometry::Polygon(_) | Geometry::MultiPolygon(_))) => Ok(geometry),
+        _ => Err(bad_request("Expected a GeoJSON `Polygon` or `MultiPolygon`.".to_string())),
+    }
+}
+
+/// Loads the datasets whose bincodes are in `data_path` as their global indexes, in place of the embedded ones.
+fn load_data(data_path: &std::path::Path) -> Void {
+    use rtz_core::geo::{admin::osm as admin_osm, tz::ned as tz_ned, tz::osm as tz_osm};
+
+    load_dataset::<NedTimezone>(data_path, tz_ned::TIMEZONE_BINCODE_DESTINATION_NAME, tz_ned::LOOKUP_BINCODE_DESTINATION_NAME)?;
+    load_dataset::<OsmTimezone>(data_path, tz_osm::TIMEZONE_BINCODE_DESTINATION_NAME, tz_osm::LOOKUP_BINCODE_DESTINATION_NAME)?;
+    load_dataset::<OsmAdmin>(data_path, admin_osm::ADMIN_BINCODE_DESTINATION_NAME, admin_osm::LOOKUP_BINCODE_DESTINATION_NAME)?;
+
+    Ok(())
+}
+
+/// Indexes the custom datasets of the config, by name.
+fn load_custom_layers(layers: &HashMap<String, CustomLayerConfig>) -> Res<HashMap<String, GeoIndex<'static, CustomFeature>>> {
+    layers
+        .iter()
+        .map(|(name, layer)| {
+            let index = match (&layer.geojson, &layer.items, &layer.lookup) {
+                (Some(geojson), None, None) => {
+                    let (index, report) = GeoIndex::from_geojson_file(geojson, &layer.mapping)?;
+
+                    if let Some(max_problems) = layer.max_problems.filter(|&max_problems| report.problem_count() > max_problems) {
+                        return Err(anyhow::Error::msg(format!(
+                            "The custom layer `{}` has {}, which is more than the {} allowed.",
+                            name, report, max_problems
+                        )));
+                    }
+
+                    if report.problem_count() > 0 {
+                        log::warn!("The custom layer `{}` has {}.", name, report);
+
+                        for diagnostic in &report.skipped {
+                            log::warn!("The custom layer `{}` skipped a feature.  {}", name, diagnostic);
+                        }
+                    }
+
+                    index
+                }
+                (None, Some(items), Some(lookup)) => GeoIndex::from_files(items, lookup)?,
+                _ => {
+                    return Err(anyhow::Error::msg(format!(
+                        "The custom layer `{}` needs either a `geojson` file, or `items` and `lookup` bincodes.",
+                        name
+                    )))
+                }
+            };
+
+            log::info!("Indexed the custom layer `{}` ({} features).", name, index.get_items().len());
+
+            Ok((name.clone(), index))
+        })
+        .collect()
+}
+
+/// Loads a dataset's bincodes from `data_path` as its global index, if they are both there.
+fn load_dataset<T>(data_path: &std::path::Path, items_name: &str, lookup_name: &str) -> Void
+where
+    T: HasGeoIndex + HasGeometry,
+    ConcreteVec<T>: bincode::Decode<()> + bincode::BorrowDecode<'static, ()>,
+{
+    let (items, lookup) = (data_path.join(items_name), data_path.join(lookup_name));
+
+    if !items.exists() || !lookup.exists() {
+        log::info!("No `{}` in `{}`: using the embedded data.", items_name, data_path.display());
+        return Ok(());
+    }
+
+    let index = GeoIndex::from_files(&items, &lookup)?;
+    T::set_mem_index(index).map_err(|_| anyhow::Error::msg(format!("The data for `{}` was already loaded.", items_name)))?;
+
+    log::info!("Loaded `{}` and `{}`.", items.display(), lookup.display());
+
+    Ok(())
+}
+
+/// Performs a lookup, falling back to the nearest result when the query asks for one and the point resolves to nothing.
+///
+/// Each result comes with its distance (in meters) when it is the nearest one, and `None` when it contains the point.
+/// A coordinate that is not a point on the globe is an error (a `422`), rather than an empty result.
+fn lookup_or_nearest<T>(lng: Float, lat: Float, query: &LookupQuery) -> WebResult<Vec<(&'static T, Option<Float>)>>
+where
+    T: CanPerformGeoLookup,
+{
+    lookup_or_nearest_in(T::get_mem_index(), lng, lat, query)
+}
+
+/// Performs a lookup over an index, falling back to the nearest result (see [`lookup_or_nearest`]).
+fn lookup_or_nearest_in<'a, T>(index: &'a GeoIndex<'_, T>, lng: Float, lat: Float, query: &LookupQuery) -> WebResult<Vec<(&'a T, Option<Float>)>>
+where
+    T: HasGeometry,
+{
+    let results = index.try_lookup(lng, lat)?;
+
+    Ok(match query.nearest {
+        Some(max_distance) if results.is_empty() => index.lookup_nearest(lng, lat, max_distance).map(|(item, distance)| (item, Some(distance))).into_iter().collect(),
+        _ => results.into_iter().map(|item| (item, None)).collect(),
+    })
+}
+
+/// Gets a time zone by its stable id, or a `404` when no time zone has it.
+fn get_by_stable_id<T>(stable_id: u64) -> WebResult<&'static T>
+where
+    T: CanGetByStableId,
+{
+    T::get_by_stable_id(stable_id).ok_or_else(|| WebError {
+        status: 404,
+        message: format!("No time zone has the stable id `{}`.", stable_id),
+        backtrace: None,
+        code: Some("unknown_stable_id".to_string()),
+    })
+}
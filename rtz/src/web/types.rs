@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fmt::Display,
     sync::{Arc, OnceLock},
 };
@@ -11,8 +12,14 @@ use axum::{
 use axum_insights::AppInsightsError;
 use chrono::{DateTime, Utc};
 use hyper::{header, StatusCode};
+use rtz_core::{
+    base::types::{CoordinateError, Float},
+    geo::custom::CustomFeature,
+};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::GeoIndex;
 
 use super::config::Config;
 
@@ -35,6 +42,8 @@ pub fn get_last_modified_time() -> &'static str {
 pub struct AppState {
     #[allow(dead_code)]
     pub config: Arc<Config>,
+    /// The indexed custom datasets (see [`Config`]), by name, which live for the rest of the program.
+    pub layers: &'static HashMap<String, GeoIndex<'static, CustomFeature>>,
 }
 
 // Helper types.
@@ -64,6 +73,24 @@ impl IfModifiedSince {
     }
 }
 
+/// The query parameters shared by the point lookup endpoints.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LookupQuery {
+    /// When a point resolves to nothing (e.g., it lies on a boundary), fall back to the nearest
+    /// result whose boundary is within this many meters, with that distance as its `distance`.
+    pub nearest: Option<Float>,
+}
+
+/// The query parameters of the OSM point lookup endpoints.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BoundaryQuery {
+    /// Whether to include each result's distance to its nearest boundary, and whether the point is
+    /// near enough to it that build-time simplification may have put it on the wrong side.
+    pub boundary: Option<bool>,
+}
+
 // Web types.
 
 /// A simple web result with a custom error string.
@@ -80,6 +107,9 @@ pub struct WebError {
     pub status: u16,
     pub message: String,
     pub backtrace: Option<String>,
+    /// A stable, machine-readable code for the error, when there is one (e.g., `latitude_out_of_range`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 impl Default for WebError {
@@ -88,6 +118,18 @@ impl Default for WebError {
             status: 0,
             message: "An unknown error occurred.".to_string(),
             backtrace: None,
+            code: None,
+        }
+    }
+}
+
+impl From<CoordinateError> for WebError {
+    fn from(value: CoordinateError) -> Self {
+        WebError {
+            status: 422,
+            message: value.to_string(),
+            backtrace: None,
+            code: Some(value.code().to_string()),
         }
     }
 }
@@ -126,13 +168,13 @@ mod tests {
 
     #[test]
     fn web_error_display_is_the_message() {
-        let e = WebError { status: 400, message: "boom".to_string(), backtrace: None };
+        let e = WebError { status: 400, message: "boom".to_string(), backtrace: None, code: None };
         assert_eq!(e.to_string(), "boom");
     }
 
     #[test]
     fn web_error_into_response_uses_its_status() {
-        let e = WebError { status: 404, message: "nope".to_string(), backtrace: None };
+        let e = WebError { status: 404, message: "nope".to_string(), backtrace: None, code: None };
         let response = e.into_response();
         assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
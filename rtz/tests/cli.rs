@@ -27,6 +27,14 @@ fn malformed_lng_lat_exits_nonzero() {
     assert!(!output.status.success());
 }
 
+#[test]
+fn out_of_range_latitude_exits_nonzero() {
+    let output = Command::new(BIN).args(["ned", "tz", "--", "-121,95"]).output().unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("latitude"), "stderr was: {stderr}");
+}
+
 #[test]
 fn version_flag_prints_the_crate_version() {
     let output = Command::new(BIN).arg("--version").output().unwrap();
@@ -34,3 +42,27 @@ fn version_flag_prints_the_crate_version() {
     let stdout = String::from_utf8_lossy(&output.stdout);
     assert!(stdout.contains(env!("CARGO_PKG_VERSION")), "stdout was: {stdout}");
 }
+
+#[test]
+fn ned_tz_falls_back_to_the_nearest_zone() {
+    // This point lies on a boundary, so it resolves to nothing without a fallback.
+    let output = Command::new(BIN).args(["ned", "tz", "--nearest", "1000", "179.9968,-67.0959"]).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Distance:"), "stdout was: {stdout}");
+}
+
+#[test]
+fn osm_route_lists_zones_in_order() {
+    let path = std::env::temp_dir().join(format!("rtz-route-{}.geojson", std::process::id()));
+    std::fs::write(&path, r#"{"type":"LineString","coordinates":[[-118,34],[-112,33.5]]}"#).unwrap();
+
+    let output = Command::new(BIN).args(["osm", "route", path.to_str().unwrap()]).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let los_angeles = stdout.find("America/Los_Angeles").unwrap_or_else(|| panic!("stdout was: {stdout}"));
+    let phoenix = stdout.find("America/Phoenix").unwrap_or_else(|| panic!("stdout was: {stdout}"));
+    assert!(los_angeles < phoenix, "stdout was: {stdout}");
+}
@@ -0,0 +1,78 @@
+//! Checks, with a counting global allocator, that dropping an index frees everything it allocated,
+//! whether it was built in memory or borrow-decoded over (leaked) asset bytes.
+//!
+//! This is its own test binary, with a single test, so that nothing else allocates while it counts.
+#![cfg(all(feature = "tz-ned", feature = "self-contained"))]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicIsize, Ordering},
+};
+
+use rtz_core::geo::{
+    shared::{get_geojson_features_from_string, get_items_from_features},
+    tz::ned::{NedTimezone, LOOKUP_BINCODE_DESTINATION_NAME, TIMEZONE_BINCODE_DESTINATION_NAME},
+};
+use rtzlib::GeoIndex;
+
+const FIXTURE: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../test/ne_10m_time_zones.test.geojson"));
+
+/// The number of bytes currently allocated.
+static ALLOCATED: AtomicIsize = AtomicIsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size() as isize, Ordering::SeqCst);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size() as isize, Ordering::SeqCst);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Runs `f` twice (the first time to warm up anything that lives for the rest of the program, like
+/// rayon's thread pool), and returns how many bytes the second run left allocated.
+fn get_retained_bytes(f: impl Fn()) -> isize {
+    f();
+
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    f();
+
+    ALLOCATED.load(Ordering::SeqCst) - before
+}
+
+/// Reads an asset into 8-byte aligned memory that lives for the rest of the program, so that it
+/// is borrowed, rather than copied, by the decode.
+fn read_asset(name: &str) -> &'static [u8] {
+    let bytes = std::fs::read(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets").join(name)).unwrap();
+    let words = Vec::leak(vec![0u64; bytes.len().div_ceil(8)]);
+
+    // SAFETY: The words span at least `bytes.len()` bytes, and live for the rest of the program.
+    let data = unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, bytes.len()) };
+    data.copy_from_slice(&bytes);
+
+    data
+}
+
+#[test]
+fn dropping_an_index_frees_its_memory() {
+    let built = get_retained_bytes(|| {
+        let index = GeoIndex::from_items(get_items_from_features::<NedTimezone>(get_geojson_features_from_string(FIXTURE)));
+        assert_eq!(index.get_items().len(), 3);
+    });
+    assert_eq!(built, 0, "an index built in memory retained {built} bytes");
+
+    let (items, lookup) = (read_asset(TIMEZONE_BINCODE_DESTINATION_NAME), read_asset(LOOKUP_BINCODE_DESTINATION_NAME));
+    let decoded = get_retained_bytes(|| {
+        let index = GeoIndex::<NedTimezone>::from_bytes(items, lookup).unwrap();
+        assert!(!index.lookup(-121.0, 46.0).is_empty());
+    });
+    assert_eq!(decoded, 0, "a decoded index retained {decoded} bytes");
+}
@@ -6,9 +6,10 @@
 //! progress / warning messages. See each subcommand's `--help` text for
 //! details, and the top-level `update` subcommand for the full pipeline.
 //!
-//! The one exception is `resort-admins`, which calls into `rtz-core` directly
-//! rather than shelling out — it rewrites the committed bincodes in place, and
-//! the encode/decode logic that knows their layout lives there.
+//! The exceptions are `resort-admins` and `rebuild-lookups`, which call into
+//! `rtz-core` directly rather than shelling out — they rewrite the committed
+//! bincodes in place, and the encode/decode logic that knows their layout
+//! lives there.
 
 use std::{
     env,
@@ -93,6 +94,13 @@ enum Cmd {
     /// `regen` applies the same order to anything it produces.
     ResortAdmins,
 
+    /// Rebuild every lookup cache in `rtz/assets/` from its items bincode,
+    /// without needing any source data.
+    ///
+    /// Use this after a change to the lookup format; datasets whose items
+    /// bincode is absent are skipped with a warning.
+    RebuildLookups,
+
     /// Remove the `.rtz-data/` scratch directory (downloaded PBF + extracted
     /// admin GeoJSON) to reclaim disk space.
     Clean,
@@ -109,6 +117,7 @@ fn main() -> Result<()> {
         Cmd::Verify => verify(&repo_root),
         Cmd::Update { pbf, admin_out, admin_dirs } => update(&repo_root, pbf, &admin_out, admin_dirs),
         Cmd::ResortAdmins => resort_admins(&repo_root),
+        Cmd::RebuildLookups => rebuild_lookups(&repo_root),
         Cmd::Clean => clean(&repo_root),
     }
 }
@@ -323,6 +332,40 @@ fn resort_admins(repo_root: &Path) -> Result<()> {
     Ok(())
 }
 
+/// `rebuild-lookups`: regenerates each dataset's lookup cache from its
+/// committed items bincode.
+fn rebuild_lookups(repo_root: &Path) -> Result<()> {
+    use rtz_core::geo::{admin, tz};
+
+    let assets = repo_root.join("rtz").join("assets");
+
+    rebuild_lookup::<tz::ned::NedTimezone>(&assets, tz::ned::TIMEZONE_BINCODE_DESTINATION_NAME, tz::ned::LOOKUP_BINCODE_DESTINATION_NAME);
+    rebuild_lookup::<tz::osm::OsmTimezone>(&assets, tz::osm::TIMEZONE_BINCODE_DESTINATION_NAME, tz::osm::LOOKUP_BINCODE_DESTINATION_NAME);
+    rebuild_lookup::<admin::osm::OsmAdmin>(&assets, admin::osm::ADMIN_BINCODE_DESTINATION_NAME, admin::osm::LOOKUP_BINCODE_DESTINATION_NAME);
+
+    println!("done. re-run `cargo xtask verify` to confirm the assets still decode.");
+
+    Ok(())
+}
+
+/// Rebuilds one dataset's lookup cache, or warns and skips it if its items
+/// bincode is absent.
+fn rebuild_lookup<T>(assets: &Path, items_name: &str, lookup_name: &str)
+where
+    T: rtz_core::geo::shared::HasGeometry + bincode::Decode<()> + Send + Sync + 'static,
+{
+    let items = assets.join(items_name);
+    let lookup = assets.join(lookup_name);
+
+    if !items.exists() {
+        println!("warning: {} does not exist — skipping {}.", items.display(), lookup.display());
+        return;
+    }
+
+    println!("rebuilding {} from {} ...", lookup.display(), items.display());
+    rtz_core::geo::shared::generate_lookup_bincode::<T>(&items, &lookup);
+}
+
 fn clean(repo_root: &Path) -> Result<()> {
     let scratch = repo_root.join(".rtz-data");
 